@@ -0,0 +1,322 @@
+//! Readable hints for the arguments of common syscalls, shown under the full
+//! line in the timeline's expanded view.
+//!
+//! strace already prints arguments symbolically, but it takes some practice
+//! to read `mmap(NULL, 2101248, PROT_READ|PROT_EXEC, MAP_PRIVATE|MAP_DENYWRITE,
+//! 3, 0)` at a glance. For the calls below the interesting arguments are
+//! picked out and labelled (size 2.0M, protection read + execute, fd 3);
+//! other calls get no hints and the raw line speaks for itself.
+
+use crate::format_bytes;
+use crate::parser;
+
+/// Labelled hints for the arguments and result of the call on `line`, or
+/// nothing if the syscall is not one of the decoded ones.
+pub(crate) fn decode(line: &str) -> Vec<(&'static str, String)> {
+    let Some(call) = parser::parse_syscall(line) else {
+        return Vec::new();
+    };
+    let (_, text) = parser::split_prefix(line);
+    let Some(args) = text
+        .strip_prefix(call.name)
+        .and_then(|rest| rest.strip_prefix('('))
+    else {
+        return Vec::new();
+    };
+    let (args, result) = split_call(args);
+    let arg = |i: usize| args.get(i).copied().unwrap_or("");
+    let mut hints = Vec::new();
+    match call.name {
+        "open" | "creat" => {
+            hints.push(("path", unquote(arg(0))));
+            if call.name == "open" {
+                hints.push(("flags", describe_flags(arg(1), OPEN_FLAGS)));
+            }
+        }
+        "openat" | "openat2" => {
+            hints.push(("relative to", directory(arg(0))));
+            hints.push(("path", unquote(arg(1))));
+            if call.name == "openat" {
+                hints.push(("flags", describe_flags(arg(2), OPEN_FLAGS)));
+            }
+        }
+        "read" | "write" | "pread64" | "pwrite64" | "readv" | "writev" => {
+            hints.push(("fd", arg(0).to_string()));
+            let requested = if call.name.ends_with('v') {
+                // The vector's length, not its byte count.
+                match arg(2) {
+                    "1" => "1 buffer".to_string(),
+                    count => format!("{} buffers", count),
+                }
+            } else {
+                bytes(arg(2))
+            };
+            hints.push(("requested", requested));
+            if let Some(done) = result.and_then(|r| r.parse::<u64>().ok()) {
+                hints.push(("transferred", format_bytes(done)));
+            }
+        }
+        "close" | "fsync" | "fstat" | "ioctl" | "getdents64" => {
+            hints.push(("fd", arg(0).to_string()));
+            if call.name == "ioctl" {
+                hints.push(("request", arg(1).to_string()));
+            }
+        }
+        "stat" | "lstat" | "access" | "unlink" | "chdir" | "mkdir" | "readlink" => {
+            hints.push(("path", unquote(arg(0))));
+        }
+        "newfstatat" | "statx" | "faccessat" | "faccessat2" | "unlinkat" | "mkdirat"
+        | "readlinkat" => {
+            hints.push(("relative to", directory(arg(0))));
+            hints.push(("path", unquote(arg(1))));
+        }
+        "execve" => {
+            hints.push(("program", unquote(arg(0))));
+            hints.push(("arguments", arg(1).to_string()));
+        }
+        "socket" => {
+            hints.push(("domain", arg(0).to_string()));
+            hints.push(("type", arg(1).to_string()));
+        }
+        "connect" | "bind" => {
+            hints.push(("fd", arg(0).to_string()));
+            hints.push(("address", address(arg(1))));
+        }
+        "accept" | "accept4" => {
+            hints.push(("listening fd", arg(0).to_string()));
+            if arg(1) != "NULL" {
+                hints.push(("peer", address(arg(1))));
+            }
+        }
+        "sendto" | "recvfrom" => {
+            hints.push(("fd", arg(0).to_string()));
+            hints.push(("requested", bytes(arg(2))));
+            if !matches!(arg(4), "" | "NULL") {
+                hints.push(("address", address(arg(4))));
+            }
+        }
+        "mmap" => {
+            hints.push(("size", bytes(arg(1))));
+            hints.push(("protection", describe_flags(arg(2), PROT_FLAGS)));
+            hints.push(("flags", describe_flags(arg(3), MAP_FLAGS)));
+            let fd = arg(4);
+            hints.push((
+                "backing",
+                if fd == "-1" {
+                    "anonymous memory".to_string()
+                } else {
+                    format!("fd {} at offset {}", fd, arg(5))
+                },
+            ));
+        }
+        "munmap" => {
+            hints.push(("address", arg(0).to_string()));
+            hints.push(("size", bytes(arg(1))));
+        }
+        "mprotect" => {
+            hints.push(("address", arg(0).to_string()));
+            hints.push(("size", bytes(arg(1))));
+            hints.push(("protection", describe_flags(arg(2), PROT_FLAGS)));
+        }
+        "kill" | "tgkill" => {
+            let (pid, signal) = if call.name == "kill" {
+                (arg(0), arg(1))
+            } else {
+                (arg(1), arg(2))
+            };
+            hints.push(("target", format!("PID {}", pid)));
+            hints.push(("signal", signal.to_string()));
+        }
+        "clone" | "clone3" => {
+            if let Some(flags) = field(text, "flags=") {
+                hints.push(("flags", flags.to_string()));
+            }
+        }
+        _ => return Vec::new(),
+    }
+    if let Some(errno) = parser::errno(line) {
+        hints.push(("failed with", errno.to_string()));
+    } else if let Some(fd) = result.filter(|_| RETURNS_NEW_FD.contains(&call.name)) {
+        hints.push(("new fd", fd.to_string()));
+    }
+    hints
+}
+
+/// Calls whose result, when they succeed, is the descriptor they opened.
+const RETURNS_NEW_FD: &[&str] = &[
+    "open", "creat", "openat", "openat2", "socket", "accept", "accept4",
+];
+
+/// Meanings of the `open` flags worth spelling out.
+const OPEN_FLAGS: &[(&str, &str)] = &[
+    ("O_RDONLY", "read only"),
+    ("O_WRONLY", "write only"),
+    ("O_RDWR", "read and write"),
+    ("O_CREAT", "create if missing"),
+    ("O_EXCL", "must not exist"),
+    ("O_TRUNC", "truncate"),
+    ("O_APPEND", "append"),
+    ("O_NONBLOCK", "non-blocking"),
+    ("O_DIRECTORY", "must be a directory"),
+    ("O_NOFOLLOW", "do not follow symlinks"),
+    ("O_CLOEXEC", "close on exec"),
+    ("O_PATH", "path only"),
+    ("O_TMPFILE", "unnamed temporary file"),
+];
+
+/// Meanings of the memory protection flags.
+const PROT_FLAGS: &[(&str, &str)] = &[
+    ("PROT_NONE", "no access"),
+    ("PROT_READ", "read"),
+    ("PROT_WRITE", "write"),
+    ("PROT_EXEC", "execute"),
+];
+
+/// Meanings of the common `mmap` flags.
+const MAP_FLAGS: &[(&str, &str)] = &[
+    ("MAP_SHARED", "shared"),
+    ("MAP_PRIVATE", "private copy"),
+    ("MAP_FIXED", "at a fixed address"),
+    ("MAP_FIXED_NOREPLACE", "at a fixed address"),
+    ("MAP_ANONYMOUS", "anonymous"),
+    ("MAP_DENYWRITE", "deny writes"),
+    ("MAP_STACK", "stack"),
+    ("MAP_NORESERVE", "no swap reserved"),
+    ("MAP_POPULATE", "prefaulted"),
+];
+
+/// Splits the text after a call's `(` into its top-level arguments and the
+/// result after `) = `, if the line has one. Commas inside strings, arrays
+/// and structures do not split.
+fn split_call(text: &str) -> (Vec<&str>, Option<&str>) {
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if quoted {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => quoted = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => quoted = true,
+            '(' | '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            ')' if depth > 0 => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(text[start..i].trim());
+                start = i + 1;
+            }
+            ')' => {
+                args.push(text[start..i].trim());
+                let result = text[i + 1..]
+                    .trim_start()
+                    .strip_prefix("= ")
+                    .and_then(|r| r.split_whitespace().next());
+                return (args, result);
+            }
+            _ => {}
+        }
+    }
+    // An unfinished call: the arguments strace printed before stopping.
+    let rest = text[start..].trim_end();
+    let rest = rest.strip_suffix("<unfinished ...>").unwrap_or(rest).trim();
+    if !rest.is_empty() {
+        args.push(rest);
+    }
+    (args, None)
+}
+
+/// Removes the quotes (and the `...` strace adds to cut-off strings) from a
+/// string argument.
+fn unquote(arg: &str) -> String {
+    let arg = arg.strip_suffix("...").unwrap_or(arg);
+    arg.strip_prefix('"')
+        .and_then(|a| a.strip_suffix('"'))
+        .unwrap_or(arg)
+        .to_string()
+}
+
+/// Describes the directory fd of an `*at` call.
+fn directory(arg: &str) -> String {
+    if arg == "AT_FDCWD" {
+        "current directory".to_string()
+    } else {
+        format!("directory fd {}", arg)
+    }
+}
+
+/// Formats a byte count argument, keeping the raw text if it is not a number.
+fn bytes(arg: &str) -> String {
+    let value = match arg.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => arg.parse().ok(),
+    };
+    value.map_or_else(|| arg.to_string(), format_bytes)
+}
+
+/// Spells out a `A|B|C` flags argument with the meanings in `table`, keeping
+/// unknown flags as they are.
+fn describe_flags(arg: &str, table: &[(&str, &str)]) -> String {
+    let words: Vec<&str> = arg
+        .split('|')
+        .map(str::trim)
+        .map(|flag| {
+            table
+                .iter()
+                .find(|(name, _)| *name == flag)
+                .map_or(flag, |(_, meaning)| *meaning)
+        })
+        .collect();
+    let mut unique: Vec<&str> = Vec::new();
+    for word in words {
+        if !unique.contains(&word) {
+            unique.push(word);
+        }
+    }
+    unique.join(", ")
+}
+
+/// Extracts the value after `key` in a structure, up to the next `,` or `}`.
+fn field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let start = text.find(key)? + key.len();
+    let rest = &text[start..];
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
+
+/// Turns a decoded `struct sockaddr` into `1.2.3.4:80`, `[::1]:443` or a
+/// socket path; other families are shown as strace printed them.
+fn address(arg: &str) -> String {
+    let port = field(arg, "port=htons(").map(|p| p.trim_end_matches(')'));
+    let quoted_after = |key: &str| {
+        let start = arg.find(key)? + key.len();
+        let rest = &arg[start..];
+        let end = rest.find('"')?;
+        Some(&rest[..end])
+    };
+    match field(arg, "sa_family=") {
+        Some("AF_INET") => match (quoted_after("inet_addr(\""), port) {
+            (Some(ip), Some(port)) => format!("{}:{}", ip, port),
+            _ => arg.to_string(),
+        },
+        Some("AF_INET6") => match (quoted_after("AF_INET6, \""), port) {
+            (Some(ip), Some(port)) => format!("[{}]:{}", ip, port),
+            _ => arg.to_string(),
+        },
+        Some("AF_UNIX") => match quoted_after("sun_path=\"") {
+            Some(path) => format!("unix socket {}", path),
+            None => match quoted_after("sun_path=@\"") {
+                Some(name) => format!("abstract unix socket @{}", name),
+                None => arg.to_string(),
+            },
+        },
+        _ => arg.to_string(),
+    }
+}
@@ -0,0 +1,251 @@
+//! Non-interactive mode: traces a process or reads a log without opening the
+//! TUI, writing either a JSON line per syscall or a summary table at the end.
+//!
+//! JSON lines (`--json`) look like
+//!
+//! ```text
+//! {"pid":1234,"name":"openat","errno":"ENOENT","timestamp":"12:00:00.123456"}
+//! ```
+//!
+//! where `errno` is `null` for calls that succeeded. Calls strace splits into
+//! an unfinished and a resumed half are written once, when they complete.
+//! Output is flushed after every line so consumers see calls as they happen.
+//!
+//! The summary (`--summary`) is an aligned table of counts per syscall,
+//! printed when the trace ends (the process exits or Ctrl+C is pressed) or
+//! the log is exhausted. It is colored only when stdout is a terminal, so
+//! piping it into a file or another tool yields plain text.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
+
+use crate::parser::{self, CallKind};
+use crate::replay;
+use crate::syscalls::{self, Category};
+use crate::{tracers, TraceOptions, TraceTarget, TERMINATE};
+
+/// Where batch mode reads strace output from.
+pub(crate) enum Source {
+    /// A running process, traced with the default options.
+    Attach(i32),
+    /// A captured log file, or standard input given no path.
+    Log(Option<PathBuf>),
+}
+
+/// What batch mode writes to stdout.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Output {
+    /// A JSON line per completed syscall, as it happens.
+    Json,
+    /// A table of per-syscall counts once the trace ends.
+    Summary,
+}
+
+/// Calls and failures seen for one syscall.
+#[derive(Default)]
+struct Tally {
+    calls: u64,
+    errors: u64,
+}
+
+/// Runs batch mode until the trace or log ends, or until stdout is closed.
+///
+/// A termination signal stops strace, which ends the trace as if the
+/// process had exited, so the summary is still printed.
+///
+/// No strace is started once `max_tracers` are already running.
+pub(crate) fn run(source: Source, output: Output, max_tracers: usize) -> io::Result<()> {
+    match source {
+        Source::Attach(pid) => {
+            tracers::check_limit(max_tracers)?;
+            let target = TraceTarget::Attach(pid);
+            let command = TraceOptions::default().command(&target, None);
+            let mut child = Command::new(&command[0])
+                .args(&command[1..])
+                .stderr(Stdio::piped())
+                .spawn()?;
+            // strace is stopped below either way; an unrecorded one is only
+            // missing from the orphan checks.
+            if let Err(err) = tracers::register(child.id()) {
+                eprintln!("Failed to record strace PID {}: {}", child.id(), err);
+            }
+            let strace = Pid::from_raw(child.id() as i32);
+            thread::spawn(move || {
+                while !TERMINATE.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(100));
+                }
+                let _ = signal::kill(strace, Signal::SIGTERM);
+            });
+            let stderr = child.stderr.take().expect("Failed to capture stderr");
+            let result = stream(BufReader::new(stderr), Some(pid), output);
+            // Stops strace if we stopped first because stdout was closed.
+            let _ = signal::kill(strace, Signal::SIGTERM);
+            let _ = child.wait();
+            let _ = tracers::unregister(child.id());
+            result
+        }
+        Source::Log(Some(path)) => stream(BufReader::new(File::open(path)?), None, output),
+        Source::Log(None) => stream(io::stdin().lock(), None, output),
+    }
+}
+
+/// Reads syscall lines from `input`, writing JSON lines as they arrive or the
+/// summary table at the end.
+///
+/// Lines without a `[pid N]` tag are attributed to `default_pid`. strace's
+/// own messages are passed on to stderr so failures to attach stay visible.
+fn stream(input: impl BufRead, default_pid: Option<i32>, output: Output) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut tallies: HashMap<String, Tally> = HashMap::new();
+    for (_, line) in replay::entries(parser::lossy_lines(input)) {
+        if let Some(message) = line.strip_prefix("strace: ") {
+            eprintln!("strace: {}", message);
+            continue;
+        }
+        let Some(call) = parser::parse_syscall(&line) else {
+            continue;
+        };
+        if call.kind == CallKind::Unfinished {
+            continue;
+        }
+        let errno = parser::errno(&line);
+        if output == Output::Summary {
+            let tally = tallies.entry(call.name.to_string()).or_default();
+            tally.calls += 1;
+            tally.errors += errno.is_some() as u64;
+            continue;
+        }
+        let stamp = parser::timestamp(&line).map(|(range, _)| &line[range]);
+        let result = writeln!(
+            stdout,
+            "{{\"pid\":{},\"name\":{},\"errno\":{},\"timestamp\":{}}}",
+            call.pid
+                .or(default_pid)
+                .map_or_else(|| "null".to_string(), |pid| pid.to_string()),
+            json_string(call.name),
+            errno.map_or_else(|| "null".to_string(), json_string),
+            stamp.map_or_else(|| "null".to_string(), json_string),
+        )
+        .and_then(|()| stdout.flush());
+        if !keep_writing(result)? {
+            return Ok(());
+        }
+    }
+    if output == Output::Summary {
+        let color = io::stdout().is_terminal();
+        keep_writing(write_summary(&mut stdout, &tallies, color))?;
+    }
+    Ok(())
+}
+
+/// Whether to go on after a write: a closed stdout (the consumer, e.g. `head`,
+/// went away) ends the output quietly; other errors are returned.
+fn keep_writing(result: io::Result<()>) -> io::Result<bool> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Writes the per-syscall table, most called first, with the syscall names
+/// colored by category and failures in red when `color` is set.
+fn write_summary(
+    out: &mut impl Write,
+    tallies: &HashMap<String, Tally>,
+    color: bool,
+) -> io::Result<()> {
+    let mut rows: Vec<(&String, &Tally)> = tallies.iter().collect();
+    rows.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then_with(|| a.0.cmp(b.0)));
+    let name_width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    let paint = |text: String, code: &str| {
+        if color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text
+        }
+    };
+    let header = format!(
+        "{:<w$}  {:>10}  {:>8}  CATEGORY",
+        "SYSCALL",
+        "CALLS",
+        "ERRORS",
+        w = name_width
+    );
+    writeln!(out, "{}", paint(header, "1"))?;
+    for (name, tally) in &rows {
+        let category = syscalls::category(name);
+        let errors = format!("{:>8}", tally.errors);
+        writeln!(
+            out,
+            "{}  {:>10}  {}  {}",
+            paint(
+                format!("{:<w$}", name, w = name_width),
+                ansi_color(category)
+            ),
+            tally.calls,
+            if tally.errors > 0 {
+                paint(errors, "31")
+            } else {
+                errors
+            },
+            category.label()
+        )?;
+    }
+    let calls: u64 = rows.iter().map(|(_, tally)| tally.calls).sum();
+    let errors: u64 = rows.iter().map(|(_, tally)| tally.errors).sum();
+    let total = format!(
+        "{:<w$}  {:>10}  {:>8}",
+        "total",
+        calls,
+        errors,
+        w = name_width
+    );
+    writeln!(out, "{}", paint(total, "1"))?;
+    out.flush()
+}
+
+/// The ANSI foreground code matching the TUI's color for `category`.
+fn ansi_color(category: Category) -> &'static str {
+    match category {
+        Category::File => "36",
+        Category::Network => "32",
+        Category::Memory => "33",
+        Category::Process => "35",
+        Category::Signal => "31",
+        Category::Ipc => "94",
+        Category::Time => "93",
+        Category::Other => "37",
+    }
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,79 @@
+//! The eBPF backend: syscall counts gathered in the kernel instead of by
+//! strace.
+//!
+//! strace stops the traced process twice for every syscall, which can slow a
+//! busy program down many times over. Here the counting happens in eBPF
+//! programs on the `syscalls` tracepoints and only the totals come back,
+//! twice a second, so the target runs at close to full speed. The programs
+//! are compiled and loaded by `bpftrace`, which must be installed, and
+//! loading them needs `CAP_BPF` (or `CAP_SYS_ADMIN` on older kernels).
+//!
+//! Only counts and failures are collected: there are no raw lines, arguments,
+//! durations or stacks, and children the process forks are not followed
+//! (its threads are, since they share its PID).
+//!
+//! The backend is only built with the `bpf` cargo feature.
+
+use crate::capabilities::Capabilities;
+use crate::counting::{Counter, Output};
+use crate::{installed, procfs};
+
+/// The program binary.
+const BPFTRACE: &str = "bpftrace";
+
+/// The eBPF backend, as the session drives it.
+pub(crate) struct Bpftrace;
+
+impl Counter for Bpftrace {
+    fn name(&self) -> &'static str {
+        "eBPF"
+    }
+
+    fn unavailable(&self) -> Option<String> {
+        if !installed(BPFTRACE) {
+            return Some("bpftrace is not installed".to_string());
+        }
+        let allowed = procfs::capabilities(std::process::id() as i32)
+            .map(|(effective, _)| Capabilities {
+                effective,
+                permitted: 0,
+            })
+            .is_ok_and(|caps| caps.holds("CAP_BPF") || caps.holds("CAP_SYS_ADMIN"));
+        if !allowed {
+            return Some("loading eBPF programs needs CAP_BPF (run as root)".to_string());
+        }
+        None
+    }
+
+    fn command(&self, pid: i32) -> Vec<String> {
+        let program = format!(
+            "tracepoint:syscalls:sys_enter_* /pid == {pid}/ {{ @calls[probe] = count(); }}
+tracepoint:syscalls:sys_exit_* /pid == {pid} && args->ret < 0/ {{ @failures[probe] = count(); }}
+tracepoint:sched:sched_process_exit /pid == {pid} && tid == {pid}/ {{ exit(); }}
+interval:ms:500 {{ print(@calls); print(@failures); clear(@calls); clear(@failures); }}",
+            pid = pid
+        );
+        vec![BPFTRACE.to_string(), "-e".to_string(), program]
+    }
+
+    /// Reads bpftrace's `Attaching N probes...` and the `@calls[...]: N` and
+    /// `@failures[...]: N` lines its maps print as.
+    fn parse<'a>(&self, line: &'a str) -> Output<'a> {
+        let line = line.trim();
+        if line.starts_with("Attaching ") && line.ends_with("probes...") {
+            return Output::Attached;
+        }
+        let entry = |map: &str, probe: &str| -> Option<(&'a str, u64)> {
+            let rest = line.strip_prefix(map)?.strip_prefix('[')?;
+            let (name, count) = rest.strip_prefix(probe)?.split_once("]: ")?;
+            Some((name, count.trim().parse().ok()?))
+        };
+        if let Some((name, count)) = entry("@calls", "tracepoint:syscalls:sys_enter_") {
+            Output::Calls(name, count)
+        } else if let Some((name, count)) = entry("@failures", "tracepoint:syscalls:sys_exit_") {
+            Output::Failures(name, count)
+        } else {
+            Output::Other(line)
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Names of Linux capabilities, for decoding the `CapEff`/`CapPrm` masks in
+//! `/proc/<pid>/status`.
+//!
+//! Bit `n` of a mask stands for the capability numbered `n` in
+//! `<linux/capability.h>`. Bits newer than this table are shown as `CAP_<n>`.
+
+/// Capability names indexed by bit number.
+const NAMES: [&str; 41] = [
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+/// The effective and permitted capability sets of a process.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Capabilities {
+    /// What the process may do right now.
+    pub(crate) effective: u64,
+    /// What it may raise into its effective set.
+    pub(crate) permitted: u64,
+}
+
+impl Capabilities {
+    /// Whether `name` (case-insensitive, with or without the `CAP_` prefix) is
+    /// in either set, i.e. the process holds or can raise it.
+    pub(crate) fn holds(self, name: &str) -> bool {
+        let name = name.to_ascii_uppercase();
+        let name = name.strip_prefix("CAP_").unwrap_or(&name);
+        let held = self.effective | self.permitted;
+        (0..64)
+            .any(|bit| held & (1 << bit) != 0 && cap_name(bit).strip_prefix("CAP_") == Some(name))
+    }
+}
+
+/// The name of capability `bit`.
+fn cap_name(bit: u32) -> String {
+    NAMES
+        .get(bit as usize)
+        .map_or_else(|| format!("CAP_{}", bit), |name| name.to_string())
+}
+
+/// Names the capabilities in `mask`, lowest bit first.
+pub(crate) fn names(mask: u64) -> Vec<String> {
+    (0..64)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(cap_name)
+        .collect()
+}
+
+/// A compact description of `mask` for a list column: `all` when it holds
+/// every known capability (as root's usually does), `-` when empty, otherwise
+/// the names without their `CAP_` prefix.
+pub(crate) fn summary(mask: u64) -> String {
+    let known = (1u64 << NAMES.len()) - 1;
+    if mask & known == known {
+        return "all".to_string();
+    }
+    if mask == 0 {
+        return "-".to_string();
+    }
+    names(mask)
+        .iter()
+        .map(|name| name.strip_prefix("CAP_").unwrap_or(name))
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,156 @@
+//! The "what changed" report: how the trace differs from the baseline marked
+//! with `z`, for checking what an action (clicking a button, sending a
+//! request) made the process do.
+//!
+//! Everything is derived from two snapshots of the per-syscall call and
+//! failure counts, the one taken at the baseline and the current one, so the
+//! report costs nothing until it is opened.
+
+use std::collections::HashMap;
+
+/// How many of the largest count increases the report lists.
+const TOP_INCREASES: usize = 10;
+
+/// The counts snapshotted when the baseline was marked.
+pub(crate) struct Baseline {
+    pub(crate) counts: HashMap<String, u64>,
+    pub(crate) errors: HashMap<String, u64>,
+    /// Wall-clock time of the snapshot, for the report's heading.
+    pub(crate) marked_at: String,
+}
+
+/// The differences between a baseline and the current counts.
+pub(crate) struct ChangeReport {
+    /// Syscalls never called before the baseline, with their calls since.
+    new: Vec<(String, u64)>,
+    /// Syscalls that failed more often, with the added and total failures.
+    more_errors: Vec<(String, u64, u64)>,
+    /// The largest increases in calls, with the calls before the baseline.
+    increases: Vec<(String, u64, u64)>,
+}
+
+impl ChangeReport {
+    /// Compares `counts` and `errors` with what they were at `baseline`.
+    pub(crate) fn new(
+        baseline: &Baseline,
+        counts: &HashMap<String, u64>,
+        errors: &HashMap<String, u64>,
+    ) -> Self {
+        let before = |map: &HashMap<String, u64>, name: &str| map.get(name).copied().unwrap_or(0);
+        // Largest first, then by name so the report is stable.
+        let by_value =
+            |a: &(String, u64), b: &(String, u64)| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0));
+
+        let mut new: Vec<(String, u64)> = counts
+            .iter()
+            .filter(|(name, &count)| count > 0 && before(&baseline.counts, name) == 0)
+            .map(|(name, &count)| (name.clone(), count))
+            .collect();
+        new.sort_by(by_value);
+
+        let mut more_errors: Vec<(String, u64)> = errors
+            .iter()
+            .filter_map(|(name, &total)| {
+                let added = total.saturating_sub(before(&baseline.errors, name));
+                (added > 0).then(|| (name.clone(), added))
+            })
+            .collect();
+        more_errors.sort_by(by_value);
+
+        let mut increases: Vec<(String, u64)> = counts
+            .iter()
+            .filter_map(|(name, &count)| {
+                let added = count.saturating_sub(before(&baseline.counts, name));
+                (added > 0).then(|| (name.clone(), added))
+            })
+            .collect();
+        increases.sort_by(by_value);
+        increases.truncate(TOP_INCREASES);
+
+        Self {
+            new,
+            more_errors: more_errors
+                .into_iter()
+                .map(|(name, added)| {
+                    let total = before(errors, &name);
+                    (name, added, total)
+                })
+                .collect(),
+            increases: increases
+                .into_iter()
+                .map(|(name, added)| {
+                    let earlier = before(&baseline.counts, &name);
+                    (name, added, earlier)
+                })
+                .collect(),
+        }
+    }
+
+    /// The report as sections of a heading and its indented rows, in the
+    /// order they are shown and exported. Empty sections say so.
+    pub(crate) fn sections(&self) -> Vec<(String, Vec<String>)> {
+        let or_none = |rows: Vec<String>| {
+            if rows.is_empty() {
+                vec!["  (none)".to_string()]
+            } else {
+                rows
+            }
+        };
+        vec![
+            (
+                format!("New syscalls ({})", self.new.len()),
+                or_none(
+                    self.new
+                        .iter()
+                        .map(|(name, count)| format!("  {:<20} {} {}", name, count, calls(*count)))
+                        .collect(),
+                ),
+            ),
+            (
+                format!("More failures ({})", self.more_errors.len()),
+                or_none(
+                    self.more_errors
+                        .iter()
+                        .map(|(name, added, total)| {
+                            format!("  {:<20} +{} (now {} failed)", name, added, total)
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                format!("Largest increases (top {})", TOP_INCREASES),
+                or_none(
+                    self.increases
+                        .iter()
+                        .map(|(name, added, earlier)| {
+                            format!("  {:<20} +{} (was {})", name, added, earlier)
+                        })
+                        .collect(),
+                ),
+            ),
+        ]
+    }
+
+    /// The report as plain text for exporting, headed with `title`.
+    pub(crate) fn text(&self, title: &str) -> String {
+        let mut out = format!("{}\n", title);
+        for (heading, rows) in self.sections() {
+            out.push('\n');
+            out.push_str(&heading);
+            out.push('\n');
+            for row in rows {
+                out.push_str(&row);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+fn calls(count: u64) -> &'static str {
+    if count == 1 {
+        "call"
+    } else {
+        "calls"
+    }
+}
@@ -0,0 +1,319 @@
+//! Checkpoints of a live session's accumulated data, so a long capture
+//! survives the inspector being quit by accident or killed.
+//!
+//! While a trace runs, its counts, errors, options and target are written
+//! every [`INTERVAL`] to a file of its own under `checkpoints` in the config
+//! directory, named after the instance and when the session began, so no
+//! session overwrites another's. Ending the session deliberately deletes it;
+//! it is only left behind when the inspector is killed or its terminal goes
+//! away mid-session. On the next start the user is offered to resume the
+//! newest one left by an instance that is no longer running: the data is
+//! shown again, but nothing is traced, as the trace itself ended with the
+//! old instance. The raw timeline is not kept; only what the counts, stats
+//! and errno views need is.
+//!
+//! The file is the usual `key = value` format, headed by a format version
+//! and followed by one section per table:
+//!
+//! ```text
+//! version = 1
+//! target = PID 1234
+//! name = nginx
+//! saved = 2026-10-14 18:00:00
+//! trace_filter = all
+//! follow_forks = true
+//! stack_traces = false
+//! extra_args = -yy
+//! [calls]
+//! read = 120
+//! [errors]
+//! openat = 3
+//! [time]
+//! read = 1534
+//! [errnos]
+//! ENOENT = 3 openat stat
+//! [notes]
+//! 12.345 = clicked the submit button
+//! ```
+//!
+//! Times are in microseconds, note offsets in seconds into the session.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{before_sections, pairs, parse_bool, sections, Config};
+
+/// How often a live session is checkpointed.
+pub(crate) const INTERVAL: Duration = Duration::from_secs(30);
+
+/// The format version written, and the only one read back.
+const VERSION: u32 = 1;
+
+/// A session's data as checkpointed.
+pub(crate) struct Checkpoint {
+    /// What was traced, as the event log describes it, and its program name.
+    pub(crate) target: String,
+    pub(crate) name: String,
+    /// Local time of the write.
+    pub(crate) saved_at: String,
+    pub(crate) trace_filter: String,
+    pub(crate) follow_forks: bool,
+    pub(crate) stack_traces: bool,
+    /// The extra strace arguments, quoted as they are typed.
+    pub(crate) extra_args: String,
+    /// Calls per syscall, in the order the syscalls were first seen.
+    pub(crate) calls: Vec<(String, u64)>,
+    pub(crate) errors: HashMap<String, u64>,
+    /// Total time spent in each syscall.
+    pub(crate) time: HashMap<String, Duration>,
+    /// How often each errno was returned, and by which syscalls.
+    pub(crate) errnos: Vec<(String, u64, Vec<String>)>,
+    pub(crate) notes: Vec<(Duration, String)>,
+}
+
+impl Checkpoint {
+    fn dir() -> Option<PathBuf> {
+        Some(Config::dir()?.join("checkpoints"))
+    }
+
+    /// A file for a session beginning now in this instance.
+    pub(crate) fn session_path() -> Option<PathBuf> {
+        let began = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        Some(Self::dir()?.join(format!("{}-{}", std::process::id(), began)))
+    }
+
+    /// Loads the newest checkpoint left by an instance that is no longer
+    /// running, with its path, if there is one.
+    pub(crate) fn latest() -> Option<(PathBuf, Result<Self, String>)> {
+        let entries = std::fs::read_dir(Self::dir()?).ok()?;
+        let path = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let (pid, began) = name.split_once('-')?;
+                let began = began.parse::<u128>().ok()?;
+                // A live instance still owns and rewrites its checkpoint.
+                if Path::new("/proc").join(pid).exists() {
+                    return None;
+                }
+                Some((began, entry.path()))
+            })
+            .max()?
+            .1;
+        let loaded = Self::load(&path);
+        Some((path, loaded))
+    }
+
+    /// Loads the checkpoint at `path`, failing if it cannot be read or is
+    /// from an unknown format version.
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| Self::parse(&contents))
+            .map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Writes the checkpoint to `path`, replacing the previous one of the
+    /// session in a single rename so a crash mid-write leaves the old one
+    /// intact. Only the user can read it, since it names what was traced.
+    pub(crate) fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let partial = path.with_extension("partial");
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&partial)?;
+        file.write_all(self.text().as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(partial, path)
+    }
+
+    /// Deletes the checkpoint at `path`, if it is there.
+    pub(crate) fn discard(path: &Path) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// The total number of calls.
+    pub(crate) fn total_calls(&self) -> u64 {
+        self.calls.iter().map(|(_, count)| count).sum()
+    }
+
+    fn text(&self) -> String {
+        let mut out = format!(
+            "version = {}\ntarget = {}\nname = {}\nsaved = {}\ntrace_filter = {}\n\
+             follow_forks = {}\nstack_traces = {}\nextra_args = {}\n",
+            VERSION,
+            self.target,
+            self.name,
+            self.saved_at,
+            self.trace_filter,
+            self.follow_forks,
+            self.stack_traces,
+            self.extra_args
+        );
+        out.push_str("[calls]\n");
+        for (name, count) in &self.calls {
+            out.push_str(&format!("{} = {}\n", name, count));
+        }
+        out.push_str("[errors]\n");
+        for (name, count) in &self.errors {
+            out.push_str(&format!("{} = {}\n", name, count));
+        }
+        out.push_str("[time]\n");
+        for (name, time) in &self.time {
+            out.push_str(&format!("{} = {}\n", name, time.as_micros()));
+        }
+        out.push_str("[errnos]\n");
+        for (errno, count, syscalls) in &self.errnos {
+            out.push_str(&format!("{} = {} {}\n", errno, count, syscalls.join(" ")));
+        }
+        out.push_str("[notes]\n");
+        for (offset, text) in &self.notes {
+            out.push_str(&format!("{:.3} = {}\n", offset.as_secs_f64(), text));
+        }
+        out
+    }
+
+    fn parse(contents: &str) -> Result<Self, String> {
+        let mut checkpoint = Self {
+            target: String::new(),
+            name: String::new(),
+            saved_at: String::new(),
+            trace_filter: "all".to_string(),
+            follow_forks: true,
+            stack_traces: false,
+            extra_args: String::new(),
+            calls: Vec::new(),
+            errors: HashMap::new(),
+            time: HashMap::new(),
+            errnos: Vec::new(),
+            notes: Vec::new(),
+        };
+        let mut version = None;
+        for (key, value) in pairs(before_sections(contents)) {
+            match key {
+                "version" => version = value.parse::<u32>().ok(),
+                "target" => checkpoint.target = value.to_string(),
+                "name" => checkpoint.name = value.to_string(),
+                "saved" => checkpoint.saved_at = value.to_string(),
+                "trace_filter" => checkpoint.trace_filter = value.to_string(),
+                "follow_forks" => checkpoint.follow_forks = parse_bool(value).unwrap_or(true),
+                "stack_traces" => checkpoint.stack_traces = parse_bool(value).unwrap_or(false),
+                "extra_args" => checkpoint.extra_args = value.to_string(),
+                _ => {}
+            }
+        }
+        match version {
+            Some(VERSION) => {}
+            Some(other) => {
+                return Err(format!(
+                    "format version {} is not supported (this version reads {})",
+                    other, VERSION
+                ))
+            }
+            None => return Err("not a checkpoint (no format version)".to_string()),
+        }
+        let number = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("'{}' is not a number", value))
+        };
+        for (section, body) in sections(contents) {
+            for (key, value) in pairs(body) {
+                match section {
+                    "calls" => checkpoint.calls.push((key.to_string(), number(value)?)),
+                    "errors" => {
+                        checkpoint.errors.insert(key.to_string(), number(value)?);
+                    }
+                    "time" => {
+                        let micros = number(value)?;
+                        checkpoint
+                            .time
+                            .insert(key.to_string(), Duration::from_micros(micros));
+                    }
+                    "errnos" => {
+                        let mut fields = value.split_whitespace();
+                        let count = number(fields.next().unwrap_or_default())?;
+                        let syscalls = fields.map(str::to_string).collect();
+                        checkpoint.errnos.push((key.to_string(), count, syscalls));
+                    }
+                    "notes" => {
+                        let offset = key
+                            .parse::<f64>()
+                            .ok()
+                            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                            .ok_or_else(|| format!("'{}' is not a note time", key))?;
+                        checkpoint.notes.push((offset, value.to_string()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_parses_back() {
+        let checkpoint = Checkpoint {
+            target: "PID 1234".to_string(),
+            name: "nginx".to_string(),
+            saved_at: "2026-10-14 18:00:00".to_string(),
+            trace_filter: "file".to_string(),
+            follow_forks: false,
+            stack_traces: true,
+            extra_args: "-yy -e 'signal=!all'".to_string(),
+            calls: vec![("read".to_string(), 120), ("openat".to_string(), 7)],
+            errors: HashMap::from([("openat".to_string(), 3)]),
+            time: HashMap::from([("read".to_string(), Duration::from_micros(1534))]),
+            errnos: vec![(
+                "ENOENT".to_string(),
+                3,
+                vec!["openat".to_string(), "stat".to_string()],
+            )],
+            notes: vec![(
+                Duration::from_millis(12_345),
+                "clicked the submit button".to_string(),
+            )],
+        };
+        let parsed = Checkpoint::parse(&checkpoint.text()).unwrap();
+        assert_eq!(parsed.target, checkpoint.target);
+        assert_eq!(parsed.name, checkpoint.name);
+        assert_eq!(parsed.saved_at, checkpoint.saved_at);
+        assert_eq!(parsed.trace_filter, checkpoint.trace_filter);
+        assert!(!parsed.follow_forks);
+        assert!(parsed.stack_traces);
+        assert_eq!(parsed.extra_args, checkpoint.extra_args);
+        assert_eq!(parsed.calls, checkpoint.calls);
+        assert_eq!(parsed.errors, checkpoint.errors);
+        assert_eq!(parsed.time, checkpoint.time);
+        assert_eq!(parsed.errnos, checkpoint.errnos);
+        assert_eq!(parsed.notes, checkpoint.notes);
+    }
+
+    #[test]
+    fn other_versions_are_refused() {
+        let err = Checkpoint::parse("version = 2\n[calls]\nread = 1\n")
+            .err()
+            .unwrap();
+        assert!(err.contains("version 2"), "{}", err);
+        assert!(Checkpoint::parse("[calls]\nread = 1\n").is_err());
+    }
+}
@@ -0,0 +1,37 @@
+//! Copying text to the system clipboard through the terminal.
+//!
+//! The text is sent as an OSC 52 escape sequence, which most terminal
+//! emulators (and tmux with `set-clipboard on`) turn into a clipboard write.
+//! This also works over SSH, where no local clipboard tool could be reached.
+//! Terminals without OSC 52 support silently ignore the sequence.
+
+use std::io::{self, Write};
+
+/// Asks the terminal to place `text` on the clipboard.
+pub(crate) fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Encodes `bytes` as standard, padded base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
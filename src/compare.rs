@@ -0,0 +1,157 @@
+//! A second trace shown next to the main one, for comparing two processes
+//! (say a working and a broken instance of the same program) side by side.
+//!
+//! The comparison pane only keeps what its list shows: per-syscall call and
+//! failure counts. It runs its own strace with the same options as the main
+//! trace, so both sides see the same kinds of calls.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use ratatui::widgets::ListState;
+
+use crate::parser::{self, CallKind};
+use crate::{tracers, TraceOptions, TraceTarget};
+
+/// The process traced in the right-hand pane and what it has called so far.
+pub(crate) struct ComparePane {
+    pub(crate) pid: i32,
+    pub(crate) name: String,
+    child: Option<Child>,
+    receiver: Option<Receiver<String>>,
+    pub(crate) counts: HashMap<String, u64>,
+    pub(crate) errors: HashMap<String, u64>,
+    /// Syscall names in the order they first showed up.
+    pub(crate) first_seen: Vec<String>,
+    /// Fuzzy filter typed while the pane has focus.
+    pub(crate) filter: String,
+    pub(crate) selected: usize,
+    /// Scroll state of the pane's list, and its first visible row.
+    pub(crate) list_state: ListState,
+    pub(crate) list_offset: usize,
+    /// strace's latest diagnostic, such as why attaching failed.
+    last_message: Option<String>,
+    /// Set once the pane's strace has exited, with what it last reported.
+    pub(crate) ended: Option<String>,
+}
+
+impl ComparePane {
+    /// Attaches strace to `pid`, traced with `options`, unless `max_tracers`
+    /// are already running.
+    pub(crate) fn start(
+        pid: i32,
+        name: String,
+        options: &TraceOptions,
+        max_tracers: usize,
+    ) -> io::Result<Self> {
+        tracers::check_limit(max_tracers)?;
+        let command = options.command(&TraceTarget::Attach(pid), None);
+        let mut child = Command::new(&command[0])
+            .args(&command[1..])
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Err(err) = tracers::register(child.id()) {
+            // Not left running untracked, where nothing would stop it.
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(err);
+        }
+        let stderr = child.stderr.take().expect("Failed to capture stderr");
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in parser::lossy_lines(BufReader::new(stderr)) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            pid,
+            name,
+            child: Some(child),
+            receiver: Some(rx),
+            counts: HashMap::new(),
+            errors: HashMap::new(),
+            first_seen: Vec::new(),
+            filter: String::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            list_offset: 0,
+            last_message: None,
+            ended: None,
+        })
+    }
+
+    /// Counts the lines strace has written since the last call. Returns
+    /// whether anything changed, including the trace ending.
+    pub(crate) fn drain(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(rx) = &self.receiver {
+            let line = match rx.try_recv() {
+                Ok(line) => line,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.receiver = None;
+                    break;
+                }
+            };
+            changed = true;
+            let trimmed = line.trim();
+            if let Some(message) = trimmed.strip_prefix("strace: ") {
+                self.last_message = Some(message.to_string());
+                continue;
+            }
+            let Some(call) = parser::parse_syscall(trimmed) else {
+                continue;
+            };
+            if parser::errno(trimmed).is_some() {
+                *self.errors.entry(call.name.to_string()).or_insert(0) += 1;
+            }
+            // A split call is counted once, with its first half.
+            if call.kind != CallKind::Resumed {
+                let count = self.counts.entry(call.name.to_string()).or_insert(0);
+                if *count == 0 {
+                    self.first_seen.push(call.name.to_string());
+                }
+                *count += 1;
+            }
+        }
+        let exited = self
+            .child
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(Some(_))));
+        if exited && self.receiver.is_none() {
+            self.stop();
+            self.ended = Some(
+                self.last_message
+                    .take()
+                    .unwrap_or_else(|| "trace ended".to_string()),
+            );
+            changed = true;
+        }
+        changed
+    }
+
+    /// Whether the pane's strace is still running.
+    pub(crate) fn running(&self) -> bool {
+        self.child.is_some()
+    }
+
+    /// Stops the pane's strace.
+    pub(crate) fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = tracers::unregister(child.id());
+        }
+    }
+}
+
+impl Drop for ComparePane {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,518 @@
+//! User configuration, read from `$XDG_CONFIG_HOME/syscall-monitor/config`
+//! (or `~/.config/syscall-monitor/config`).
+//!
+//! The file holds one `key = value` pair per line; blank lines and lines
+//! starting with `#` are ignored. Unknown keys and malformed values are
+//! skipped, and reported once running, so a bad entry never prevents the
+//! tool from starting.
+//!
+//! Key bindings follow in a `[keys]` section; see [`crate::keymap`].
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::syscalls::Category;
+use crate::view::{
+    ColorMode, ProcessColumn, SyscallOrder, DASHBOARD_SPLIT_MAX, DASHBOARD_SPLIT_MIN,
+};
+
+/// Settings loaded from the config file.
+#[derive(Clone)]
+pub struct Config {
+    /// Disables every action that signals or otherwise alters a process.
+    pub safe_mode: bool,
+    /// How many syscalls the count-sorted view shows before "show all" is
+    /// toggled; 0 shows them all.
+    pub top_n: usize,
+    /// Whether mouse capture starts enabled; it can be toggled at runtime.
+    pub mouse_capture: bool,
+    /// Saves each session's trace filter and view as its program's profile when
+    /// the session ends, so reattaching picks up where it left off.
+    pub remember_settings: bool,
+    /// Rings the terminal bell on alerts (the target exiting, a syscall from
+    /// `notify_syscalls` appearing).
+    pub notify_bell: bool,
+    /// Sends alerts as desktop notifications via `notify-send`.
+    pub notify_desktop: bool,
+    /// Syscalls whose first occurrence in a session raises an alert.
+    pub notify_syscalls: Vec<String>,
+    /// How many strace processes this user's instances may run at once; 0
+    /// lifts the limit.
+    pub max_tracers: usize,
+    /// Detaches from a traced process after this many minutes without a key
+    /// press or mouse event; 0 never detaches.
+    pub idle_detach_minutes: u64,
+    /// Leaves this inspector and its strace processes out of the process list.
+    pub hide_self: bool,
+    /// Makes a syscall filter of several terms list syscalls matching all of
+    /// them instead of any.
+    pub filter_all_terms: bool,
+    /// Processes that are never signalled: names, PIDs, and `ancestors` for
+    /// this inspector and the processes that started it (such as the shell).
+    pub protected: Vec<String>,
+    /// The `action = key` pairs of the `[keys]` section, checked when the
+    /// key map is built.
+    pub keys: Vec<(String, String)>,
+    /// Appends recoverable errors, with timestamps, to `errors.log` in the
+    /// config directory. Off by default, since the errors can name the
+    /// processes and files being inspected.
+    pub error_log: bool,
+    /// Unknown keys and malformed values that were skipped, for reporting.
+    pub warnings: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            safe_mode: false,
+            top_n: 20,
+            mouse_capture: true,
+            remember_settings: false,
+            notify_bell: false,
+            notify_desktop: false,
+            notify_syscalls: Vec::new(),
+            max_tracers: 8,
+            idle_detach_minutes: 0,
+            hide_self: true,
+            filter_all_terms: false,
+            protected: vec!["1".to_string(), "ancestors".to_string()],
+            keys: Vec::new(),
+            error_log: false,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Returns the directory holding the config file and other persisted state.
+    pub fn dir() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("syscall-monitor"))
+    }
+
+    /// Returns the path of the config file.
+    pub fn path() -> Option<PathBuf> {
+        Some(Self::dir()?.join("config"))
+    }
+
+    /// Returns the path of the error log written when `error_log` is on.
+    pub fn error_log_path() -> Option<PathBuf> {
+        Some(Self::dir()?.join("errors.log"))
+    }
+
+    /// When the config file was last modified, or `None` if it is missing.
+    pub fn modified() -> Option<SystemTime> {
+        std::fs::metadata(Self::path()?).ok()?.modified().ok()
+    }
+
+    /// Loads the config file, falling back to defaults if it is missing or unreadable.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parses config file contents.
+    pub fn parse(contents: &str) -> Self {
+        let mut config = Self::default();
+        for (name, section) in sections(contents) {
+            if name == "keys" {
+                config.keys = pairs(section)
+                    .map(|(action, key)| (action.to_string(), key.to_string()))
+                    .collect();
+            }
+        }
+        for (key, value) in pairs(before_sections(contents)) {
+            match key {
+                "safe_mode" => match parse_bool(value) {
+                    Some(v) => config.safe_mode = v,
+                    None => config.reject(key, value),
+                },
+                "top_n" => match value.parse() {
+                    Ok(v) => config.top_n = v,
+                    Err(_) => config.reject(key, value),
+                },
+                "max_tracers" => match value.parse() {
+                    Ok(v) => config.max_tracers = v,
+                    Err(_) => config.reject(key, value),
+                },
+                "idle_detach_minutes" => match value.parse() {
+                    Ok(v) => config.idle_detach_minutes = v,
+                    Err(_) => config.reject(key, value),
+                },
+                "hide_self" => match parse_bool(value) {
+                    Some(v) => config.hide_self = v,
+                    None => config.reject(key, value),
+                },
+                "filter_all_terms" => match parse_bool(value) {
+                    Some(v) => config.filter_all_terms = v,
+                    None => config.reject(key, value),
+                },
+                "mouse_capture" => match parse_bool(value) {
+                    Some(v) => config.mouse_capture = v,
+                    None => config.reject(key, value),
+                },
+                "remember_settings" => match parse_bool(value) {
+                    Some(v) => config.remember_settings = v,
+                    None => config.reject(key, value),
+                },
+                "notify_bell" => match parse_bool(value) {
+                    Some(v) => config.notify_bell = v,
+                    None => config.reject(key, value),
+                },
+                "notify_desktop" => match parse_bool(value) {
+                    Some(v) => config.notify_desktop = v,
+                    None => config.reject(key, value),
+                },
+                "notify_syscalls" => {
+                    config.notify_syscalls = list(value);
+                }
+                "protected" => {
+                    config.protected = list(value);
+                }
+                "error_log" => match parse_bool(value) {
+                    Some(v) => config.error_log = v,
+                    None => config.reject(key, value),
+                },
+                _ => config
+                    .warnings
+                    .push(format!("Unknown config key '{}' ignored", key)),
+            }
+        }
+        config
+    }
+
+    /// Notes a value of `key` that could not be parsed.
+    fn reject(&mut self, key: &str, value: &str) {
+        self.warnings.push(format!(
+            "Config value '{}' for {} is not valid; it keeps its default",
+            value, key
+        ));
+    }
+}
+
+/// UI state remembered between runs, stored next to the config file in `state`.
+///
+/// Unlike the config file this is written by the tool itself on exit.
+pub struct UiState {
+    pub show_detailed: bool,
+    /// The syscall list and timeline shown together, and the list's share
+    /// of the height in percent.
+    pub dashboard: bool,
+    pub dashboard_split: u16,
+    pub syscall_order: SyscallOrder,
+    /// Whether the count-sorted list reorders live rather than on demand.
+    pub auto_sort: bool,
+    pub color_mode: ColorMode,
+    /// Whether the first-run tutorial has been shown and dismissed.
+    pub tutorial_seen: bool,
+    pub show_events: bool,
+    pub show_all: bool,
+    /// Timeline timestamps relative to the first captured one instead of wall-clock.
+    pub relative_times: bool,
+    /// Process list columns in display order, with whether each is shown.
+    pub process_columns: Vec<(ProcessColumn, bool)>,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            show_detailed: false,
+            dashboard: false,
+            dashboard_split: 50,
+            syscall_order: SyscallOrder::Alphabetical,
+            auto_sort: false,
+            color_mode: ColorMode::Category,
+            tutorial_seen: false,
+            show_events: false,
+            show_all: false,
+            relative_times: false,
+            process_columns: ProcessColumn::defaults(),
+        }
+    }
+}
+
+impl UiState {
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join("state"))
+    }
+
+    /// Loads the saved state; a missing or corrupt file yields the defaults.
+    ///
+    /// Without a state file this is the first run, unless a config file
+    /// shows the tool has been set up before.
+    pub fn load() -> Self {
+        let mut state = Self::default();
+        let Some(contents) = Self::path().and_then(|path| std::fs::read_to_string(path).ok())
+        else {
+            state.tutorial_seen = Config::path().is_some_and(|path| path.exists());
+            return state;
+        };
+        // State files from before the tutorial existed belong to seasoned users.
+        state.tutorial_seen = true;
+        for (key, value) in pairs(&contents) {
+            match key {
+                "view" => {
+                    state.show_detailed = value == "raw";
+                    state.dashboard = value == "dashboard";
+                }
+                "dashboard_split" => {
+                    if let Ok(v) = value.parse::<u16>() {
+                        state.dashboard_split = v.clamp(DASHBOARD_SPLIT_MIN, DASHBOARD_SPLIT_MAX);
+                    }
+                }
+                "sort" => {
+                    if let Some(order) = SyscallOrder::from_key(value) {
+                        state.syscall_order = order;
+                    }
+                }
+                "colors" => {
+                    if let Some(mode) = ColorMode::from_key(value) {
+                        state.color_mode = mode;
+                    }
+                }
+                "tutorial_seen" => state.tutorial_seen = parse_bool(value).unwrap_or(true),
+                "auto_sort" => state.auto_sort = parse_bool(value).unwrap_or(false),
+                "show_events" => state.show_events = parse_bool(value).unwrap_or(false),
+                "show_all" => state.show_all = parse_bool(value).unwrap_or(false),
+                "times" => state.relative_times = value == "relative",
+                "columns" => state.process_columns = parse_columns(value),
+                _ => {}
+            }
+        }
+        state
+    }
+
+    /// Writes the state file, creating the config directory if needed.
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let contents = format!(
+            "view = {}\ndashboard_split = {}\nsort = {}\nauto_sort = {}\ncolors = {}\ntutorial_seen = {}\nshow_events = {}\nshow_all = {}\ntimes = {}\ncolumns = {}\n",
+            if self.show_detailed {
+                "raw"
+            } else if self.dashboard {
+                "dashboard"
+            } else {
+                "list"
+            },
+            self.dashboard_split,
+            self.syscall_order.key(),
+            self.auto_sort,
+            self.color_mode.key(),
+            self.tutorial_seen,
+            self.show_events,
+            self.show_all,
+            if self.relative_times {
+                "relative"
+            } else {
+                "absolute"
+            },
+            format_columns(&self.process_columns),
+        );
+        std::fs::write(path, contents)
+    }
+}
+
+/// Preferred trace filter and view for one program, applied whenever a process
+/// with that name is traced.
+///
+/// Profiles are saved by the tool in `profiles` next to the config file, one
+/// `[process name]` section per program followed by its `key = value` pairs.
+#[derive(Clone)]
+pub struct Profile {
+    pub trace_filter: String,
+    pub category: Option<Category>,
+    pub show_detailed: bool,
+    pub syscall_order: SyscallOrder,
+    /// Syscalls kept at the top of the list.
+    pub pins: Vec<String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            trace_filter: "all".to_string(),
+            category: None,
+            show_detailed: false,
+            syscall_order: SyscallOrder::Alphabetical,
+            pins: Vec::new(),
+        }
+    }
+}
+
+impl Profile {
+    fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join("profiles"))
+    }
+
+    /// Loads every saved profile keyed by process name; a missing file yields none.
+    pub fn load_all() -> BTreeMap<String, Profile> {
+        let mut profiles = BTreeMap::new();
+        let Some(contents) = Self::path().and_then(|path| std::fs::read_to_string(path).ok())
+        else {
+            return profiles;
+        };
+        for (name, section) in sections(&contents) {
+            let mut profile = Profile::default();
+            for (key, value) in pairs(section) {
+                match key {
+                    "trace_filter" if !value.is_empty() => profile.trace_filter = value.to_string(),
+                    "category" => profile.category = Category::from_label(value),
+                    "view" => profile.show_detailed = value == "raw",
+                    "sort" => {
+                        if let Some(order) = SyscallOrder::from_key(value) {
+                            profile.syscall_order = order;
+                        }
+                    }
+                    "pins" => {
+                        profile.pins = value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|name| !name.is_empty())
+                            .map(str::to_string)
+                            .collect();
+                    }
+                    _ => {}
+                }
+            }
+            profiles.insert(name.to_string(), profile);
+        }
+        profiles
+    }
+
+    /// Writes all profiles, replacing the file.
+    pub fn save_all(profiles: &BTreeMap<String, Profile>) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut contents = String::new();
+        for (name, profile) in profiles {
+            contents.push_str(&format!(
+                "[{}]\ntrace_filter = {}\ncategory = {}\nview = {}\nsort = {}\npins = {}\n\n",
+                name,
+                profile.trace_filter,
+                profile.category.map_or("none", Category::label),
+                if profile.show_detailed { "raw" } else { "list" },
+                profile.syscall_order.key(),
+                profile.pins.join(","),
+            ));
+        }
+        std::fs::write(path, contents)
+    }
+}
+
+/// The part of a file before its first `[name]` section.
+pub(crate) fn before_sections(contents: &str) -> &str {
+    let mut offset = 0;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            break;
+        }
+        offset += line.len();
+    }
+    &contents[..offset]
+}
+
+/// Splits a file into `[name]` sections, yielding each name with the text
+/// below it. Anything before the first section is ignored.
+pub(crate) fn sections(contents: &str) -> Vec<(&str, &str)> {
+    let mut sections = Vec::new();
+    let mut current: Option<(&str, usize)> = None;
+    let mut offset = 0;
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            if let Some((prev, start)) = current {
+                sections.push((prev, &contents[start..offset]));
+            }
+            current = Some((name.trim(), offset + line.len()));
+        }
+        offset += line.len();
+    }
+    if let Some((name, start)) = current {
+        sections.push((name, &contents[start..]));
+    }
+    sections
+}
+
+/// Formats a column layout as `pid,threads,-user,...`, hidden columns prefixed with `-`.
+fn format_columns(columns: &[(ProcessColumn, bool)]) -> String {
+    columns
+        .iter()
+        .map(|(column, shown)| format!("{}{}", if *shown { "" } else { "-" }, column.key()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parses a layout written by [`format_columns`]. Unknown and repeated names
+/// are skipped, columns missing from the list are appended hidden, and a
+/// layout with nothing visible falls back to the defaults.
+fn parse_columns(value: &str) -> Vec<(ProcessColumn, bool)> {
+    let mut columns: Vec<(ProcessColumn, bool)> = Vec::new();
+    for entry in value.split(',').map(str::trim) {
+        let (key, shown) = match entry.strip_prefix('-') {
+            Some(key) => (key, false),
+            None => (entry, true),
+        };
+        if let Some(column) = ProcessColumn::from_key(key) {
+            if !columns.iter().any(|(c, _)| *c == column) {
+                columns.push((column, shown));
+            }
+        }
+    }
+    if !columns.iter().any(|(_, shown)| *shown) {
+        return ProcessColumn::defaults();
+    }
+    for column in ProcessColumn::ALL {
+        if !columns.iter().any(|(c, _)| *c == column) {
+            columns.push((column, false));
+        }
+    }
+    columns
+}
+
+/// Yields the trimmed `key = value` pairs of a file, skipping blanks, comments
+/// and lines without `=`.
+pub(crate) fn pairs(contents: &str) -> impl Iterator<Item = (&str, &str)> {
+    contents.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (key, value) = line.split_once('=')?;
+        Some((key.trim(), value.trim()))
+    })
+}
+
+/// Splits a comma-separated list, dropping empty entries.
+fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parses `true`/`false` (and the common `yes`/`no`, `on`/`off`, `1`/`0` spellings).
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,45 @@
+//! Backends that count syscalls in place of strace.
+//!
+//! strace sees every call in full but stops the traced process for each one.
+//! A counting backend runs a program that only tallies calls and failures per
+//! syscall and reports the totals as lines of text, which reach the session
+//! through the same channel strace's lines would. The backends a build has
+//! depend on its cargo features; without any, strace is always used.
+
+/// One line of a counting backend's output, as far as the session cares.
+#[cfg_attr(not(feature = "bpf"), allow(dead_code))]
+pub(crate) enum Output<'a> {
+    /// The probes are loaded and counting.
+    Attached,
+    /// `count` more calls of the syscall `name`.
+    Calls(&'a str, u64),
+    /// `count` more failed calls of `name`.
+    Failures(&'a str, u64),
+    /// Anything else, such as an error message.
+    Other(&'a str),
+}
+
+/// A way of counting the syscalls of a single running process.
+pub(crate) trait Counter {
+    /// What the backend is called in messages and the header, e.g. "eBPF".
+    fn name(&self) -> &'static str;
+
+    /// Why the backend cannot be used here, or `None` if it can.
+    fn unavailable(&self) -> Option<String>;
+
+    /// The command line counting the syscalls of `pid`, writing its totals
+    /// to stdout and its errors to stderr. It exits by itself when the
+    /// process does.
+    fn command(&self, pid: i32) -> Vec<String>;
+
+    /// Makes sense of a line the command printed.
+    fn parse<'a>(&self, line: &'a str) -> Output<'a>;
+}
+
+/// The counting backend of this build, if it was built with one.
+pub(crate) fn counter() -> Option<Box<dyn Counter>> {
+    #[cfg(feature = "bpf")]
+    return Some(Box::new(crate::bpf::Bpftrace));
+    #[cfg(not(feature = "bpf"))]
+    None
+}
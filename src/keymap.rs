@@ -0,0 +1,235 @@
+//! Rebindable keys for the monitoring screen's single-letter actions (and
+//! quitting the process list), read from the `[keys]` section of the config
+//! file:
+//!
+//! ```text
+//! [keys]
+//! quit = Q
+//! kill = X
+//! filter = /
+//! ```
+//!
+//! Every action keeps its default key unless rebound, so the defaults are the
+//! keys the tool has always used. The key handlers match actions, which
+//! [`KeyMap::action`] finds for a pressed key, rather than keys.
+
+use std::collections::HashMap;
+
+/// Every rebindable action with its default key, in the order the README
+/// documents them.
+pub(crate) const ACTIONS: &[(&str, char)] = &[
+    ("quit", 'q'),
+    ("back", 'b'),
+    ("filter", 'f'),
+    ("kill", 'k'),
+    ("details", 't'),
+    ("stats", 'v'),
+    ("dashboard", 'D'),
+    ("expand", 'x'),
+    ("copy", 'y'),
+    ("pin", 'p'),
+    ("note", 'j'),
+    ("baseline", 'z'),
+    ("clear_baseline", 'Z'),
+    ("what_changed", 'W'),
+    ("resources", 'G'),
+    ("normal_set", 'N'),
+    ("only_not_normal", 'h'),
+    ("follow_fd", 'd'),
+    ("lock_pid", 'i'),
+    ("unlock_pid", 'I'),
+    ("relative_times", 'r'),
+    ("sort", 's'),
+    ("resort", 'u'),
+    ("auto_sort", 'U'),
+    ("colors", 'C'),
+    ("show_all", 'a'),
+    ("categories", 'g'),
+    ("errnos", 'o'),
+    ("sampling", 'n'),
+    ("timed_capture", 'T'),
+    ("command", 'c'),
+    ("trace_filter", 'e'),
+    ("strace_args", 'A'),
+    ("follow_forks", 'F'),
+    ("stack_traces", 'K'),
+    ("callers", 'w'),
+    ("manual", 'M'),
+    ("restart", 'R'),
+    ("save_profile", 'P'),
+    ("export", 'E'),
+    ("events", 'l'),
+    ("mouse", 'm'),
+    ("compare_sync", 'L'),
+];
+
+/// Monitoring keys that cannot be rebound: category numbers and the
+/// dashboard split.
+const FIXED: &str = "012345678+=-";
+
+/// The key bound to each action.
+pub(crate) struct KeyMap {
+    keys: HashMap<&'static str, char>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            keys: ACTIONS.iter().copied().collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Builds the map from the `action = key` pairs of the `[keys]` section.
+    /// Returns it with a warning for every entry that was not applied:
+    /// unknown actions, keys that are not a single character or are fixed,
+    /// empty keys (which would leave the action unbound) and keys claimed by
+    /// two actions, whose rebindings are undone.
+    pub(crate) fn parse<'a>(
+        pairs: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> (Self, Vec<String>) {
+        let mut map = Self::default();
+        let mut warnings = Vec::new();
+        let mut rebound: Vec<&'static str> = Vec::new();
+        for (action, key) in pairs {
+            let Some(&(action, _)) = ACTIONS.iter().find(|(name, _)| *name == action) else {
+                warnings.push(format!("Unknown action '{}' in [keys]", action));
+                continue;
+            };
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if FIXED.contains(c) => warnings.push(format!(
+                    "Key {} for {} is taken by a fixed key; it stays on {}",
+                    c,
+                    action,
+                    default_key(action)
+                )),
+                (Some(c), None) if !c.is_whitespace() => {
+                    map.keys.insert(action, c);
+                    rebound.push(action);
+                }
+                (None, _) => warnings.push(format!(
+                    "Key for {} is empty; it stays on {}",
+                    action,
+                    default_key(action)
+                )),
+                _ => warnings.push(format!(
+                    "Key '{}' for {} is not a single character; it stays on {}",
+                    key,
+                    action,
+                    default_key(action)
+                )),
+            }
+        }
+        // Reverting one conflict can cause another (its default may be taken
+        // by a third rebinding), so repeat until none is left; each round
+        // reverts at least one action, so this ends.
+        loop {
+            let conflict = ACTIONS.iter().find_map(|&(action, _)| {
+                let key = map.keys[action];
+                let other = ACTIONS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .find(|other| *other != action && map.keys[other] == key)?;
+                Some((action, other, key))
+            });
+            let Some((first, second, key)) = conflict else {
+                break;
+            };
+            let reverted: Vec<&str> = [first, second]
+                .into_iter()
+                .filter(|action| rebound.contains(action))
+                .collect();
+            warnings.push(format!(
+                "Key {} is bound to both {} and {}; {} back to the default",
+                key,
+                first,
+                second,
+                if reverted.len() == 1 {
+                    "putting it"
+                } else {
+                    "putting both"
+                }
+            ));
+            for action in reverted {
+                map.keys.insert(action, default_key(action));
+                rebound.retain(|a| *a != action);
+            }
+        }
+        (map, warnings)
+    }
+
+    /// The key bound to `action`.
+    pub(crate) fn key(&self, action: &str) -> char {
+        self.keys
+            .get(action)
+            .copied()
+            .unwrap_or_else(|| default_key(action))
+    }
+
+    /// The action bound to `c`, if any; the default key of an action that
+    /// was moved elsewhere has none.
+    pub(crate) fn action(&self, c: char) -> Option<&'static str> {
+        ACTIONS
+            .iter()
+            .map(|&(action, _)| action)
+            .find(|action| self.keys[action] == c)
+    }
+
+    /// Rewrites the key part of a `k: Description` hint with the keys they
+    /// are bound to, so the instruction bar shows what to press.
+    pub(crate) fn relabel(&self, hint: &str) -> String {
+        let Some((keys, description)) = hint.split_once(": ") else {
+            return hint.to_string();
+        };
+        let keys: Vec<String> = keys
+            .split(' ')
+            .map(|word| {
+                let mut chars = word.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => ACTIONS
+                        .iter()
+                        .find(|&&(_, default)| default == c)
+                        .map_or(c, |(action, _)| self.keys[action])
+                        .to_string(),
+                    _ => word.to_string(),
+                }
+            })
+            .collect();
+        format!("{}: {}", keys.join(" "), description)
+    }
+}
+
+fn default_key(action: &str) -> char {
+    ACTIONS
+        .iter()
+        .find(|(name, _)| *name == action)
+        .map_or(' ', |&(_, key)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_find_the_action_bound_to_them() {
+        let (map, warnings) = KeyMap::parse([("kill", "X"), ("manual", "?")].into_iter());
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(map.action('X'), Some("kill"));
+        assert_eq!(map.action('?'), Some("manual"));
+        assert_eq!(map.action('f'), Some("filter"));
+        // The defaults of moved actions do nothing.
+        assert_eq!(map.action('k'), None);
+        assert_eq!(map.action('M'), None);
+        assert_eq!(map.key("manual"), '?');
+    }
+
+    #[test]
+    fn clashing_keys_fall_back_to_the_defaults() {
+        let (map, warnings) = KeyMap::parse([("kill", "f")].into_iter());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(map.action('f'), Some("filter"));
+        assert_eq!(map.action('k'), Some("kill"));
+    }
+}
@@ -0,0 +1,110 @@
+//! The leaderboard: the listed processes ranked by how busy they are right
+//! now, to find the ones worth tracing first.
+//!
+//! Attaching strace to every candidate to count its syscalls would slow them
+//! all down, so the ranking comes from their `/proc` counters instead, read
+//! once a second: context switches, which a process makes every time a
+//! syscall blocks, and CPU time. A process busy in syscalls that never block
+//! shows up through its system CPU time.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::procfs::{self, Counters};
+
+/// How often the counters are read again.
+pub(crate) const INTERVAL: Duration = Duration::from_secs(1);
+
+/// One ranked process.
+pub(crate) struct Entry {
+    pub(crate) pid: i32,
+    pub(crate) name: String,
+    /// Voluntary plus involuntary context switches per second.
+    pub(crate) switches: u64,
+    /// User and system CPU use, in percent of one core.
+    pub(crate) user_cpu: u64,
+    pub(crate) system_cpu: u64,
+}
+
+pub(crate) struct Leaderboard {
+    /// The counters read last, and when.
+    previous: HashMap<i32, Counters>,
+    sampled_at: Option<Instant>,
+    /// The ranking from the last two readings, busiest first; empty until
+    /// the second.
+    pub(crate) entries: Vec<Entry>,
+    pub(crate) selected: usize,
+}
+
+impl Leaderboard {
+    /// Starts ranking; the first reading is taken right away.
+    pub(crate) fn new<'a>(processes: impl Iterator<Item = (i32, &'a str)>) -> Self {
+        let mut board = Self {
+            previous: HashMap::new(),
+            sampled_at: None,
+            entries: Vec::new(),
+            selected: 0,
+        };
+        board.sample(processes);
+        board
+    }
+
+    /// Whether a new reading is due.
+    pub(crate) fn due(&self) -> bool {
+        self.sampled_at.is_none_or(|at| at.elapsed() >= INTERVAL)
+    }
+
+    /// Reads the counters of `processes` and ranks them by their rates since
+    /// the last reading. Processes that could not be read then or now, such
+    /// as ones that exited, are left out.
+    pub(crate) fn sample<'a>(&mut self, processes: impl Iterator<Item = (i32, &'a str)>) {
+        let now = Instant::now();
+        let secs = self
+            .sampled_at
+            .map(|at| now.duration_since(at).as_secs_f64());
+        let ticks = procfs::clock_ticks() as f64;
+        let mut current = HashMap::new();
+        let mut entries = Vec::new();
+        for (pid, name) in processes {
+            let Ok(counters) = procfs::counters(pid) else {
+                continue;
+            };
+            if let (Some(secs), Some(before)) = (secs, self.previous.get(&pid)) {
+                let switches = (counters.voluntary_switches + counters.involuntary_switches)
+                    .saturating_sub(before.voluntary_switches + before.involuntary_switches);
+                let cpu = |now: u64, then: u64| {
+                    (now.saturating_sub(then) as f64 / ticks / secs * 100.0).round() as u64
+                };
+                entries.push(Entry {
+                    pid,
+                    name: name.to_string(),
+                    switches: (switches as f64 / secs).round() as u64,
+                    user_cpu: cpu(counters.utime, before.utime),
+                    system_cpu: cpu(counters.stime, before.stime),
+                });
+            }
+            current.insert(pid, counters);
+        }
+        if secs.is_some() {
+            entries.sort_by(|a, b| {
+                b.switches
+                    .cmp(&a.switches)
+                    .then((b.user_cpu + b.system_cpu).cmp(&(a.user_cpu + a.system_cpu)))
+                    .then(a.pid.cmp(&b.pid))
+            });
+            // The highlight follows its process as the ranking changes.
+            let highlighted = self.entries.get(self.selected).map(|e| e.pid);
+            self.selected = highlighted
+                .and_then(|pid| entries.iter().position(|e| e.pid == pid))
+                .unwrap_or(0);
+            self.entries = entries;
+        }
+        self.previous = current;
+        self.sampled_at = Some(now);
+    }
+
+    /// The PID of the highlighted entry.
+    pub(crate) fn highlighted(&self) -> Option<i32> {
+        self.entries.get(self.selected).map(|e| e.pid)
+    }
+}
@@ -1,43 +1,673 @@
 use std::{
-    collections::HashSet,
-    io::{BufRead, BufReader},
-    process::{Child, Command, Stdio},
-    sync::mpsc::{self, Receiver},
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fs::{File, OpenOptions},
+    io::{BufReader, Read, Write},
+    os::unix::{
+        fs::{MetadataExt, OpenOptionsExt},
+        io::AsRawFd,
+        process::ExitStatusExt,
+    },
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+    },
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{
+        Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row, Sparkline,
+        Table, TableState, Widget, Wrap,
+    },
     Terminal,
 };
 
-use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+use sysinfo::{PidExt, ProcessExt, System, SystemExt, UserExt};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+mod args;
+mod batch;
+#[cfg(feature = "bpf")]
+mod bpf;
+mod capabilities;
+mod changes;
+mod checkpoint;
+mod clipboard;
+mod compare;
+mod config;
+mod counting;
+mod keymap;
+mod leaderboard;
+mod manpage;
+mod notify;
+mod parser;
+mod procfs;
+mod procstats;
+mod protect;
+mod replay;
+mod resources;
+mod snapshot;
+mod syscalls;
+mod tracers;
+mod view;
+
+use capabilities::Capabilities;
+use changes::{Baseline, ChangeReport};
+use checkpoint::Checkpoint;
+use config::{Config, Profile, UiState};
+use keymap::KeyMap;
+use parser::CallKind;
+use protect::Protection;
+use resources::Resources;
+use syscalls::Category;
+use view::{ColorMode, ProcessColumn, SyscallOrder, DASHBOARD_SPLIT_MAX, DASHBOARD_SPLIT_MIN};
+
+use nix::fcntl::{fcntl, FcntlArg, OFlag};
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::signal::{self, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use nix::sys::stat::Mode;
+use nix::unistd::{geteuid, mkfifo, Pid};
+
+/// Maximum number of entries kept in the event log.
+const MAX_EVENTS: usize = 50;
+
+/// Raw-line sampling rates cycled through with the `n` key (keep 1 in N).
+const SAMPLE_RATES: [u64; 4] = [1, 10, 100, 1000];
+
+/// Maximum number of raw lines kept in the timeline.
+const MAX_TIMELINE: usize = 10_000;
+
+/// Color identifying each syscall category in the lists and the legend.
+fn category_color(category: Category) -> Color {
+    match category {
+        Category::File => Color::Cyan,
+        Category::Network => Color::Green,
+        Category::Memory => Color::Yellow,
+        Category::Process => Color::Magenta,
+        Category::Signal => Color::Red,
+        Category::Ipc => Color::LightBlue,
+        Category::Time => Color::LightYellow,
+        Category::Other => Color::Gray,
+    }
+}
+
+/// Colors a syscall by the share of its calls that failed, from green (none)
+/// through yellow to red (all); gray if it has no calls to go by.
+fn error_rate_color(errors: u64, calls: u64) -> Color {
+    if calls == 0 {
+        return Color::Gray;
+    }
+    let rate = (errors as f64 / calls as f64).min(1.0);
+    let red = (rate * 2.0).min(1.0) * 255.0;
+    let green = ((1.0 - rate) * 2.0).min(1.0) * 255.0;
+    Color::Rgb(red as u8, green as u8, 0)
+}
+
+/// Prefix marking pinned syscalls in the list and stats table.
+const PIN_MARKER: &str = "★ ";
+
+/// How often the config file is checked for changes.
+const CONFIG_POLL: Duration = Duration::from_secs(1);
 
-use nix::sys::signal::{self, Signal};
-use nix::unistd::Pid;
+/// How many syscalls the summary shown after detaching lists.
+const SESSION_REPORT_TOP: usize = 5;
+
+/// Syscalls that create, replace or end a process, highlighted in the timeline.
+const LIFECYCLE_SYSCALLS: [&str; 7] = [
+    "clone",
+    "clone3",
+    "fork",
+    "vfork",
+    "execve",
+    "execveat",
+    "exit_group",
+];
+
+/// strace exiting sooner than this after starting, with errors and without the
+/// target's exit being seen, is reported as a failure to trace at all.
+const EARLY_EXIT: Duration = Duration::from_secs(1);
+
+/// Time without any strace output after which the header reports the trace as idle.
+const IDLE_AFTER: Duration = Duration::from_secs(3);
+
+/// Longest time the screen goes without a redraw, so clocks and countdowns
+/// keep moving while nothing else changes.
+const IDLE_REDRAW: Duration = Duration::from_secs(1);
+
+/// Terminals narrower or shorter than this get the compact layout.
+const COMPACT_WIDTH: u16 = 100;
+const COMPACT_HEIGHT: u16 = 30;
+
+/// Height of the event log panel, including borders.
+const EVENTS_PANEL_HEIGHT: u16 = 8;
 
 /// Represents a running process.
 #[derive(Clone)]
 struct ProcessInfo {
     pid: i32,
+    parent: Option<i32>,
     name: String,
     cmd: String,
+    // Seconds since the epoch at which the process started.
+    start_time: u64,
+    // Number of threads, from `/proc/<pid>/status`; `None` if unreadable.
+    thread_count: Option<u32>,
+    // Owning user's name, or the numeric UID if it has none.
+    user: String,
+    // Resident memory in bytes.
+    memory: u64,
+    // Path from `/proc/<pid>/cgroup`; `None` if unreadable.
+    cgroup: Option<String>,
+    // Working directory from `/proc/<pid>/cwd`; `None` if unreadable.
+    cwd: Option<PathBuf>,
+    // Capability sets from `/proc/<pid>/status`; `None` if unreadable.
+    capabilities: Option<Capabilities>,
+}
+
+/// Whether `p` is this inspector or an strace it started, which the process
+/// list hides unless asked to show them.
+fn is_own_process(p: &ProcessInfo) -> bool {
+    let own = std::process::id() as i32;
+    p.pid == own || (p.parent == Some(own) && p.name == "strace")
+}
+
+/// `root` and every process below it, found breadth-first over the parent
+/// links of `processes`.
+fn descendants(processes: &[ProcessInfo], root: i32) -> HashSet<i32> {
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for p in processes {
+        if let Some(parent) = p.parent {
+            children.entry(parent).or_default().push(p.pid);
+        }
+    }
+    let mut found = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(pid) = queue.pop_front() {
+        for &child in children.get(&pid).into_iter().flatten() {
+            if found.insert(child) {
+                queue.push_back(child);
+            }
+        }
+    }
+    found
+}
+
+impl ProcessColumn {
+    fn header(self) -> &'static str {
+        match self {
+            ProcessColumn::Pid => "PID",
+            ProcessColumn::Threads => "THR",
+            ProcessColumn::User => "USER",
+            ProcessColumn::Memory => "MEM",
+            ProcessColumn::Name => "NAME",
+            ProcessColumn::Cgroup => "CGROUP",
+            ProcessColumn::Cwd => "CWD",
+            ProcessColumn::Capabilities => "CAPS",
+            ProcessColumn::Command => "[CMD]",
+        }
+    }
+
+    /// Width the column is padded to, or `None` if it takes the rest of the line.
+    fn width(self) -> Option<usize> {
+        match self {
+            ProcessColumn::Pid => Some(7),
+            ProcessColumn::Threads => Some(4),
+            ProcessColumn::User => Some(10),
+            ProcessColumn::Memory => Some(7),
+            // Kernel process names are at most 15 bytes.
+            ProcessColumn::Name => Some(15),
+            ProcessColumn::Cgroup => Some(24),
+            ProcessColumn::Cwd => Some(24),
+            ProcessColumn::Capabilities => Some(24),
+            ProcessColumn::Command => None,
+        }
+    }
+
+    /// Pads `text` to the column width, right-aligning numeric columns.
+    fn pad(self, text: &str) -> String {
+        match (self, self.width()) {
+            (ProcessColumn::Pid | ProcessColumn::Threads | ProcessColumn::Memory, Some(w)) => {
+                format!("{:>w$}", text, w = w)
+            }
+            (_, Some(w)) => format!("{:<w$}", truncate_to_width(text, w), w = w),
+            (_, None) => text.to_string(),
+        }
+    }
+
+    fn cell(self, p: &ProcessInfo) -> String {
+        let text = match self {
+            ProcessColumn::Pid => p.pid.to_string(),
+            ProcessColumn::Threads => p
+                .thread_count
+                .map_or_else(|| "?".to_string(), |count| count.to_string()),
+            ProcessColumn::User => p.user.clone(),
+            ProcessColumn::Memory => format_bytes(p.memory),
+            ProcessColumn::Name => p.name.clone(),
+            ProcessColumn::Cgroup => p
+                .cgroup
+                .as_deref()
+                .map_or_else(|| "?".to_string(), cgroup_label),
+            ProcessColumn::Cwd => p
+                .cwd
+                .as_ref()
+                .map_or_else(|| "?".to_string(), |cwd| cwd.display().to_string()),
+            ProcessColumn::Capabilities => p.capabilities.map_or_else(
+                || "?".to_string(),
+                |caps| capabilities::summary(caps.effective),
+            ),
+            ProcessColumn::Command => format!("[{}]", p.cmd),
+        };
+        self.pad(&text)
+    }
+}
+
+/// Ordering applied to the process list.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProcessSort {
+    Pid,
+    Threads,
+}
+
+impl ProcessSort {
+    fn next(self) -> Self {
+        match self {
+            ProcessSort::Pid => ProcessSort::Threads,
+            ProcessSort::Threads => ProcessSort::Pid,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ProcessSort::Pid => "by PID",
+            ProcessSort::Threads => "by threads",
+        }
+    }
+}
+
+/// One entry of the raw strace timeline.
+#[derive(Clone)]
+enum TimelineEntry {
+    /// A raw strace line, including any `[pid N]` prefix.
+    Line(String),
+    /// Marks the point where a PID first shows up in the trace.
+    NewPid(i32),
+    /// A note added with `j`, with its time into the session.
+    Note(Duration, String),
+}
+
+/// Least width of the stats table's name column, and the width of each of
+/// the others.
+const STATS_NAME_WIDTH: u16 = 20;
+const STATS_COLUMN_WIDTH: u16 = 10;
+
+/// Column of the syscall stats table, which is also what it is sorted by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StatsColumn {
+    Name,
+    Count,
+    Errors,
+    ErrorRate,
+    AvgTime,
+}
+
+impl StatsColumn {
+    const ALL: [StatsColumn; 5] = [
+        StatsColumn::Name,
+        StatsColumn::Count,
+        StatsColumn::Errors,
+        StatsColumn::ErrorRate,
+        StatsColumn::AvgTime,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            StatsColumn::Name => "SYSCALL",
+            StatsColumn::Count => "COUNT",
+            StatsColumn::Errors => "ERRORS",
+            StatsColumn::ErrorRate => "ERROR %",
+            StatsColumn::AvgTime => "AVG TIME",
+        }
+    }
+
+    fn width(self) -> Constraint {
+        match self {
+            StatsColumn::Name => Constraint::Min(STATS_NAME_WIDTH),
+            _ => Constraint::Length(STATS_COLUMN_WIDTH),
+        }
+    }
+
+    /// The column `step` places to the right, wrapping around.
+    fn shifted(self, step: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        let index = Self::ALL.iter().position(|&c| c == self).unwrap_or(0) as isize;
+        Self::ALL[(index + step).rem_euclid(len) as usize]
+    }
+}
+
+/// One row of the stats table, derived from the per-syscall maps.
+struct StatsRow {
+    name: String,
+    count: u64,
+    errors: u64,
+    // Mean time spent in the call, if `-T` durations were seen for it.
+    avg_time: Option<Duration>,
+}
+
+impl StatsRow {
+    fn error_rate(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.errors as f64 * 100.0 / self.count as f64
+        }
+    }
+}
+
+/// What a trace session follows.
+#[derive(Clone)]
+enum TraceTarget {
+    /// An already running process, traced with `-p`.
+    Attach(i32),
+    /// Every member of a process group or session, each attached with its own
+    /// `-p`, so strace tags their lines with `[pid N]`.
+    Group {
+        kind: GroupKind,
+        id: i32,
+        members: Vec<i32>,
+    },
+    /// A command line started by strace itself, traced from its first syscall.
+    Launch(Vec<String>),
+    /// strace output captured elsewhere, read from a file or, given no path,
+    /// from standard input.
+    Log(Option<PathBuf>),
+    /// The data of an earlier instance's session, resumed from its
+    /// checkpoint: what it traced and when the checkpoint was written.
+    Checkpoint {
+        description: String,
+        saved_at: String,
+    },
+}
+
+/// A set of processes the kernel keeps together, traced as a whole to follow a
+/// pipeline or a job.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GroupKind {
+    ProcessGroup,
+    Session,
+}
+
+impl GroupKind {
+    fn label(self) -> &'static str {
+        match self {
+            GroupKind::ProcessGroup => "process group",
+            GroupKind::Session => "session",
+        }
+    }
+}
+
+/// What collects the syscalls of attached processes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TraceBackend {
+    /// strace, which sees every call in full. Launched commands, groups and
+    /// the comparison pane always use it.
+    Strace,
+    /// Counts kept in the kernel by eBPF programs, at a fraction of the
+    /// overhead, in builds with the `bpf` feature; see [`counting`].
+    Bpf,
+    /// No tracing at all: coarse activity counters polled from /proc, for
+    /// where neither can run; see [`procstats`].
+    Proc,
+}
+
+/// Options that shape the strace command line.
+#[derive(Clone)]
+struct TraceOptions {
+    // `-f`: also trace forked children and threads.
+    follow_forks: bool,
+    // Expression passed as `-e trace=...`.
+    trace_filter: String,
+    // `-k`: print the user-space stack after each call.
+    stack_traces: bool,
+    // Arguments given with `A`, passed through verbatim after the others.
+    extra_args: Vec<String>,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            follow_forks: true,
+            trace_filter: "all".to_string(),
+            stack_traces: false,
+            extra_args: Vec::new(),
+        }
+    }
+}
+
+impl TraceOptions {
+    /// Builds the full strace command line (binary first) used to trace `target`.
+    ///
+    /// `output` names the file passed to `-o`; without it the trace goes to stderr.
+    fn command(&self, target: &TraceTarget, output: Option<&Path>) -> Vec<String> {
+        let mut args = vec!["strace".to_string()];
+        let attached: &[i32] = match target {
+            TraceTarget::Attach(pid) => std::slice::from_ref(pid),
+            TraceTarget::Group { members, .. } => members,
+            _ => &[],
+        };
+        for pid in attached {
+            args.push("-p".to_string());
+            args.push(pid.to_string());
+        }
+        args.push("-e".to_string());
+        args.push(format!("trace={}", self.trace_filter));
+        // Wall-clock time of each call, and the time spent in it for the
+        // blocked-vs-running estimate.
+        args.push("-tt".to_string());
+        args.push("-T".to_string());
+        if self.follow_forks {
+            args.push("-f".to_string());
+        }
+        if self.stack_traces {
+            args.push("-k".to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        if let Some(output) = output {
+            args.push("-o".to_string());
+            args.push(output.display().to_string());
+        }
+        if let TraceTarget::Launch(argv) = target {
+            args.push("--".to_string());
+            args.extend(argv.iter().cloned());
+        }
+        args
+    }
+}
+
+/// What an export writes.
+#[derive(Clone, Copy)]
+enum ExportKind {
+    /// Per-syscall counts and categories as CSV.
+    Summary,
+    /// Every raw line kept in the timeline, oldest first.
+    Timeline,
+    /// The report of what changed since the baseline, as text.
+    Changes,
+}
+
+impl ExportKind {
+    fn label(self) -> &'static str {
+        match self {
+            ExportKind::Summary => "summary",
+            ExportKind::Timeline => "timeline",
+            ExportKind::Changes => "changes",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportKind::Summary => "csv",
+            ExportKind::Timeline => "log",
+            ExportKind::Changes => "txt",
+        }
+    }
+}
+
+/// What a text prompt is asking for.
+#[derive(Clone, Copy)]
+enum PromptKind {
+    CaptureDuration,
+    TraceFilter,
+    RestartConfirm,
+    StopOrphans,
+    ResumeCheckpoint,
+    AttachScope,
+    LaunchCommand,
+    FdFilter,
+    NormalSet,
+    Note,
+    ManSearch,
+    StraceArgs,
+    StraceArgsConfirm,
+    ExportPath(ExportKind),
+}
+
+impl PromptKind {
+    fn title(self) -> &'static str {
+        match self {
+            PromptKind::CaptureDuration => {
+                "Capture duration, e.g. 30s or 5m (empty to clear) | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::TraceFilter => {
+                "strace -e trace= expression, e.g. all, %file, read,write | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::RestartConfirm => {
+                "Restart strace: keep captured data? [Y/n] | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::StopOrphans => {
+                "strace processes left by an earlier run are still tracing (see events): stop them? [Y/n] | Enter: OK | Esc: Leave them"
+            }
+            PromptKind::ResumeCheckpoint => {
+                "Show the session checkpointed before the last exit again? [Y/n: discard] | Enter: OK | Esc: Later"
+            }
+            PromptKind::AttachScope => {
+                "Trace p: this process, g: its process group, s: its session (empty: p) | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::LaunchCommand => {
+                "Command to launch and trace, e.g. ls -l '/tmp/my dir' | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::FdFilter => {
+                "File descriptor to follow in the timeline, e.g. 7 (empty to clear) | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::NormalSet => {
+                "File of known-normal syscalls, e.g. a summary export (empty to clear) | Enter: Load | Esc: Cancel"
+            }
+            PromptKind::Note => {
+                "Note for the timeline, e.g. clicked the submit button | Enter: Add | Esc: Cancel"
+            }
+            PromptKind::ManSearch => {
+                "Search the manual page for | Enter: Find | Esc: Cancel"
+            }
+            PromptKind::StraceArgs => {
+                "Extra strace arguments, passed verbatim, e.g. -yy -X raw (empty: none) | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::StraceArgsConfirm => {
+                "Use this strace command from the next restart? [Y/n] | Enter: OK | Esc: Cancel"
+            }
+            PromptKind::ExportPath(ExportKind::Summary) => {
+                "Export syscall summary to | Enter: Write | Esc: Cancel"
+            }
+            PromptKind::ExportPath(ExportKind::Timeline) => {
+                "Export raw timeline to | Enter: Write | Esc: Cancel"
+            }
+            PromptKind::ExportPath(ExportKind::Changes) => {
+                "Export the report of what changed to | Enter: Write | Esc: Cancel"
+            }
+        }
+    }
+}
+
+/// A single-line text prompt drawn over the current screen.
+struct Prompt {
+    kind: PromptKind,
+    input: String,
+    // Shown above the input, e.g. why a previous attempt failed.
+    error: Option<String>,
+    // Neutral information shown above the input.
+    detail: Option<String>,
+}
+
+/// Syscalls a program is known to make when it behaves normally.
+struct NormalSet {
+    /// The file they were loaded from.
+    path: String,
+    names: HashSet<String>,
+}
+
+/// The memory map popup of the selection screen.
+struct MapsView {
+    pid: i32,
+    name: String,
+    /// Every mapping is listed instead of the summary.
+    expanded: bool,
+    scroll: u16,
+}
+
+/// What a PID seen in the trace is, for the PID picker.
+enum PidKind {
+    /// A thread of the traced process (its main thread included), by name.
+    Thread(String),
+    /// Another process, such as a followed child, by command name.
+    Process(String),
+    /// Gone before it could be looked up.
+    Unknown,
+}
+
+impl PidKind {
+    /// Looks `pid` up in `/proc`: among the threads of `target` first, since
+    /// with `-f` a thread's TID tags its lines just like a child's PID.
+    fn resolve(target: i32, pid: i32) -> Self {
+        if let Ok(name) = procfs::thread_name(target, pid) {
+            PidKind::Thread(name)
+        } else if let Ok(name) = procfs::comm(pid) {
+            PidKind::Process(name)
+        } else {
+            PidKind::Unknown
+        }
+    }
 }
 
-/// The two primary screens.
+/// A message shown in a popup until a key is pressed.
+struct Summary {
+    title: &'static str,
+    text: String,
+}
+
+/// The primary screens.
 enum AppMode {
     ProcessSelection,
     SyscallMonitoring,
+    // Polling for a new process matching `watch_name`.
+    Watching,
 }
 
 /// The main application state.
@@ -46,445 +676,7145 @@ struct App {
     mode: AppMode,
     // Process selection fields.
     processes: Vec<ProcessInfo>,
-    filtered_processes: Vec<ProcessInfo>,
+    // Indices into `processes` of the entries passing the filter, in display order.
+    filtered_processes: Vec<usize>,
+    // Hides this inspector and the strace processes it started from the list.
+    hide_own: bool,
+    // Narrows the list to this process and its descendants.
+    tree_root: Option<i32>,
+    // Memory maps read since the process list was last refreshed, and the
+    // popup summarizing one of them.
+    memory_maps: HashMap<i32, Vec<procfs::Mapping>>,
+    maps_view: Option<MapsView>,
+    // The listed processes ranked by activity, while Ctrl+B shows them.
+    leaderboard: Option<leaderboard::Leaderboard>,
+    /// The manual page popup opened with `M`.
+    man_page: Option<manpage::ManPage>,
+    // PIDs owning each listening port, scanned on first use of a `:PORT`
+    // filter term since the process list was last refreshed.
+    port_owners: Option<HashMap<u16, HashSet<i32>>>,
     process_filter: String,
     selected_process: usize,
-    // Syscall monitoring fields.
+    process_sort: ProcessSort,
+    // Kept across draws so ratatui can scroll the highlight into view, with
+    // the first visible row tracked alongside for the scroll indicator.
+    process_list_state: ListState,
+    process_list_offset: usize,
+    // Process list columns in display order with their visibility, and the
+    // highlighted row of the column editor while it is open.
+    process_columns: Vec<(ProcessColumn, bool)>,
+    column_editor: Option<usize>,
+    // Watch mode: name to wait for and the PIDs that existed when watching began.
+    watch_name: String,
+    watch_known_pids: HashSet<i32>,
+    // Syscall monitoring fields. `target_pid` is 0 while a launched command's
+    // PID is not yet known.
+    trace_target: TraceTarget,
     target_pid: i32,
     target_process_name: String,
+    // How the target ended, once it has.
+    exit_status: Option<String>,
+    // Summary of the last session shown over the process list until a key is pressed.
+    summary: Option<Summary>,
+    // Whether strace has confirmed the attach, and its last error message.
+    attached: bool,
+    error_banner: Option<String>,
+    // Whether errors are also appended to the error log (`error_log`).
+    error_log: bool,
+    // When the live session was last checkpointed, and the file it goes to;
+    // the checkpoint offered at startup waits in `pending_checkpoint`.
+    checkpoint_saved: Instant,
+    checkpoint_path: Option<PathBuf>,
+    pending_checkpoint: Option<PathBuf>,
+    // What the target is doing right now, from `/proc/<pid>/syscall`.
+    current_syscall: String,
     unique_syscalls: HashSet<String>,
-    detailed_syscalls: HashSet<String>,
+    // Raw lines in arrival order, oldest first, and the PIDs seen so far.
+    timeline: VecDeque<TimelineEntry>,
+    timeline_pids: HashSet<i32>,
+    // Notes added with `j`, oldest first, with their time into the session.
+    // Kept apart from the timeline so none are lost when it is trimmed.
+    notes: Vec<(Duration, String)>,
+    // The unfinished first half of the call each PID is in, awaiting its
+    // resumed half (keyed by `None` for lines without a PID tag).
+    pending_calls: HashMap<Option<i32>, String>,
     show_detailed: bool,
+    // The dashboard view: the syscall list on top of a live timeline, with
+    // the share of the height the list gets.
+    dashboard: bool,
+    dashboard_split: u16,
     syscall_log: Vec<String>,
+    syscall_counts: HashMap<String, u64>,
+    // Time spent inside each syscall (from `-T`), the PIDs those calls came
+    // from, and when the first syscall line arrived; together they give the
+    // blocked-vs-running estimate.
+    syscall_time: HashMap<String, Duration>,
+    timed_pids: HashSet<Option<i32>>,
+    first_line_at: Option<Instant>,
+    syscall_order: SyscallOrder,
+    // Whether the count-sorted list reorders as counts change; otherwise it
+    // keeps the order it was last sorted into with `u` (or when the first
+    // counts came in), and syscalls seen since then follow in first-seen
+    // order. Whether the counts have moved on since is noted every tick.
+    auto_sort: bool,
+    frozen_order: Vec<String>,
+    order_stale: bool,
+    // What syscall name colors stand for, toggled with `C`.
+    color_mode: ColorMode,
+    // Whether the selected timeline line is shown in full in a popup.
+    expand_line: bool,
+    // Distinct call stacks (from `-k`) seen for each syscall with how often
+    // each occurred, the call whose frames are being read, and whether the
+    // stacks of the highlighted syscall are shown in a popup.
+    stacks: HashMap<String, HashMap<Vec<String>, u64>>,
+    stack_call: Option<(String, Vec<String>)>,
+    show_callers: bool,
+    // Syscalls kept at the top of the list and stats table whatever the sort.
+    pinned: HashSet<String>,
+    // Syscalls known to be normal for the program, loaded with `N` from an
+    // earlier export, and whether the views show only the others.
+    normal_set: Option<NormalSet>,
+    anomalies_only: bool,
+    // Counts snapshotted with `z`; while set, the list shows calls made since,
+    // and whether the report of what changed since is shown in a popup.
+    baseline: Option<Baseline>,
+    show_changes: bool,
+    // Captured calls grouped by the file or socket they touched, and the
+    // scroll position of their popup while it is open.
+    resources: Resources,
+    resources_scroll: Option<u16>,
+    // Lines of the summary shown after detaching, until a key is pressed.
+    session_report: Option<Vec<String>>,
+    // An export whose path was confirmed, written on the next pass of the
+    // event loop so its "Writing" notice is drawn first.
+    pending_export: Option<(ExportKind, String)>,
+    // Per-category summary panel and the category the list is narrowed to.
+    show_categories: bool,
+    category_filter: Option<Category>,
+    // Highlighted legend entry while the category panel has keyboard focus.
+    legend_focus: Option<usize>,
+    // How often each errno was returned and by which syscalls, the errno
+    // panel with its highlighted entry while focused, and the errno the views
+    // are narrowed to.
+    errno_counts: HashMap<String, u64>,
+    // Failed calls per syscall.
+    error_counts: HashMap<String, u64>,
+    errno_syscalls: HashMap<String, HashSet<String>>,
+    show_errnos: bool,
+    errno_focus: Option<usize>,
+    errno_filter: Option<String>,
+    // File descriptor the timeline is narrowed to, chosen with `d`.
+    fd_filter: Option<i32>,
+    // Calls per syscall for each traced PID, the PID the views are locked to,
+    // and the highlighted row of the PID picker while it is open.
+    pid_syscall_counts: HashMap<i32, HashMap<String, u64>>,
+    // What each of those PIDs is, resolved when it first shows up: a thread
+    // of the traced process or a child.
+    pid_kinds: HashMap<i32, PidKind>,
+    pid_lock: Option<i32>,
+    pid_picker: Option<usize>,
+    // Display cap for the count-sorted view (0 for none), and whether it is
+    // lifted.
+    top_n: usize,
+    show_all: bool,
+    // Show timeline timestamps relative to the first one seen, rather than as
+    // wall-clock times; and that first timestamp, in seconds.
+    relative_times: bool,
+    first_timestamp: Option<f64>,
+    // Keep only 1 in `sample_rate` raw lines; names are always counted.
+    sample_rate: u64,
+    lines_seen: u64,
     // Filtering mode for syscalls.
     filter_mode: bool,
     syscall_filter: String,
+    // Whether every space-separated term of the filter must match, rather
+    // than any of them.
+    filter_all_terms: bool,
+    // Whether the filter shows the syscalls its query does not match,
+    // toggled with Ctrl+V while filtering.
+    filter_inverted: bool,
+    // The query in use when filtering last ended, restored with Ctrl+Z.
+    last_syscall_filter: Option<String>,
     filtered_syscalls: Vec<String>,
-    // Child process running strace and a channel for its output.
+    // Fuzzy match score of each entry in `filtered_syscalls`, and whether the
+    // list shows them (a debugging aid for the ranking, toggled with Ctrl+D).
+    filter_scores: HashMap<String, i64>,
+    show_filter_scores: bool,
+    selected_syscall: usize,
+    syscall_list_state: ListState,
+    syscall_list_offset: usize,
+    // The stats table shown in place of the list view, its sort column and
+    // its scroll state.
+    show_stats: bool,
+    stats_sort: StatsColumn,
+    stats_state: TableState,
+    // How many columns after the name column are scrolled off to the left,
+    // and how many of them fit beside it when last drawn.
+    stats_hscroll: usize,
+    stats_columns_fit: usize,
+    // Child process running strace, the command it was started with, and a
+    // channel for its output.
+    strace_command: Vec<String>,
+    show_command: bool,
+    // Options for the next strace started, and whether they differ from the running one.
+    trace_options: TraceOptions,
+    options_changed: bool,
+    // The backend asked for, and the one counting the running trace's
+    // syscalls when that is not strace.
+    backend: TraceBackend,
+    counter: Option<Box<dyn counting::Counter>>,
+    // The /proc counters of the process, when it is watched instead of traced.
+    proc_stats: Option<procstats::ProcStats>,
     strace_child: Option<Child>,
     strace_receiver: Option<Receiver<String>>,
+    // When the running strace was started and the error messages it has printed.
+    strace_started: Option<Instant>,
+    // When strace last produced any line, syscall or not.
+    last_line_at: Option<Instant>,
+    strace_errors: Vec<String>,
+    // FIFO a launched command's trace is written to, removed when tracing stops.
+    trace_fifo: Option<PathBuf>,
+    // Recent notable actions, oldest first.
+    events: VecDeque<String>,
+    show_events: bool,
+    // Active text prompt, if any; it receives all key presses.
+    prompt: Option<Prompt>,
+    // The selected process, with its process group and session, while the
+    // attach scope prompt is open.
+    pending_attach: Option<(ProcessInfo, i32, i32)>,
+    // Extra strace arguments waiting for their command to be confirmed.
+    pending_strace_args: Option<Vec<String>>,
+    // Process marked with Ctrl+X to trace next to the next one attached to,
+    // the comparison pane it then runs in, whether that pane has the focus
+    // (for filtering and, unsynchronized, scrolling) and whether scrolling
+    // moves both panes together.
+    compare_candidate: Option<(i32, String)>,
+    compare: Option<compare::ComparePane>,
+    compare_focus: bool,
+    compare_sync: bool,
+    // Timed capture: length applied on each attach, and when the current one ends.
+    capture_duration: Option<Duration>,
+    capture_deadline: Option<Instant>,
+    // Auto-detach after this long without input, and when input last came.
+    idle_timeout: Option<Duration>,
+    last_input: Instant,
+    // Read-only mode: refuse to signal processes.
+    safe_mode: bool,
+    // Processes never signalled even outside safe mode.
+    protection: Protection,
+    // Whether the terminal reports mouse events to us; while off, the terminal
+    // handles the mouse itself so text can be selected and copied.
+    mouse_capture: bool,
+    // Saved per-program profiles, the one applied to the current session, and
+    // the settings it replaced, restored when the session ends.
+    profiles: BTreeMap<String, Profile>,
+    active_profile: Option<String>,
+    settings_before_profile: Option<Profile>,
+    // Save each session's settings as its program's profile when it ends.
+    remember_settings: bool,
+    // Limit on running strace processes, from the config.
+    max_tracers: usize,
+    // Alerts for the target exiting and for the first call of each syscall in
+    // `notify_syscalls`; `notified_syscalls` holds those already alerted on.
+    notifier: notify::Notifier,
+    notify_syscalls: HashSet<String>,
+    notified_syscalls: HashSet<String>,
+    // The first-run tutorial overlay, and whether it has ever been dismissed.
+    show_tutorial: bool,
+    tutorial_seen: bool,
+    // Where `--record` writes the first session's lines.
+    recorder: Option<replay::Recorder>,
+    // Keys bound to the rebindable actions.
+    keymap: KeyMap,
+    // Write the next frame drawn to a snapshot file.
+    snapshot_requested: bool,
+    // The config file as last applied, the warnings its last parse gave, its
+    // modification time then, a newer modification time waiting to settle
+    // before it is applied, and when the file was last checked.
+    file_config: Config,
+    config_warnings: Vec<String>,
+    config_modified: Option<SystemTime>,
+    config_changing: Option<SystemTime>,
+    config_checked: Instant,
+    // Set when something visible changed since the last draw.
+    dirty: bool,
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(config: &Config, state: &UiState) -> Self {
         let processes = Self::get_processes();
-        Self {
+        let (keymap, key_warnings) = KeyMap::parse(
+            config
+                .keys
+                .iter()
+                .map(|(action, key)| (action.as_str(), key.as_str())),
+        );
+        let mut app = Self {
             mode: AppMode::ProcessSelection,
-            filtered_processes: processes.clone(),
+            filtered_processes: (0..processes.len())
+                .filter(|&i| !(config.hide_self && is_own_process(&processes[i])))
+                .collect(),
+            hide_own: config.hide_self,
+            tree_root: None,
+            memory_maps: HashMap::new(),
+            maps_view: None,
+            leaderboard: None,
+            man_page: None,
+            port_owners: None,
             processes,
             process_filter: String::new(),
             selected_process: 0,
+            process_sort: ProcessSort::Pid,
+            process_list_state: ListState::default(),
+            process_list_offset: 0,
+            process_columns: state.process_columns.clone(),
+            column_editor: None,
+            watch_name: String::new(),
+            watch_known_pids: HashSet::new(),
+            trace_target: TraceTarget::Attach(0),
             target_pid: 0,
             target_process_name: String::new(),
+            exit_status: None,
+            summary: None,
+            resources: Resources::default(),
+            resources_scroll: None,
+            session_report: None,
+            pending_export: None,
+            attached: false,
+            error_banner: None,
+            error_log: config.error_log,
+            checkpoint_saved: Instant::now(),
+            checkpoint_path: None,
+            pending_checkpoint: None,
+            current_syscall: String::new(),
             unique_syscalls: HashSet::new(),
-            detailed_syscalls: HashSet::new(),
-            show_detailed: false,
+            timeline: VecDeque::new(),
+            notes: Vec::new(),
+            timeline_pids: HashSet::new(),
+            pending_calls: HashMap::new(),
+            show_detailed: state.show_detailed,
+            dashboard: state.dashboard,
+            dashboard_split: state.dashboard_split,
             syscall_log: Vec::new(),
+            syscall_counts: HashMap::new(),
+            syscall_time: HashMap::new(),
+            timed_pids: HashSet::new(),
+            first_line_at: None,
+            syscall_order: state.syscall_order,
+            auto_sort: state.auto_sort,
+            frozen_order: Vec::new(),
+            order_stale: false,
+            color_mode: state.color_mode,
+            expand_line: false,
+            stacks: HashMap::new(),
+            stack_call: None,
+            show_callers: false,
+            show_categories: false,
+            category_filter: None,
+            pinned: HashSet::new(),
+            normal_set: None,
+            anomalies_only: false,
+            baseline: None,
+            show_changes: false,
+            legend_focus: None,
+            errno_counts: HashMap::new(),
+            error_counts: HashMap::new(),
+            errno_syscalls: HashMap::new(),
+            show_errnos: false,
+            errno_focus: None,
+            errno_filter: None,
+            fd_filter: None,
+            pid_syscall_counts: HashMap::new(),
+            pid_kinds: HashMap::new(),
+            pid_lock: None,
+            pid_picker: None,
+            top_n: config.top_n,
+            show_all: state.show_all,
+            relative_times: state.relative_times,
+            first_timestamp: None,
+            sample_rate: 1,
+            lines_seen: 0,
             filter_mode: false,
             syscall_filter: String::new(),
+            filter_all_terms: config.filter_all_terms,
+            filter_inverted: false,
+            last_syscall_filter: None,
             filtered_syscalls: Vec::new(),
+            filter_scores: HashMap::new(),
+            show_filter_scores: false,
+            selected_syscall: 0,
+            syscall_list_state: ListState::default(),
+            syscall_list_offset: 0,
+            show_stats: false,
+            stats_sort: StatsColumn::Count,
+            stats_state: TableState::default(),
+            stats_hscroll: 0,
+            stats_columns_fit: StatsColumn::ALL.len() - 1,
+            strace_command: Vec::new(),
+            show_command: false,
+            trace_options: TraceOptions::default(),
+            backend: TraceBackend::Strace,
+            counter: None,
+            proc_stats: None,
+            options_changed: false,
             strace_child: None,
             strace_receiver: None,
+            strace_started: None,
+            last_line_at: None,
+            strace_errors: Vec::new(),
+            trace_fifo: None,
+            events: VecDeque::new(),
+            show_events: state.show_events,
+            prompt: None,
+            pending_attach: None,
+            pending_strace_args: None,
+            capture_duration: None,
+            compare_candidate: None,
+            compare: None,
+            compare_focus: false,
+            compare_sync: true,
+            capture_deadline: None,
+            idle_timeout: (config.idle_detach_minutes > 0)
+                .then(|| Duration::from_secs(config.idle_detach_minutes * 60)),
+            last_input: Instant::now(),
+            safe_mode: config.safe_mode,
+            protection: Protection::new(&config.protected),
+            mouse_capture: config.mouse_capture,
+            profiles: Profile::load_all(),
+            active_profile: None,
+            settings_before_profile: None,
+            remember_settings: config.remember_settings,
+            max_tracers: config.max_tracers,
+            notifier: notify::Notifier::new(config),
+            notify_syscalls: config.notify_syscalls.iter().cloned().collect(),
+            notified_syscalls: HashSet::new(),
+            recorder: None,
+            show_tutorial: !state.tutorial_seen,
+            tutorial_seen: state.tutorial_seen,
+            keymap,
+            snapshot_requested: false,
+            // The file itself, without command-line overrides such as --safe.
+            file_config: Config::load(),
+            config_warnings: config
+                .warnings
+                .iter()
+                .cloned()
+                .chain(key_warnings)
+                .collect(),
+            config_modified: Config::modified(),
+            config_changing: None,
+            config_checked: Instant::now(),
+            dirty: true,
+        };
+        for warning in app.config_warnings.clone() {
+            app.report_error(warning);
         }
+        app
     }
 
-    fn process_strace_line(&mut self, line: &str) {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || !trimmed.chars().next().unwrap_or(' ').is_alphabetic() {
-            return;
+    /// Captures the UI settings worth restoring on the next run.
+    fn ui_state(&self) -> UiState {
+        UiState {
+            show_detailed: self.show_detailed,
+            dashboard: self.dashboard,
+            dashboard_split: self.dashboard_split,
+            syscall_order: self.syscall_order,
+            auto_sort: self.auto_sort,
+            color_mode: self.color_mode,
+            tutorial_seen: self.tutorial_seen,
+            show_events: self.show_events,
+            show_all: self.show_all,
+            relative_times: self.relative_times,
+            process_columns: self.process_columns.clone(),
         }
-        if let Some(idx) = trimmed.find('(') {
-            let name = trimmed[..idx].to_string();
-            if self.unique_syscalls.insert(name.clone()) {
-                self.syscall_log.push(name);
+    }
+
+    /// Turns mouse capture on or off, leaving the mouse to the terminal (for
+    /// selecting text) while it is off.
+    fn set_mouse_capture(&mut self, enabled: bool) {
+        let result = if enabled {
+            execute!(std::io::stdout(), EnableMouseCapture)
+        } else {
+            execute!(std::io::stdout(), DisableMouseCapture)
+        };
+        match result {
+            Ok(()) => {
+                self.mouse_capture = enabled;
+                self.log_event(format!(
+                    "Mouse capture {}",
+                    if enabled { "enabled" } else { "disabled" }
+                ));
             }
+            Err(err) => self.report_error(format!("Failed to toggle mouse capture: {}", err)),
         }
-        self.detailed_syscalls.insert(trimmed.to_string());
     }
 
-    /// Retrieves running processes using sysinfo.
-    fn get_processes() -> Vec<ProcessInfo> {
-        let mut system = System::new_all();
-        system.refresh_all();
-        let mut processes = Vec::new();
-        for (pid, process) in system.processes() {
-            processes.push(ProcessInfo {
-                pid: pid.as_u32() as i32,
-                name: process.name().to_string(),
-                cmd: process.cmd().join(" "),
-            });
+    /// Records a timestamped entry in the event log, dropping the oldest when full.
+    fn log_event(&mut self, message: impl Into<String>) {
+        if self.events.len() >= MAX_EVENTS {
+            self.events.pop_front();
         }
-        processes.sort_by(|a, b| a.pid.cmp(&b.pid));
-        processes
+        self.events
+            .push_back(format!("{} {}", clock_time(), message.into()));
+        self.dirty = true;
     }
 
-    /// Updates the filtered process list based on the current filter string.
-    fn update_filtered_processes(&mut self) {
-        if self.process_filter.is_empty() {
-            self.filtered_processes = self.processes.clone();
-        } else {
-            self.filtered_processes = self
-                .processes
-                .iter()
-                .filter(|p| {
-                    p.name
-                        .to_lowercase()
-                        .contains(&self.process_filter.to_lowercase())
-                        || p.cmd
-                            .to_lowercase()
-                            .contains(&self.process_filter.to_lowercase())
-                })
-                .cloned()
-                .collect();
+    /// Shows a recoverable error in the banner and the event log, and
+    /// appends it to the error log when that is enabled.
+    fn report_error(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.error_banner = Some(message.clone());
+        self.log_event(message.clone());
+        if !self.error_log {
+            return;
         }
-        if self.selected_process >= self.filtered_processes.len() {
-            self.selected_process = 0;
+        let written = Config::error_log_path()
+            .ok_or_else(|| std::io::Error::other("no config directory"))
+            .and_then(|path| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .mode(0o600)
+                    .open(path)?;
+                writeln!(file, "{} {}", log_time(), message)
+            });
+        if let Err(err) = written {
+            // Once is enough; the errors still reach the event log.
+            self.error_log = false;
+            self.log_event(format!("Error log disabled: writing it failed: {}", err));
         }
     }
 
-    /// Updates the filtered syscall list based on the fuzzy query.
-    fn update_filtered_syscalls(&mut self) {
-        let query = self.syscall_filter.clone();
-        if query.is_empty() {
-            self.filtered_syscalls = if self.show_detailed {
-                self.detailed_syscalls.iter().cloned().collect()
-            } else {
-                self.unique_syscalls.iter().cloned().collect()
-            };
+    fn process_strace_line(&mut self, line: &str) {
+        self.dirty = true;
+        self.last_line_at = Some(Instant::now());
+        if self.counter.is_some() {
+            self.process_counted_line(line);
+            return;
+        }
+        if let Some(frame) = parser::stack_frame(line) {
+            if let Some((_, frames)) = &mut self.stack_call {
+                frames.push(frame.to_string());
+            }
+            return;
+        }
+        self.finish_stack();
+        let trimmed = line.trim();
+        if let Some(message) = trimmed.strip_prefix("strace: ") {
+            self.process_strace_message(message);
+            return;
+        }
+        let (pid, rest) = parser::split_prefix(trimmed);
+        if let Some(status) = rest.strip_prefix("+++ ") {
+            self.process_exit_line(pid, status.trim_end_matches('+').trim());
+            return;
+        }
+        let Some(call) = parser::parse_syscall(trimmed) else {
+            return;
+        };
+        self.first_line_at.get_or_insert_with(Instant::now);
+        if self.first_timestamp.is_none() {
+            self.first_timestamp = parser::timestamp(trimmed).map(|(_, seconds)| seconds);
+        }
+        // With `-k` the stack follows the line that completes the call.
+        if call.kind != CallKind::Unfinished {
+            self.stack_call = Some((call.name.to_string(), Vec::new()));
+        }
+        // The duration ends the line that carries the return value, so a split
+        // call's time is on its resumed half.
+        if let Some(spent) = parser::duration(trimmed) {
+            *self.syscall_time.entry(call.name.to_string()).or_default() += spent;
+            self.timed_pids.insert(call.pid);
+        }
+        if let Some(errno) = parser::errno(trimmed) {
+            *self.errno_counts.entry(errno.to_string()).or_insert(0) += 1;
+            *self.error_counts.entry(call.name.to_string()).or_insert(0) += 1;
+            self.errno_syscalls
+                .entry(errno.to_string())
+                .or_default()
+                .insert(call.name.to_string());
+        }
+        // A split call is counted once, with its first half. Its second half is
+        // shown joined to the first, so the timeline line carries the whole call
+        // including its return value and duration.
+        let mut text = trimmed.to_string();
+        if call.kind == CallKind::Resumed {
+            let first = self
+                .pending_calls
+                .remove(&call.pid)
+                .filter(|first| parser::parse_syscall(first).is_some_and(|f| f.name == call.name));
+            if let Some(joined) = first.and_then(|first| parser::join_split_call(&first, trimmed)) {
+                text = joined;
+            }
         } else {
-            if self.show_detailed {
-                self.filtered_syscalls = self
-                    .detailed_syscalls
-                    .iter()
-                    .filter(|s| s.to_lowercase().contains(&query))
-                    .cloned()
-                    .collect();
-            } else {
-                self.filtered_syscalls = self
-                    .unique_syscalls
-                    .iter()
-                    .filter(|s| s.to_lowercase().contains(&query))
-                    .cloned()
-                    .collect();
+            if call.kind == CallKind::Unfinished {
+                self.pending_calls.insert(call.pid, trimmed.to_string());
+            }
+            self.count_calls(call.name, call.pid, 1);
+        }
+        if call.kind != CallKind::Unfinished {
+            // Untagged lines of a launched command whose PID is not known
+            // yet cannot be looked up in /proc, but still have their paths.
+            let pid = call.pid.or_else(|| self.untagged_pid()).unwrap_or(0);
+            let live = !self.reading_log();
+            self.resources.record(pid, call.name, &text, live);
+        }
+        self.lines_seen += 1;
+        if (self.lines_seen - 1).is_multiple_of(self.sample_rate) {
+            if let Some(pid) = call.pid {
+                if self.timeline_pids.insert(pid) {
+                    self.push_timeline(TimelineEntry::NewPid(pid));
+                }
             }
+            self.push_timeline(TimelineEntry::Line(text));
         }
     }
 
-    /// Spawns an `strace` process to monitor syscalls of the given PID.
-    fn start_strace(&mut self, pid: i32) {
-        let mut child = Command::new("strace")
-            .arg("-p")
-            .arg(pid.to_string())
-            .arg("-e")
-            .arg("trace=all")
-            .arg("-f")
-            .stderr(Stdio::piped())
-            .spawn()
-            .expect("Failed to start strace. (Are you root?)");
+    /// Adds `calls` calls of `name` by `pid` (the traced process itself if
+    /// untagged) to the counts, alerting on the first one if asked to.
+    fn count_calls(&mut self, name: &str, pid: Option<i32>, calls: u64) {
+        let name = name.to_string();
+        if self.notify_syscalls.contains(&name)
+            && !self.reading_log()
+            && self.notified_syscalls.insert(name.clone())
+        {
+            self.notifier
+                .notify(&format!("{} called {}", self.target_description(), name));
+        }
+        *self.syscall_counts.entry(name.clone()).or_insert(0) += calls;
+        if let Some(pid) = pid.or_else(|| self.untagged_pid()) {
+            if !self.pid_kinds.contains_key(&pid) && !self.reading_log() {
+                let kind = PidKind::resolve(self.target_pid, pid);
+                self.pid_kinds.insert(pid, kind);
+            }
+            *self
+                .pid_syscall_counts
+                .entry(pid)
+                .or_default()
+                .entry(name.clone())
+                .or_insert(0) += calls;
+        }
+        if self.unique_syscalls.insert(name.clone()) {
+            self.syscall_log.push(name);
+        }
+    }
 
-        let stderr = child.stderr.take().expect("Failed to capture stderr");
-        let (tx, rx) = mpsc::channel();
+    /// Handles a line of a counting backend's output: the totals since its
+    /// previous report, or a message from the backend.
+    fn process_counted_line(&mut self, line: &str) {
+        let Some(counter) = &self.counter else {
+            return;
+        };
+        let name = counter.name();
+        match counter.parse(line) {
+            counting::Output::Attached => {
+                self.process_strace_message(&format!("Process {} attached", self.target_pid))
+            }
+            counting::Output::Calls(syscall, calls) => {
+                self.first_line_at.get_or_insert_with(Instant::now);
+                self.count_calls(syscall, None, calls);
+            }
+            counting::Output::Failures(syscall, failures) => {
+                *self.error_counts.entry(syscall.to_string()).or_insert(0) += failures;
+            }
+            counting::Output::Other("") => {}
+            counting::Output::Other(message) => {
+                self.strace_errors.push(message.to_string());
+                self.report_error(format!("{} backend: {}", name, message));
+            }
+        }
+    }
 
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(l) = line {
-                    let _ = tx.send(l);
-                }
+    /// Records the stack read after the last completed call, if any.
+    fn finish_stack(&mut self) {
+        if let Some((name, frames)) = self.stack_call.take() {
+            if !frames.is_empty() {
+                *self
+                    .stacks
+                    .entry(name)
+                    .or_default()
+                    .entry(frames)
+                    .or_insert(0) += 1;
             }
-        });
+        }
+    }
 
-        self.strace_child = Some(child);
-        self.strace_receiver = Some(rx);
+    /// Appends to the timeline, dropping the oldest entry when full.
+    fn push_timeline(&mut self, entry: TimelineEntry) {
+        if self.timeline.len() >= MAX_TIMELINE {
+            self.timeline.pop_front();
+        }
+        self.timeline.push_back(entry);
     }
 
-    /// Stops the running strace process.
-    fn stop_strace(&mut self) {
-        if let Some(mut child) = self.strace_child.take() {
-            let _ = child.kill();
-            let _ = child.wait();
+    /// Handles an strace terminator line such as `+++ exited with 1 +++` (given
+    /// without the `+++` markers), recording how the target ended.
+    ///
+    /// Lines without a `[pid N]` tag belong to the traced process itself; exits
+    /// of followed children and threads are ignored.
+    fn process_exit_line(&mut self, pid: Option<i32>, status: &str) {
+        if pid.is_some_and(|pid| pid != self.target_pid) {
+            return;
         }
-        self.strace_receiver = None;
+        let exit_status = match status.strip_prefix("exited with ") {
+            Some(code) => format!("exited with code {}", code),
+            None => status.to_string(),
+        };
+        if !self.reading_log() {
+            // A launched command's description already names the program.
+            let message = if self.traces_running_processes() {
+                format!(
+                    "{} ({}) {}",
+                    self.target_description(),
+                    self.target_process_name,
+                    exit_status
+                )
+            } else {
+                format!("{} {}", self.target_description(), exit_status)
+            };
+            self.notifier.notify(&message);
+        }
+        self.exit_status = Some(exit_status);
     }
-}
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Set up terminal.
-    enable_raw_mode()?;
-    let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    /// Handles a diagnostic printed by strace itself rather than a traced syscall.
+    fn process_strace_message(&mut self, message: &str) {
+        if message.starts_with("Process ") && message.ends_with(" attached") {
+            if !self.attached {
+                self.attached = true;
+                self.error_banner = None;
+                self.log_event(format!("strace attached to PID {}", self.target_pid));
+            }
+        } else if message.starts_with("Process ") && message.ends_with(" detached") {
+            // Expected when we stop tracing; nothing to report.
+        } else {
+            self.strace_errors.push(message.to_string());
+            self.report_error(format!("strace: {}", message));
+        }
+    }
+
+    /// Retrieves running processes using sysinfo.
+    fn get_processes() -> Vec<ProcessInfo> {
+        let mut system = System::new_all();
+        system.refresh_all();
+        let mut processes: Vec<ProcessInfo> = system
+            .processes()
+            .iter()
+            .map(|(pid, process)| Self::process_info(&system, pid.as_u32() as i32, process))
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+        processes
+    }
+
+    /// Reads the single process `pid` as [`Self::get_processes`] lists it,
+    /// or `None` if it is gone.
+    fn get_process(pid: i32) -> Option<ProcessInfo> {
+        let mut system = System::new();
+        let sys_pid = sysinfo::Pid::from_u32(pid as u32);
+        if !system.refresh_process(sys_pid) {
+            return None;
+        }
+        system.refresh_users_list();
+        let process = system.process(sys_pid)?;
+        Some(Self::process_info(&system, pid, process))
+    }
+
+    fn process_info(system: &System, pid: i32, process: &sysinfo::Process) -> ProcessInfo {
+        // Arguments may contain newlines, which would make a list row span
+        // several lines; flatten all control characters to spaces.
+        let cmd: String = process
+            .cmd()
+            .join(" ")
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        let user = match process.user_id() {
+            Some(uid) => match system.get_user_by_id(uid) {
+                Some(user) => user.name().to_string(),
+                None => (**uid).to_string(),
+            },
+            None => "?".to_string(),
+        };
+        ProcessInfo {
+            pid,
+            parent: process.parent().map(|parent| parent.as_u32() as i32),
+            name: Self::display_name(process.name(), &cmd, pid),
+            cmd,
+            start_time: process.start_time(),
+            thread_count: procfs::thread_count(pid).ok(),
+            user,
+            memory: process.memory(),
+            cgroup: procfs::cgroup(pid).ok(),
+            cwd: procfs::cwd(pid).ok(),
+            capabilities: procfs::capabilities(pid)
+                .ok()
+                .map(|(effective, permitted)| Capabilities {
+                    effective,
+                    permitted,
+                }),
+        }
+    }
+
+    /// Closes the `--record` file once the session it recorded has ended; a
+    /// recorder that has not seen a line yet waits for the next session.
+    fn finish_recording(&mut self) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        if recorder.lines() == 0 {
+            return;
+        }
+        let flushed = match recorder.flush() {
+            Ok(()) => Ok(format!(
+                "Recorded {} lines to {}; play them back with --input",
+                recorder.lines(),
+                recorder.path().display()
+            )),
+            Err(err) => Err(format!(
+                "Failed to save the recording to {}: {}",
+                recorder.path().display(),
+                err
+            )),
+        };
+        self.recorder = None;
+        match flushed {
+            Ok(message) => self.log_event(message),
+            Err(message) => self.report_error(message),
+        }
+    }
+
+    /// Attaches to `pid` straight away, as given on the command line. If no
+    /// such process exists the selection screen stays up with an error.
+    fn attach_pid(&mut self, pid: i32) {
+        match self.processes.iter().find(|p| p.pid == pid).cloned() {
+            Some(proc) => self.attach(proc),
+            None => {
+                self.report_error(format!(
+                    "PID {} does not exist; choose a process instead",
+                    pid
+                ));
+            }
+        }
+    }
+
+    /// The highlighted process, or `None` if the list is empty. The selection
+    /// is clamped whenever the list changes, but is checked here anyway so a
+    /// stale index can never panic.
+    fn highlighted_process(&self) -> Option<&ProcessInfo> {
+        let index = *self.filtered_processes.get(self.selected_process)?;
+        self.processes.get(index)
+    }
+
+    /// Marks the highlighted process to be traced side by side with the next
+    /// one attached to, or clears the mark if it is already set on it.
+    fn toggle_compare_candidate(&mut self) {
+        let Some(&index) = self.filtered_processes.get(self.selected_process) else {
+            return;
+        };
+        let proc = &self.processes[index];
+        if self
+            .compare_candidate
+            .as_ref()
+            .is_some_and(|(pid, _)| *pid == proc.pid)
+        {
+            self.compare_candidate = None;
+            self.log_event("Comparison cleared");
+            return;
+        }
+        let message = format!(
+            "PID {} ({}) marked for comparison; select the other process",
+            proc.pid, proc.name
+        );
+        self.compare_candidate = Some((proc.pid, proc.name.clone()));
+        self.log_event(message);
+    }
+
+    /// Starts tracing the process marked for comparison in a second pane,
+    /// unless it is the one just attached to.
+    fn start_compare(&mut self, attached: i32) {
+        let Some((pid, name)) = self.compare_candidate.take() else {
+            return;
+        };
+        if pid == attached {
+            self.log_event("Not comparing a process with itself");
+            return;
+        }
+        match compare::ComparePane::start(pid, name.clone(), &self.trace_options, self.max_tracers)
+        {
+            Ok(pane) => {
+                self.log_event(format!(
+                    "Comparing with PID {} ({}) | Tab: switch pane, L: lock scrolling",
+                    pid, name
+                ));
+                self.compare = Some(pane);
+                self.compare_focus = false;
+            }
+            Err(err) => self.report_error(format!(
+                "Failed to start strace for PID {}: {}{}",
+                pid,
+                err,
+                root_hint(&err)
+            )),
+        }
+    }
+
+    /// Counts the comparison pane's new lines and reports its trace ending.
+    fn drain_compare(&mut self) {
+        let Some(pane) = &mut self.compare else {
+            return;
+        };
+        let was_running = pane.running();
+        if !pane.drain() {
+            return;
+        }
+        self.dirty = true;
+        if was_running && !pane.running() {
+            let message = format!(
+                "Comparison trace of PID {} ended: {}",
+                pane.pid,
+                pane.ended.clone().unwrap_or_default()
+            );
+            self.log_event(message);
+        }
+    }
+
+    /// Moves the highlight by `step` rows: in the focused pane, or in both
+    /// while comparing with scrolling locked.
+    fn move_selection(&mut self, step: isize) {
+        let shift = |index: usize| index.saturating_add_signed(step);
+        let pane_focused = self.compare.is_some() && self.compare_focus;
+        if let Some(pane) = &mut self.compare {
+            if self.compare_sync || pane_focused {
+                pane.selected = shift(pane.selected);
+            }
+        }
+        if self.compare_sync || !pane_focused {
+            self.selected_syscall = shift(self.selected_syscall);
+        }
+    }
+
+    /// Picks a non-blank name for a process: its own name, else the first token of
+    /// its command line, else `[pid N]`.
+    fn display_name(name: &str, cmd: &str, pid: i32) -> String {
+        let name = name.trim();
+        if !name.is_empty() {
+            return name.to_string();
+        }
+        match cmd.split_whitespace().next() {
+            Some(first) => first.to_string(),
+            None => format!("[pid {}]", pid),
+        }
+    }
+
+    /// Checks that `pid` still exists before tracing it; processes can exit
+    /// between the list refresh and the key press. If it is gone, says so and
+    /// shows a refreshed list.
+    fn still_running(&mut self, pid: i32) -> bool {
+        let mut system = System::new();
+        if system.refresh_process(sysinfo::Pid::from_u32(pid as u32)) {
+            return true;
+        }
+        if matches!(self.mode, AppMode::ProcessSelection) {
+            self.processes = Self::get_processes();
+            self.update_filtered_processes();
+        } else {
+            self.return_to_selection();
+        }
+        self.report_error(format!("Process {} no longer exists", pid));
+        false
+    }
+
+    /// Starts monitoring the given process, falling back to selection if strace fails.
+    fn attach(&mut self, proc: ProcessInfo) {
+        if !self.still_running(proc.pid) {
+            return;
+        }
+        self.current_syscall = read_current_syscall(proc.pid);
+        // Said up front: the attach is about to fail without root.
+        if !geteuid().is_root() {
+            if let Some(help) = setuid_help(proc.pid) {
+                self.log_event(help);
+            }
+        }
+        match self.begin_session(TraceTarget::Attach(proc.pid), proc.name.clone()) {
+            Ok(()) => {
+                self.log_event(format!("Attached to PID {} ({})", proc.pid, proc.name));
+                self.start_compare(proc.pid);
+            }
+            Err(err) => {
+                self.return_to_selection();
+                self.report_error(format!(
+                    "Failed to start strace for PID {}: {}{}",
+                    proc.pid,
+                    err,
+                    root_hint(&err)
+                ));
+            }
+        }
+    }
+
+    /// Asks whether to trace just the selected process or its whole process
+    /// group or session; processes that are alone in both are attached at once.
+    fn choose_attach_scope(&mut self, proc: ProcessInfo) {
+        if !self.still_running(proc.pid) {
+            return;
+        }
+        let Ok((pgid, sid)) = procfs::group_and_session(proc.pid) else {
+            self.attach(proc);
+            return;
+        };
+        let group = group_members(GroupKind::ProcessGroup, pgid).len();
+        let session = group_members(GroupKind::Session, sid).len();
+        if group <= 1 && session <= 1 {
+            self.attach(proc);
+            return;
+        }
+        self.pending_attach = Some((proc, pgid, sid));
+        self.open_prompt(PromptKind::AttachScope);
+        if let Some(prompt) = &mut self.prompt {
+            prompt.detail = Some(format!(
+                "Process group {}: {} process{} | session {}: {} process{}",
+                pgid,
+                group,
+                if group == 1 { "" } else { "es" },
+                sid,
+                session,
+                if session == 1 { "" } else { "es" }
+            ));
+        }
+    }
+
+    /// Starts monitoring every current member of a process group or session.
+    ///
+    /// `name` is the selected process's, so its profile applies.
+    fn attach_group(&mut self, kind: GroupKind, id: i32, name: String) {
+        let members = group_members(kind, id);
+        if members.is_empty() {
+            self.log_event(format!(
+                "{} {} has no processes that can be traced",
+                kind.label(),
+                id
+            ));
+            return;
+        }
+        let count = members.len();
+        let target = TraceTarget::Group { kind, id, members };
+        match self.begin_session(target, name) {
+            Ok(()) => {
+                self.current_syscall = read_current_syscall(self.target_pid);
+                self.log_event(format!(
+                    "Attached to {} {} ({} processes)",
+                    kind.label(),
+                    id,
+                    count
+                ));
+            }
+            Err(err) => {
+                self.return_to_selection();
+                self.report_error(format!(
+                    "Failed to start strace for {} {}: {}{}",
+                    kind.label(),
+                    id,
+                    err,
+                    root_hint(&err)
+                ));
+            }
+        }
+    }
+
+    /// Starts `argv` under strace and monitors it from its first syscall.
+    fn launch(&mut self, argv: Vec<String>) {
+        let command = shell_join(&argv);
+        let name = Path::new(&argv[0])
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| argv[0].clone());
+        self.current_syscall = String::new();
+        match self.begin_session(TraceTarget::Launch(argv), name) {
+            Ok(()) => self.log_event(format!("Launched {} under strace", command)),
+            Err(err) => {
+                self.return_to_selection();
+                self.report_error(format!("Failed to launch {}: {}", command, err));
+            }
+        }
+    }
+
+    /// Shows a previously captured strace log instead of running strace.
+    fn open_log(&mut self, path: Option<PathBuf>) {
+        let name = match &path {
+            Some(path) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            None => "stdin".to_string(),
+        };
+        self.current_syscall = String::new();
+        let path_is_replay = path.as_deref().is_some_and(replay::is_replay);
+        let target = TraceTarget::Log(path);
+        let verb = if path_is_replay {
+            "Replaying session"
+        } else {
+            "Reading log"
+        };
+        match self.begin_session(target, name) {
+            Ok(()) => self.log_event(format!("{} from {}", verb, self.target_description())),
+            Err(err) => {
+                let message = format!(
+                    "Failed to read log from {}: {}",
+                    self.target_description(),
+                    err
+                );
+                self.return_to_selection();
+                self.report_error(message);
+            }
+        }
+    }
+
+    /// Copies the highlighted entry: the full raw line in the timeline, exactly
+    /// as strace printed it rather than cut to the panel, or the syscall name
+    /// in the list and stats table.
+    fn copy_selection(&mut self) {
+        let (text, what) = if self.show_detailed {
+            match visible_timeline(self).get(self.selected_syscall) {
+                Some(TimelineEntry::Line(line)) => (line.clone(), "full line"),
+                _ => return,
+            }
+        } else {
+            match selected_syscall_name(self) {
+                Some(name) => (name, "syscall name"),
+                None => return,
+            }
+        };
+        match clipboard::copy(&text) {
+            Ok(()) => self.log_event(format!("Copied {} ({} bytes)", what, text.len())),
+            Err(err) => self.report_error(format!("Failed to copy {}: {}", what, err)),
+        }
+    }
+
+    /// Shows the highlighted process's working directory in the event log and
+    /// copies it, for making sense of the relative paths in its syscalls.
+    fn copy_process_cwd(&mut self) {
+        let Some(pid) = self.highlighted_process().map(|p| p.pid) else {
+            return;
+        };
+        // Read afresh: the process may have changed directory since the refresh.
+        let cwd = match procfs::cwd(pid) {
+            Ok(cwd) => cwd.display().to_string(),
+            Err(err) => {
+                self.report_error(format!(
+                    "Working directory of PID {} is unavailable: {}",
+                    pid, err
+                ));
+                return;
+            }
+        };
+        match clipboard::copy(&cwd) {
+            Ok(()) => self.log_event(format!("PID {} works in {} (copied)", pid, cwd)),
+            Err(err) => self.report_error(format!(
+                "PID {} works in {} (copy failed: {})",
+                pid, cwd, err
+            )),
+        }
+    }
+
+    /// Opens the memory map popup for the highlighted process, reading its
+    /// maps unless they were read since the last refresh.
+    fn show_memory_map(&mut self) {
+        let Some((pid, name)) = self.highlighted_process().map(|p| (p.pid, p.name.clone())) else {
+            return;
+        };
+        if let std::collections::hash_map::Entry::Vacant(entry) = self.memory_maps.entry(pid) {
+            match procfs::maps(pid) {
+                Ok(maps) => {
+                    entry.insert(maps);
+                }
+                Err(err) => {
+                    self.report_error(format!("Memory map of PID {} is unavailable: {}", pid, err));
+                    return;
+                }
+            }
+        }
+        self.maps_view = Some(MapsView {
+            pid,
+            name,
+            expanded: false,
+            scroll: 0,
+        });
+    }
+
+    /// Handles a key while the memory map popup is open.
+    fn handle_maps_key(&mut self, key: event::KeyEvent) {
+        let Some(view) = &mut self.maps_view else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+            KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+            KeyCode::PageDown => view.scroll = view.scroll.saturating_add(10),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                view.expanded = !view.expanded;
+                view.scroll = 0;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.maps_view = None,
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.maps_view = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the leaderboard of the processes in the list, busiest first.
+    fn open_leaderboard(&mut self) {
+        let listed = self.filtered_processes.iter().map(|&i| {
+            let p = &self.processes[i];
+            (p.pid, p.name.as_str())
+        });
+        self.leaderboard = Some(leaderboard::Leaderboard::new(listed));
+    }
+
+    /// Reads the leaderboard's counters again once a sample is due.
+    fn poll_leaderboard(&mut self) {
+        let Some(board) = &mut self.leaderboard else {
+            return;
+        };
+        if board.due() {
+            board.sample(self.filtered_processes.iter().map(|&i| {
+                let p = &self.processes[i];
+                (p.pid, p.name.as_str())
+            }));
+            self.dirty = true;
+        }
+    }
+
+    /// Handles a key while the leaderboard is open. Enter goes on to trace
+    /// the highlighted process as if it had been chosen in the list.
+    fn handle_leaderboard_key(&mut self, key: event::KeyEvent) {
+        let Some(board) = &mut self.leaderboard else {
+            return;
+        };
+        let last = board.entries.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Up => board.selected = board.selected.saturating_sub(1),
+            KeyCode::Down => board.selected = (board.selected + 1).min(last),
+            KeyCode::PageUp => board.selected = board.selected.saturating_sub(10),
+            KeyCode::PageDown => board.selected = (board.selected + 10).min(last),
+            KeyCode::Enter => {
+                let pid = board.highlighted();
+                let proc = pid.and_then(|pid| self.processes.iter().find(|p| p.pid == pid));
+                if let Some(proc) = proc.cloned() {
+                    self.leaderboard = None;
+                    self.choose_attach_scope(proc);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.leaderboard = None,
+            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.leaderboard = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the manual page of the highlighted syscall, or says why there
+    /// is none.
+    fn show_man_page(&mut self) {
+        let Some(name) = selected_syscall_name(self) else {
+            self.log_event("No syscall is highlighted to look up");
+            return;
+        };
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        let area = man_page_area(Rect::new(0, 0, width, height));
+        let inner = (area.width.saturating_sub(2), area.height.saturating_sub(2));
+        match manpage::ManPage::load(&name, inner.0, inner.1 as usize) {
+            Ok(page) => self.man_page = Some(page),
+            Err(text) => {
+                self.summary = Some(Summary {
+                    title: "Manual page unavailable",
+                    text,
+                });
+            }
+        }
+    }
+
+    /// Handles a key while the manual page is open.
+    fn handle_man_page_key(&mut self, code: KeyCode) {
+        let Some(page) = &mut self.man_page else {
+            return;
+        };
+        let full = page.page as isize;
+        match code {
+            KeyCode::Up => page.scroll_by(-1),
+            KeyCode::Down => page.scroll_by(1),
+            KeyCode::PageUp => page.scroll_by(-full),
+            KeyCode::PageDown | KeyCode::Char(' ') => page.scroll_by(full),
+            KeyCode::Home => page.scroll_by(isize::MIN),
+            KeyCode::End => page.scroll_by(isize::MAX),
+            KeyCode::Left => page.scroll_sideways(-8),
+            KeyCode::Right => page.scroll_sideways(8),
+            KeyCode::Char('/') => {
+                let current = page.search.clone().unwrap_or_default();
+                self.open_prompt_with(PromptKind::ManSearch, current, None);
+            }
+            KeyCode::Char('n') => {
+                page.find(true, false);
+            }
+            KeyCode::Char('N') => {
+                page.find(false, false);
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.man_page = None,
+            KeyCode::Char(c) if c == self.keymap.key("manual") => self.man_page = None,
+            _ => {}
+        }
+    }
+
+    /// Writes the screen in `buffer` to timestamped plain and ANSI text files
+    /// in the working directory, and shows where they went.
+    fn save_snapshot(&mut self, buffer: &ratatui::buffer::Buffer) {
+        let base = format!("syscall-monitor-screen-{}", file_timestamp());
+        let plain = format!("{}.txt", base);
+        let ansi = format!("{}.ans", base);
+        let written = write_synced(&plain, &snapshot::plain(buffer))
+            .and_then(|()| write_synced(&ansi, &snapshot::ansi(buffer)));
+        match written {
+            Ok(()) => {
+                let text = format!("Saved the screen to {} and {}", plain, ansi);
+                self.log_event(text.clone());
+                self.summary = Some(Summary {
+                    title: "Screen snapshot",
+                    text,
+                });
+            }
+            Err(err) => self.report_error(format!("Could not save the screen: {}", err)),
+        }
+    }
+
+    /// Reloads the config file once it has changed and then stayed the same
+    /// for one check, so an editor's partial writes are not picked up.
+    fn poll_config(&mut self) {
+        if self.config_checked.elapsed() < CONFIG_POLL {
+            return;
+        }
+        self.config_checked = Instant::now();
+        let modified = Config::modified();
+        if modified == self.config_modified {
+            self.config_changing = None;
+        } else if self.config_changing != modified {
+            self.config_changing = modified;
+        } else {
+            self.config_changing = None;
+            self.config_modified = modified;
+            self.reload_config();
+        }
+    }
+
+    /// Applies the settings of the config file that can change while running,
+    /// all at once, and says when `safe_mode`, which only takes effect after a
+    /// restart, changed. A file with warnings is not applied at all.
+    fn reload_config(&mut self) {
+        let config = Config::load();
+        let (keymap, key_warnings) = KeyMap::parse(
+            config
+                .keys
+                .iter()
+                .map(|(action, key)| (action.as_str(), key.as_str())),
+        );
+        // Warnings already reported for an earlier version of the file are
+        // not repeated on every save.
+        let warnings: Vec<String> = config
+            .warnings
+            .iter()
+            .cloned()
+            .chain(key_warnings)
+            .collect();
+        for warning in &warnings {
+            if !self.config_warnings.contains(warning) {
+                self.report_error(warning.clone());
+            }
+        }
+        let has_warnings = !warnings.is_empty();
+        self.config_warnings = warnings;
+        // A half-edited file would apply its mistakes as defaults.
+        if has_warnings {
+            self.log_event("Config not reloaded: fix the problems above first");
+            return;
+        }
+        self.keymap = keymap;
+        self.top_n = config.top_n;
+        self.filter_all_terms = config.filter_all_terms;
+        self.idle_timeout = (config.idle_detach_minutes > 0)
+            .then(|| Duration::from_secs(config.idle_detach_minutes * 60));
+        self.protection = Protection::new(&config.protected);
+        self.remember_settings = config.remember_settings;
+        self.notifier = notify::Notifier::new(&config);
+        self.notify_syscalls = config.notify_syscalls.iter().cloned().collect();
+        if config.hide_self != self.file_config.hide_self {
+            self.hide_own = config.hide_self;
+            self.update_filtered_processes();
+        }
+        self.update_filtered_syscalls();
+        self.max_tracers = config.max_tracers;
+        if config.mouse_capture != self.file_config.mouse_capture
+            && config.mouse_capture != self.mouse_capture
+        {
+            self.set_mouse_capture(config.mouse_capture);
+        }
+        self.error_log = config.error_log;
+        let safe_mode_changed = config.safe_mode != self.file_config.safe_mode;
+        self.file_config = config;
+        if safe_mode_changed {
+            self.log_event("Config reloaded; safe_mode takes effect after a restart");
+        } else {
+            self.log_event("Config reloaded");
+        }
+    }
+
+    /// Handles a key while the resources popup is open.
+    fn handle_resources_key(&mut self, code: KeyCode) {
+        let Some(scroll) = &mut self.resources_scroll else {
+            return;
+        };
+        match code {
+            KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down => *scroll = scroll.saturating_add(1),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+            KeyCode::Esc | KeyCode::Char('q') => self.resources_scroll = None,
+            KeyCode::Char(c) if c == self.keymap.key("resources") => {
+                self.resources_scroll = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Lists the highlighted process's effective and permitted capabilities in
+    /// the event log, for seeing how much a process could do before tracing it.
+    fn show_process_capabilities(&mut self) {
+        let Some(pid) = self.highlighted_process().map(|p| p.pid) else {
+            return;
+        };
+        let (effective, permitted) = match procfs::capabilities(pid) {
+            Ok(masks) => masks,
+            Err(err) => {
+                self.report_error(format!(
+                    "Capabilities of PID {} are unavailable: {}",
+                    pid, err
+                ));
+                return;
+            }
+        };
+        let describe = |mask: u64| {
+            if mask == 0 {
+                "none".to_string()
+            } else {
+                capabilities::names(mask).join(" ")
+            }
+        };
+        self.log_event(format!(
+            "PID {} effective capabilities: {}",
+            pid,
+            describe(effective)
+        ));
+        if permitted != effective {
+            self.log_event(format!(
+                "PID {} permitted capabilities: {}",
+                pid,
+                describe(permitted)
+            ));
+        }
+    }
+
+    /// Calls of `name` since the baseline was marked, or in total without one.
+    fn count_since_baseline(&self, name: &str) -> u64 {
+        // A locked PID's own counts are shown whole.
+        if self.pid_lock.is_some() {
+            return self.calls_of(name);
+        }
+        let count = self.syscall_counts.get(name).copied().unwrap_or(0);
+        let before = self
+            .baseline
+            .as_ref()
+            .and_then(|baseline| baseline.counts.get(name).copied())
+            .unwrap_or(0);
+        count.saturating_sub(before)
+    }
+
+    /// How often `name` was called, by the locked PID if there is one.
+    fn calls_of(&self, name: &str) -> u64 {
+        let counts = match self.pid_lock {
+            Some(pid) => self.pid_syscall_counts.get(&pid),
+            None => Some(&self.syscall_counts),
+        };
+        counts
+            .and_then(|counts| counts.get(name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The PID that lines without a `[pid N]` tag come from: the traced
+    /// process itself, if it is known.
+    fn untagged_pid(&self) -> Option<i32> {
+        (self.target_pid > 0).then_some(self.target_pid)
+    }
+
+    /// PIDs seen so far with their call counts, most active first.
+    fn pid_totals(&self) -> Vec<(i32, u64)> {
+        let mut totals: Vec<(i32, u64)> = self
+            .pid_syscall_counts
+            .iter()
+            .map(|(pid, counts)| (*pid, counts.values().sum()))
+            .collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        totals
+    }
+
+    /// Handles a key while the PID picker is open. Its first row stands for
+    /// all PIDs, the others lock the views to one.
+    fn handle_pid_picker_key(&mut self, code: KeyCode) {
+        let Some(selected) = self.pid_picker else {
+            return;
+        };
+        let totals = self.pid_totals();
+        match code {
+            KeyCode::Up => self.pid_picker = Some(selected.saturating_sub(1)),
+            KeyCode::Down => self.pid_picker = Some((selected + 1).min(totals.len())),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.pid_picker = None;
+                self.selected_syscall = 0;
+                match selected.checked_sub(1).and_then(|i| totals.get(i)) {
+                    Some(&(pid, _)) => {
+                        self.pid_lock = Some(pid);
+                        let label = self.pid_label(pid);
+                        self.log_event(format!("Showing only {}", label));
+                    }
+                    None => self.unlock_pid(),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.pid_picker = None,
+            KeyCode::Char(c) if c == self.keymap.key("lock_pid") => self.pid_picker = None,
+            _ => {}
+        }
+    }
+
+    /// `PID n` followed by its thread or process name, if known.
+    fn pid_label(&self, pid: i32) -> String {
+        match self.pid_kinds.get(&pid) {
+            Some(PidKind::Thread(name)) => format!("thread {} ({})", pid, name),
+            Some(PidKind::Process(name)) => format!("PID {} ({})", pid, name),
+            _ => format!("PID {}", pid),
+        }
+    }
+
+    /// The syscall `pid` made most often, for the picker's breakdown.
+    fn top_syscall(&self, pid: i32) -> Option<&str> {
+        self.pid_syscall_counts
+            .get(&pid)?
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Goes back from one locked PID to the combined view.
+    fn unlock_pid(&mut self) {
+        if self.pid_lock.take().is_some() {
+            self.selected_syscall = 0;
+            self.log_event("Showing all PIDs");
+        }
+    }
+
+    /// Pins the highlighted syscall to the top of the list, or unpins it.
+    fn toggle_pin(&mut self) {
+        let Some(name) = selected_syscall_name(self) else {
+            return;
+        };
+        if self.pinned.remove(&name) {
+            self.log_event(format!("Unpinned {}", name));
+        } else {
+            self.log_event(format!("Pinned {}", name));
+            self.pinned.insert(name);
+        }
+    }
+
+    /// Whether the session attached to processes that were already running.
+    fn traces_running_processes(&self) -> bool {
+        matches!(
+            self.trace_target,
+            TraceTarget::Attach(_) | TraceTarget::Group { .. }
+        )
+    }
+
+    /// Whether the session shows captured data rather than a live trace: a
+    /// log, or a resumed checkpoint.
+    fn reading_log(&self) -> bool {
+        matches!(
+            self.trace_target,
+            TraceTarget::Log(_) | TraceTarget::Checkpoint { .. }
+        )
+    }
+
+    /// The current trace filter and view, as they would be saved in a profile.
+    fn current_profile(&self) -> Profile {
+        Profile {
+            trace_filter: self.trace_options.trace_filter.clone(),
+            category: self.category_filter,
+            show_detailed: self.show_detailed,
+            syscall_order: self.syscall_order,
+            pins: {
+                let mut pins: Vec<String> = self.pinned.iter().cloned().collect();
+                pins.sort();
+                pins
+            },
+        }
+    }
+
+    fn apply_profile(&mut self, profile: &Profile) {
+        self.trace_options.trace_filter = profile.trace_filter.clone();
+        self.category_filter = profile.category;
+        self.show_detailed = profile.show_detailed;
+        self.syscall_order = profile.syscall_order;
+        self.pinned = profile.pins.iter().cloned().collect();
+    }
+
+    /// Undoes the settings applied by the session's profile, if any.
+    fn restore_profile_settings(&mut self) {
+        if let Some(previous) = self.settings_before_profile.take() {
+            self.apply_profile(&previous);
+        }
+        self.active_profile = None;
+    }
+
+    /// Saves the current trace filter and view as the profile for the target's name.
+    fn save_profile(&mut self) {
+        let name = self.target_process_name.clone();
+        self.profiles.insert(name.clone(), self.current_profile());
+        match Profile::save_all(&self.profiles) {
+            Ok(()) => self.log_event(format!("Saved profile for '{}'", name)),
+            Err(err) => {
+                self.report_error(format!("Failed to save profile for '{}': {}", name, err))
+            }
+        }
+        self.active_profile = Some(name);
+    }
+
+    /// Quietly saves the session's settings as the profile for the target's
+    /// name, so they come back the next time it is traced.
+    fn remember_profile(&mut self) {
+        let profile = self.current_profile();
+        let name = self.target_process_name.clone();
+        self.profiles.insert(name.clone(), profile);
+        if let Err(err) = Profile::save_all(&self.profiles) {
+            self.report_error(format!(
+                "Failed to remember settings for '{}': {}",
+                name, err
+            ));
+        }
+    }
+
+    /// Resets the monitoring screen for a new target and starts tracing it.
+    ///
+    /// A saved profile for `name` is applied before strace starts.
+    fn begin_session(&mut self, target: TraceTarget, name: String) -> std::io::Result<()> {
+        self.restore_profile_settings();
+        if let Some(profile) = self.profiles.get(&name).cloned() {
+            self.settings_before_profile = Some(self.current_profile());
+            self.apply_profile(&profile);
+            self.active_profile = Some(name.clone());
+            self.log_event(format!("Applied profile for '{}'", name));
+        }
+        self.target_pid = match target {
+            TraceTarget::Attach(pid) => pid,
+            // The leader, if it is still around, stands for the group.
+            TraceTarget::Group {
+                id, ref members, ..
+            } => {
+                if members.contains(&id) {
+                    id
+                } else {
+                    members[0]
+                }
+            }
+            TraceTarget::Launch(_) => 0,
+            // There is no live process behind a log or a checkpoint.
+            TraceTarget::Log(_) | TraceTarget::Checkpoint { .. } => -1,
+        };
+        self.trace_target = target;
+        self.target_process_name = name;
+        self.attached = false;
+        self.error_banner = None;
+        self.options_changed = false;
+        self.checkpoint_saved = Instant::now();
+        self.checkpoint_path = None;
+        self.capture_deadline = self.capture_duration.map(|d| Instant::now() + d);
+        self.mode = AppMode::SyscallMonitoring;
+        self.clear_capture();
+        self.filter_mode = false;
+        self.syscall_filter.clear();
+        self.start_strace()
+    }
+
+    /// Describes the current target for log messages.
+    fn target_description(&self) -> String {
+        match &self.trace_target {
+            TraceTarget::Attach(pid) => format!("PID {}", pid),
+            TraceTarget::Group { kind, id, .. } => format!("{} {}", kind.label(), id),
+            TraceTarget::Launch(argv) => shell_join(argv),
+            TraceTarget::Log(Some(path)) => path.display().to_string(),
+            TraceTarget::Log(None) => "standard input".to_string(),
+            TraceTarget::Checkpoint { description, .. } => description.clone(),
+        }
+    }
+
+    /// Estimates the share of the capture window the traced threads spent
+    /// blocked inside syscalls, and the syscall accounting for most of it.
+    ///
+    /// This is rough: the window runs from the first syscall line to now (or to
+    /// the last line once strace has stopped), each PID that reported a duration
+    /// is assumed to have existed for all of it, and a call still in progress is
+    /// not counted yet.
+    fn blocked_estimate(&self) -> Option<(f64, &str, f64)> {
+        // A log arrives all at once, so arrival times say nothing about it.
+        if self.reading_log() {
+            return None;
+        }
+        let first = self.first_line_at?;
+        let end = match (&self.strace_child, self.last_line_at) {
+            (None, Some(last)) => last,
+            _ => Instant::now(),
+        };
+        let window = end.saturating_duration_since(first).as_secs_f64();
+        if window < 1.0 {
+            return None;
+        }
+        let (top, top_time) = self.syscall_time.iter().max_by_key(|(_, time)| **time)?;
+        let capacity = window * self.timed_pids.len().max(1) as f64;
+        let total: f64 = self.syscall_time.values().map(Duration::as_secs_f64).sum();
+        let share = |time: f64| (time / capacity * 100.0).min(100.0);
+        Some((share(total), top.as_str(), share(top_time.as_secs_f64())))
+    }
+
+    /// Discards everything captured so far in the current session.
+    fn clear_capture(&mut self) {
+        self.notified_syscalls.clear();
+        self.baseline = None;
+        self.show_changes = false;
+        self.unique_syscalls.clear();
+        self.syscall_log.clear();
+        self.frozen_order.clear();
+        self.syscall_counts.clear();
+        self.syscall_time.clear();
+        self.timed_pids.clear();
+        self.first_line_at = None;
+        self.first_timestamp = None;
+        self.errno_counts.clear();
+        self.error_counts.clear();
+        self.errno_syscalls.clear();
+        self.stacks.clear();
+        self.stack_call = None;
+        self.errno_filter = None;
+        self.fd_filter = None;
+        self.pid_syscall_counts.clear();
+        self.pid_kinds.clear();
+        self.pid_lock = None;
+        self.resources = Resources::default();
+        self.resources_scroll = None;
+        self.timeline.clear();
+        self.timeline_pids.clear();
+        self.notes.clear();
+        self.pending_calls.clear();
+        self.lines_seen = 0;
+        self.filtered_syscalls.clear();
+        self.filter_scores.clear();
+        self.selected_syscall = 0;
+        self.syscall_list_state = ListState::default();
+        self.syscall_list_offset = 0;
+        self.stats_state = TableState::default();
+    }
+
+    /// Replaces the running strace with one using the current options.
+    ///
+    /// The old tracer is killed and reaped before the new one is spawned, so the
+    /// target never has two tracers attached.
+    fn restart_strace(&mut self, keep_data: bool) {
+        self.drain_strace_output();
+        self.stop_strace();
+        if !keep_data {
+            self.clear_capture();
+        }
+        self.attached = false;
+        self.error_banner = None;
+        self.options_changed = false;
+        match self.start_strace() {
+            Ok(()) => self.log_event(format!(
+                "Restarted strace for {}{}",
+                self.target_description(),
+                if keep_data { "" } else { " (data cleared)" }
+            )),
+            Err(err) => self.report_error(format!("Failed to restart strace: {}", err)),
+        }
+    }
+
+    /// Feeds every line strace has produced so far through the parser.
+    ///
+    /// A log's reader thread hangs up at the end of the input, which is noted
+    /// once and leaves the data on screen.
+    fn drain_strace_output(&mut self) {
+        let Some(rx) = &self.strace_receiver else {
+            return;
+        };
+        let mut lines = Vec::new();
+        let finished = loop {
+            match rx.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break self.reading_log(),
+            }
+        };
+        if let Some(recorder) = &mut self.recorder {
+            let recorded = lines
+                .iter()
+                .try_for_each(|line| recorder.record(line))
+                .and_then(|()| recorder.flush());
+            if let Err(err) = recorded {
+                let message = format!(
+                    "Recording to {} stopped: {}",
+                    recorder.path().display(),
+                    err
+                );
+                self.recorder = None;
+                self.log_event(message);
+            }
+        }
+        for line in lines {
+            self.process_strace_line(&line);
+        }
+        if finished {
+            self.finish_stack();
+            self.strace_receiver = None;
+            self.log_event(format!(
+                "Finished reading {} ({} lines)",
+                self.target_description(),
+                self.lines_seen
+            ));
+        }
+    }
+
+    /// Opens a text prompt over the current screen.
+    fn open_prompt(&mut self, kind: PromptKind) {
+        self.open_prompt_with(kind, String::new(), None);
+    }
+
+    /// Opens a text prompt with `input` already filled in.
+    fn open_prompt_with(&mut self, kind: PromptKind, input: String, error: Option<String>) {
+        self.prompt = Some(Prompt {
+            kind,
+            input,
+            error,
+            detail: None,
+        });
+    }
+
+    /// Asks where to export the data behind the current view: the raw
+    /// timeline in the detailed view, the syscall summary otherwise.
+    fn start_export(&mut self) {
+        let kind = if self.show_changes {
+            ExportKind::Changes
+        } else if self.show_detailed {
+            ExportKind::Timeline
+        } else {
+            ExportKind::Summary
+        };
+        self.open_export_prompt(kind);
+    }
+
+    /// Asks where to write an export of `kind`, suggesting a name built from
+    /// the traced process.
+    fn open_export_prompt(&mut self, kind: ExportKind) {
+        let name = format!(
+            "syscall-monitor-{}-{}-{}.{}",
+            self.target_process_name.replace(['/', ' '], "_"),
+            self.target_pid,
+            kind.label(),
+            kind.extension()
+        );
+        self.open_prompt_with(PromptKind::ExportPath(kind), name, None);
+    }
+
+    /// Writes the export confirmed in the path prompt, if there is one.
+    /// Returns whether there was.
+    fn write_pending_export(&mut self) -> bool {
+        let Some((kind, target)) = self.pending_export.take() else {
+            return false;
+        };
+        self.perform_export(kind, &target);
+        true
+    }
+
+    /// Writes an export to `target` and reports the outcome.
+    ///
+    /// On success the final path and size are shown; on failure the path
+    /// prompt is reopened with the error so the user can fix it and retry.
+    fn perform_export(&mut self, kind: ExportKind, target: &str) {
+        let contents = match kind {
+            ExportKind::Summary => self.summary_csv(),
+            ExportKind::Timeline => self.timeline_text(),
+            ExportKind::Changes => {
+                let Some(report) = self.change_report() else {
+                    return;
+                };
+                let mut text = report.text(&self.changes_title());
+                if !self.notes.is_empty() {
+                    text.push_str("\nNotes\n");
+                    for (offset, note) in &self.notes {
+                        text.push_str(&format!("  {} {}\n", note_offset(*offset), note));
+                    }
+                }
+                text
+            }
+        };
+        match write_synced(target, &contents) {
+            Ok(()) => {
+                let path = std::fs::canonicalize(target)
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|_| target.to_string());
+                let text = format!("Wrote {} bytes to {}", contents.len(), path);
+                self.log_event(format!("Exported {}: {}", kind.label(), text));
+                self.summary = Some(Summary {
+                    title: "Export complete",
+                    text,
+                });
+            }
+            Err(err) => {
+                self.report_error(format!("Export to {} failed: {}", target, err));
+                self.open_prompt_with(
+                    PromptKind::ExportPath(kind),
+                    target.to_string(),
+                    Some(format!("Could not write {}: {}", target, err)),
+                );
+            }
+        }
+    }
+
+    /// Formats syscall counts as CSV, most frequent first.
+    fn summary_csv(&self) -> String {
+        let mut counts: Vec<(&String, &u64)> = self.syscall_counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let mut out = String::from("syscall,count,category\n");
+        for (name, count) in counts {
+            out.push_str(&format!(
+                "{},{},{}\n",
+                name,
+                count,
+                syscalls::category(name).label()
+            ));
+        }
+        for (offset, text) in &self.notes {
+            out.push_str(&note_line(*offset, text));
+        }
+        out
+    }
+
+    /// What changed since the baseline, if one is marked.
+    fn change_report(&self) -> Option<ChangeReport> {
+        let baseline = self.baseline.as_ref()?;
+        Some(ChangeReport::new(
+            baseline,
+            &self.syscall_counts,
+            &self.error_counts,
+        ))
+    }
+
+    /// Heading of the report of what changed, naming the process and when
+    /// the baseline was marked.
+    fn changes_title(&self) -> String {
+        format!(
+            "What changed in PID {} ({}) since the baseline at {}",
+            self.target_pid,
+            self.target_process_name,
+            self.baseline
+                .as_ref()
+                .map_or("", |baseline| baseline.marked_at.as_str())
+        )
+    }
+
+    /// Lines of the session summary shown after detaching: how long the
+    /// trace ran, how many calls it saw and the most frequent ones.
+    fn session_report(&self) -> Vec<String> {
+        let total: u64 = self.syscall_counts.values().sum();
+        let errors: u64 = self.error_counts.values().sum();
+        let secs = self.strace_started.map_or(0, |t| t.elapsed().as_secs());
+        let mut lines = vec![
+            format!("Duration: {}m {:02}s", secs / 60, secs % 60),
+            format!(
+                "Syscalls: {} total, {} unique",
+                total,
+                self.syscall_counts.len()
+            ),
+            format!("Errors: {}", errors),
+        ];
+        let mut counts: Vec<(&String, &u64)> = self.syscall_counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        if !counts.is_empty() {
+            lines.push(String::new());
+            lines.push("Most frequent:".to_string());
+            lines.extend(
+                counts
+                    .into_iter()
+                    .take(SESSION_REPORT_TOP)
+                    .map(|(name, count)| format!("  {:<20} {}", name, count)),
+            );
+        }
+        lines
+    }
+
+    /// Records a note at the current time into the session, shown in the
+    /// timeline and written to exports.
+    fn add_note(&mut self, text: String) {
+        let offset = self
+            .strace_started
+            .map_or(Duration::ZERO, |started| started.elapsed());
+        self.log_event(format!("Note at {}: {}", note_offset(offset), text));
+        self.notes.push((offset, text.clone()));
+        self.push_timeline(TimelineEntry::Note(offset, text));
+    }
+
+    /// Joins the raw timeline lines, oldest first, with the notes as `#`
+    /// comment lines where they were added. Notes older than the oldest line
+    /// still kept come first.
+    fn timeline_text(&self) -> String {
+        let kept = self
+            .timeline
+            .iter()
+            .filter(|entry| matches!(entry, TimelineEntry::Note(..)))
+            .count();
+        let mut out = String::new();
+        for (offset, text) in &self.notes[..self.notes.len() - kept] {
+            out.push_str(&note_line(*offset, text));
+        }
+        for entry in &self.timeline {
+            match entry {
+                TimelineEntry::Line(text) => {
+                    out.push_str(text);
+                    out.push('\n');
+                }
+                TimelineEntry::Note(offset, text) => out.push_str(&note_line(*offset, text)),
+                TimelineEntry::NewPid(_) => {}
+            }
+        }
+        out
+    }
+
+    /// Edits or submits the active prompt. Ctrl+U clears the input.
+    fn handle_prompt_key(&mut self, key: event::KeyEvent) {
+        let Some(prompt) = &mut self.prompt else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                prompt.input.clear();
+            }
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => {}
+            KeyCode::Char(c) => prompt.input.push(c),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Enter => {
+                if let Some(prompt) = self.prompt.take() {
+                    self.submit_prompt(prompt);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies a submitted prompt.
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        match prompt.kind {
+            PromptKind::CaptureDuration => {
+                let input = prompt.input.trim();
+                if input.is_empty() {
+                    self.capture_duration = None;
+                    self.capture_deadline = None;
+                    self.log_event("Timed capture cleared");
+                    return;
+                }
+                match parse_duration(input) {
+                    Some(duration) => {
+                        self.capture_duration = Some(duration);
+                        if let AppMode::SyscallMonitoring = self.mode {
+                            if self.strace_child.is_some() {
+                                self.capture_deadline = Some(Instant::now() + duration);
+                            }
+                        }
+                        self.log_event(format!("Timed capture set to {}s", duration.as_secs()));
+                    }
+                    None => self.log_event(format!("Invalid capture duration '{}'", input)),
+                }
+            }
+            PromptKind::FdFilter => {
+                let input = prompt.input.trim();
+                if input.is_empty() {
+                    self.fd_filter = None;
+                    return;
+                }
+                match input.parse::<i32>() {
+                    Ok(fd) if fd >= 0 => {
+                        self.fd_filter = Some(fd);
+                        // The filter applies to raw lines, so show them.
+                        self.show_detailed = true;
+                        self.show_stats = false;
+                        self.selected_syscall = 0;
+                    }
+                    _ => {
+                        let error = format!("'{}' is not a file descriptor number", input);
+                        self.open_prompt_with(prompt.kind, prompt.input, Some(error));
+                    }
+                }
+            }
+            PromptKind::NormalSet => {
+                let path = prompt.input.trim();
+                if path.is_empty() {
+                    if self.normal_set.take().is_some() {
+                        self.anomalies_only = false;
+                        self.log_event("Known-normal syscalls cleared");
+                    }
+                    return;
+                }
+                match std::fs::read_to_string(path) {
+                    Ok(contents) => {
+                        let names = parse_normal_set(&contents);
+                        self.log_event(format!(
+                            "Loaded {} known-normal syscalls from {}",
+                            names.len(),
+                            path
+                        ));
+                        self.normal_set = Some(NormalSet {
+                            path: path.to_string(),
+                            names,
+                        });
+                        self.update_filtered_syscalls();
+                    }
+                    Err(err) => {
+                        let error = format!("Could not read {}: {}", path, err);
+                        self.open_prompt_with(prompt.kind, prompt.input, Some(error));
+                    }
+                }
+            }
+            PromptKind::TraceFilter => {
+                let input = prompt.input.trim();
+                let filter = if input.is_empty() { "all" } else { input };
+                if filter != self.trace_options.trace_filter {
+                    self.trace_options.trace_filter = filter.to_string();
+                    self.options_changed = true;
+                    self.log_event(format!("Trace filter set to '{}'", filter));
+                }
+            }
+            PromptKind::Note => {
+                let text = prompt.input.trim();
+                if !text.is_empty() {
+                    self.add_note(text.to_string());
+                }
+            }
+            PromptKind::ManSearch => {
+                let text = prompt.input.trim();
+                if let Some(page) = &mut self.man_page {
+                    if text.is_empty() {
+                        page.search = None;
+                    } else {
+                        page.search = Some(text.to_string());
+                        // A new search starts from the top line shown.
+                        page.scroll_by(0);
+                        if !page.find(true, true) {
+                            let error = format!("No match for '{}'", text);
+                            self.open_prompt_with(
+                                PromptKind::ManSearch,
+                                text.to_string(),
+                                Some(error),
+                            );
+                        }
+                    }
+                }
+            }
+            PromptKind::StraceArgs => {
+                let args = split_command_line(&prompt.input)
+                    .map_err(str::to_string)
+                    .and_then(|args| check_strace_args(&args).map(|()| args));
+                match args {
+                    Ok(args) if args == self.trace_options.extra_args => {}
+                    Ok(args) => {
+                        // Confirmed against the whole command they make.
+                        let mut options = self.trace_options.clone();
+                        options.extra_args = args.clone();
+                        let command = options.command(&self.trace_target, None);
+                        self.pending_strace_args = Some(args);
+                        self.open_prompt(PromptKind::StraceArgsConfirm);
+                        if let Some(prompt) = &mut self.prompt {
+                            prompt.detail = Some(shell_join(&command));
+                        }
+                    }
+                    Err(err) => self.open_prompt_with(prompt.kind, prompt.input, Some(err)),
+                }
+            }
+            PromptKind::StraceArgsConfirm => {
+                let Some(args) = self.pending_strace_args.take() else {
+                    return;
+                };
+                if prompt.input.trim().eq_ignore_ascii_case("n") {
+                    return;
+                }
+                self.log_event(if args.is_empty() {
+                    "Extra strace arguments cleared".to_string()
+                } else {
+                    format!("Extra strace arguments set to '{}'", shell_join(&args))
+                });
+                self.trace_options.extra_args = args;
+                self.options_changed = true;
+            }
+            PromptKind::RestartConfirm => {
+                let keep_data = !prompt.input.trim().eq_ignore_ascii_case("n");
+                self.restart_strace(keep_data);
+            }
+            PromptKind::StopOrphans => {
+                if prompt.input.trim().eq_ignore_ascii_case("n") {
+                    return;
+                }
+                let orphans: Vec<i32> = tracers::orphans()
+                    .into_iter()
+                    .filter(|&pid| !self.refuse_protected(pid))
+                    .collect();
+                match tracers::stop_orphans(&orphans) {
+                    Ok(stopped) => self.log_event(format!(
+                        "Stopped {} orphaned strace process{}",
+                        stopped,
+                        if stopped == 1 { "" } else { "es" }
+                    )),
+                    Err(err) => {
+                        self.report_error(format!("Failed to clean up tracer list: {}", err))
+                    }
+                }
+            }
+            PromptKind::ResumeCheckpoint => {
+                let Some(path) = self.pending_checkpoint.take() else {
+                    return;
+                };
+                if prompt.input.trim().eq_ignore_ascii_case("n") {
+                    match Checkpoint::discard(&path) {
+                        Ok(()) => self.log_event("Checkpoint discarded"),
+                        Err(err) => {
+                            self.report_error(format!("Failed to discard the checkpoint: {}", err))
+                        }
+                    }
+                    return;
+                }
+                match Checkpoint::load(&path) {
+                    Ok(checkpoint) => self.resume_checkpoint(path, checkpoint),
+                    Err(err) => self.report_error(format!("Checkpoint is unreadable: {}", err)),
+                }
+            }
+            PromptKind::AttachScope => {
+                let Some((proc, pgid, sid)) = self.pending_attach.take() else {
+                    return;
+                };
+                match prompt.input.trim().to_lowercase().as_str() {
+                    "" | "p" => self.attach(proc),
+                    "g" => self.attach_group(GroupKind::ProcessGroup, pgid, proc.name),
+                    "s" => self.attach_group(GroupKind::Session, sid, proc.name),
+                    other => {
+                        let error = format!("'{}' is not one of p, g or s", other);
+                        self.pending_attach = Some((proc, pgid, sid));
+                        self.open_prompt_with(prompt.kind, String::new(), Some(error));
+                        self.prompt.as_mut().unwrap().detail = prompt.detail;
+                    }
+                }
+            }
+            PromptKind::LaunchCommand => match split_command_line(&prompt.input) {
+                Ok(argv) if argv.is_empty() => {}
+                Ok(argv) => self.launch(argv),
+                Err(err) => self.log_event(format!("Invalid command line: {}", err)),
+            },
+            PromptKind::ExportPath(kind) => {
+                let target = prompt.input.trim();
+                if target.is_empty() {
+                    self.open_prompt_with(
+                        prompt.kind,
+                        String::new(),
+                        Some("A file name is required".to_string()),
+                    );
+                } else {
+                    self.pending_export = Some((kind, target.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Time elapsed in the running timed capture and its total length.
+    fn capture_progress(&self) -> Option<(Duration, Duration)> {
+        let deadline = self.capture_deadline?;
+        let total = self.capture_duration?;
+        if total.is_zero() {
+            return None;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        Some((total.saturating_sub(left), total))
+    }
+
+    /// Ends a timed capture: detaches strace but keeps the data on screen.
+    fn finish_timed_capture(&mut self) {
+        self.capture_deadline = None;
+        self.stop_strace();
+        if let Some(pane) = &mut self.compare {
+            pane.stop();
+        }
+        self.log_event(format!(
+            "Timed capture of {} finished; detached",
+            self.target_description()
+        ));
+    }
+
+    /// Detaches strace once nobody has touched the keyboard or mouse for the
+    /// configured idle timeout, so an unattended session stops slowing down
+    /// its target. The data stays on screen under a notice.
+    fn detach_for_inactivity(&mut self, idle: Duration) {
+        self.capture_deadline = None;
+        self.stop_strace();
+        if let Some(pane) = &mut self.compare {
+            pane.stop();
+        }
+        let minutes = idle.as_secs() / 60;
+        let text = format!(
+            "Detached from {} after {} minute{} without input (idle_detach_minutes)",
+            self.target_description(),
+            minutes,
+            if minutes == 1 { "" } else { "s" }
+        );
+        self.log_event(text.clone());
+        self.summary = Some(Summary {
+            title: "Detached due to inactivity",
+            text,
+        });
+    }
+
+    /// Handles the strace process exiting on its own.
+    ///
+    /// An attached trace returns to the process list, with a summary popup if
+    /// the target's exit was seen. A launched command exits together with
+    /// strace, which passes on its status; the captured data stays on screen
+    /// with the exit status in the header. If strace instead gave up right
+    /// away, the failure is reported as such.
+    fn trace_ended(&mut self, status: ExitStatus) {
+        self.drain_remaining_output();
+        if let Some(diagnostic) = self.early_failure(status) {
+            self.stop_strace();
+            self.capture_deadline = None;
+            if self.traces_running_processes() {
+                self.summary = Some(Summary {
+                    title: "Attach failed",
+                    text: diagnostic.clone(),
+                });
+                self.return_to_selection();
+            }
+            self.report_error(diagnostic);
+            return;
+        }
+        if self.traces_running_processes() {
+            self.stop_strace();
+            match &self.exit_status {
+                Some(exit_status) => {
+                    let text = format!(
+                        "PID {} ({}) {}",
+                        self.target_pid, self.target_process_name, exit_status
+                    );
+                    self.log_event(text.clone());
+                    self.summary = Some(Summary {
+                        title: "Trace ended",
+                        text,
+                    });
+                }
+                None => self.log_event(format!("Trace of {} ended", self.target_description())),
+            }
+            self.return_to_selection();
+            return;
+        }
+        self.stop_strace();
+        self.capture_deadline = None;
+        let description = self
+            .exit_status
+            .get_or_insert_with(|| describe_exit_status(status))
+            .clone();
+        self.log_event(format!("{} {}", self.target_description(), description));
+    }
+
+    /// Recognises strace giving up shortly after starting, as opposed to the
+    /// target exiting, and describes why.
+    fn early_failure(&self, status: ExitStatus) -> Option<String> {
+        let quick = self
+            .strace_started
+            .is_some_and(|started| started.elapsed() < EARLY_EXIT);
+        if !quick
+            || self.exit_status.is_some()
+            || (self.strace_errors.is_empty() && status.success())
+        {
+            return None;
+        }
+        let detail = match self.strace_errors.last() {
+            Some(message) => format!("strace: {}", message),
+            None => format!("strace {}", describe_exit_status(status)),
+        };
+        let blocked = self.strace_errors.iter().any(|message| {
+            let message = message.to_lowercase();
+            message.contains("seccomp")
+                || (message.contains("ptrace")
+                    && (message.contains("operation not permitted")
+                        || message.contains("function not implemented")))
+        });
+        let mut message = if blocked {
+            format!(
+                "strace cannot trace here: ptrace is not permitted (missing privileges, Yama ptrace_scope, or a seccomp/container sandbox). {} Start with --backend proc to graph coarse /proc activity instead.",
+                detail
+            )
+        } else {
+            format!("strace failed right after starting. {}", detail)
+        };
+        if let TraceTarget::Attach(pid) = self.trace_target {
+            if let Some(help) = setuid_help(pid) {
+                message.push(' ');
+                message.push_str(&help);
+            }
+        }
+        Some(message)
+    }
+
+    /// Reads the output strace left behind after exiting, giving the reader
+    /// threads a moment to catch up.
+    fn drain_remaining_output(&mut self) {
+        let deadline = Instant::now() + Duration::from_millis(200);
+        loop {
+            let line = match &self.strace_receiver {
+                Some(rx) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => return,
+            };
+            match line {
+                Ok(line) => self.process_strace_line(&line),
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Looks up the PID of the command strace launched: strace's first child.
+    fn resolve_launched_pid(&mut self) {
+        let Some(child) = &self.strace_child else {
+            return;
+        };
+        // Until strace has forked, or if its children cannot be read, the PID
+        // stays unknown and is looked up again on the next tick.
+        let children = procfs::children(child.id()).unwrap_or_default();
+        if let Some(&pid) = children.first() {
+            self.target_pid = pid;
+            self.attached = true;
+            self.log_event(format!("Launched command has PID {}", pid));
+        }
+    }
+
+    /// Stops any trace and goes back to a freshly refreshed process list.
+    fn return_to_selection(&mut self) {
+        self.discard_checkpoint();
+        self.stop_strace();
+        self.finish_recording();
+        self.compare = None;
+        if self.remember_settings && self.attached {
+            self.remember_profile();
+        }
+        self.restore_profile_settings();
+        self.capture_deadline = None;
+        self.legend_focus = None;
+        self.errno_focus = None;
+        self.error_banner = None;
+        self.mode = AppMode::ProcessSelection;
+        self.processes = Self::get_processes();
+        self.port_owners = None;
+        self.memory_maps.clear();
+        self.update_filtered_processes();
+    }
+
+    /// Handles a key while the column editor is open.
+    fn handle_column_key(&mut self, code: KeyCode) {
+        let Some(selected) = self.column_editor else {
+            return;
+        };
+        let last = self.process_columns.len() - 1;
+        match code {
+            KeyCode::Up => self.column_editor = Some(selected.saturating_sub(1)),
+            KeyCode::Down => self.column_editor = Some((selected + 1).min(last)),
+            KeyCode::Char(' ') => {
+                let visible = self.process_columns.iter().filter(|(_, v)| *v).count();
+                let shown = &mut self.process_columns[selected].1;
+                // Keep at least one column so rows never render empty.
+                if !*shown || visible > 1 {
+                    *shown = !*shown;
+                }
+            }
+            KeyCode::Char('u') if selected > 0 => {
+                self.process_columns.swap(selected, selected - 1);
+                self.column_editor = Some(selected - 1);
+            }
+            KeyCode::Char('d') if selected < last => {
+                self.process_columns.swap(selected, selected + 1);
+                self.column_editor = Some(selected + 1);
+            }
+            KeyCode::Char('r') => self.process_columns = ProcessColumn::defaults(),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.column_editor = None,
+            _ => {}
+        }
+    }
+
+    /// Handles a key while the category legend has focus.
+    fn handle_legend_key(&mut self, code: KeyCode) {
+        let Some(selected) = self.legend_focus else {
+            return;
+        };
+        let last = Category::ALL.len() - 1;
+        match code {
+            KeyCode::Left | KeyCode::Up => self.legend_focus = Some(selected.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Down => self.legend_focus = Some((selected + 1).min(last)),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let category = Category::ALL[selected];
+                self.category_filter = if self.category_filter == Some(category) {
+                    None
+                } else {
+                    Some(category)
+                };
+                self.selected_syscall = 0;
+            }
+            KeyCode::Char('0') => self.category_filter = None,
+            KeyCode::Esc | KeyCode::Tab | KeyCode::Char('q') => self.legend_focus = None,
+            _ => {}
+        }
+    }
+
+    /// Handles a key while the errno panel has focus.
+    fn handle_errno_key(&mut self, code: KeyCode) {
+        let Some(selected) = self.errno_focus else {
+            return;
+        };
+        let last = self.errno_counts.len().saturating_sub(1);
+        match code {
+            KeyCode::Left | KeyCode::Up => self.errno_focus = Some(selected.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Down => self.errno_focus = Some((selected + 1).min(last)),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let errno = self
+                    .sorted_errnos()
+                    .get(selected)
+                    .map(|(errno, _)| errno.to_string());
+                if errno.is_some() {
+                    self.errno_filter = if self.errno_filter == errno {
+                        None
+                    } else {
+                        errno
+                    };
+                    self.selected_syscall = 0;
+                }
+            }
+            KeyCode::Char('0') => self.errno_filter = None,
+            KeyCode::Char(c) if c == self.keymap.key("errnos") => {
+                self.show_errnos = false;
+                self.errno_focus = None;
+            }
+            KeyCode::Esc | KeyCode::Tab | KeyCode::Char('q') => self.errno_focus = None,
+            _ => {}
+        }
+    }
+
+    /// Begins waiting for a not-yet-running process whose name matches `name`.
+    fn start_watch(&mut self, name: String) {
+        // Read afresh: processes started since the list was last refreshed
+        // were not started after the watch.
+        self.watch_known_pids = procfs::pids().unwrap_or_default().into_iter().collect();
+        self.log_event(format!("Watching for process '{}'", name));
+        self.watch_name = name;
+        self.mode = AppMode::Watching;
+    }
+
+    /// Polls for a new process matching the watch name and attaches to the newest one.
+    fn poll_watch(&mut self) {
+        let needle = self.watch_name.to_lowercase();
+        // Run every tick, so only the names of processes started since the
+        // watch began are read; a process is read in full once it matches.
+        // Those that do not are read again, as they may exec the program.
+        let newest = procfs::pids()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pid| !self.watch_known_pids.contains(pid))
+            .filter(|&pid| {
+                procfs::comm(pid).is_ok_and(|comm| comm.to_lowercase().contains(&needle))
+            })
+            .filter_map(Self::get_process)
+            .max_by_key(|p| (p.start_time, p.pid));
+        if let Some(proc) = newest {
+            self.attach(proc);
+        }
+    }
+
+    /// Updates the filtered process list based on the current filter string.
+    ///
+    /// The filter is split on whitespace and a process matches when every term
+    /// appears (case-insensitively) in at least one of its name, command line,
+    /// user or PID.
+    fn update_filtered_processes(&mut self) {
+        let terms: Vec<String> = self
+            .process_filter
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if self.port_owners.is_none() && terms.iter().any(|t| port_term(t).is_some()) {
+            self.port_owners = Some(procfs::listening_ports().unwrap_or_else(|err| {
+                self.report_error(format!("Listening ports are unavailable: {}", err));
+                HashMap::new()
+            }));
+        }
+        let owners = self.port_owners.as_ref();
+        // Recomputed on every refresh, so new children show up.
+        let tree = self
+            .tree_root
+            .map(|root| descendants(&self.processes, root));
+        self.filtered_processes = self
+            .processes
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !(self.hide_own && is_own_process(p)))
+            .filter(|(_, p)| tree.as_ref().is_none_or(|tree| tree.contains(&p.pid)))
+            .filter(|(_, p)| terms.is_empty() || Self::matches_terms(p, &terms, owners))
+            .map(|(i, _)| i)
+            .collect();
+        if self.process_sort == ProcessSort::Threads {
+            let processes = &self.processes;
+            self.filtered_processes.sort_by(|&a, &b| {
+                let (a, b) = (&processes[a], &processes[b]);
+                b.thread_count.cmp(&a.thread_count).then(a.pid.cmp(&b.pid))
+            });
+        }
+        // Stay near the old position when the list shrinks, whether from a
+        // narrower filter or a refresh after processes exited.
+        self.selected_process =
+            clamp_selection(self.selected_process, self.filtered_processes.len());
+    }
+
+    /// Narrows the list to the highlighted process and its descendants, or
+    /// back to every process if it already is.
+    fn toggle_tree_filter(&mut self) {
+        if let Some(root) = self.tree_root.take() {
+            self.log_event(format!(
+                "Showing all processes again (was the tree of PID {})",
+                root
+            ));
+        } else if let Some(p) = self.highlighted_process() {
+            let (pid, name) = (p.pid, p.name.clone());
+            let below = descendants(&self.processes, pid).len() - 1;
+            self.tree_root = Some(pid);
+            self.log_event(format!(
+                "Showing PID {} ({}) and its {} descendant{}",
+                pid,
+                name,
+                below,
+                if below == 1 { "" } else { "s" }
+            ));
+        } else {
+            return;
+        }
+        self.update_filtered_processes();
+        // Keep the root highlighted, which is where the tree starts.
+        if let Some(root) = self.tree_root {
+            if let Some(i) = self
+                .filtered_processes
+                .iter()
+                .position(|&i| self.processes[i].pid == root)
+            {
+                self.selected_process = i;
+            }
+        }
+    }
+
+    /// Whether every lowercase term occurs in one of the process's searchable
+    /// fields; a `:PORT` term instead requires the process to listen on that
+    /// port, a `cg:TEXT` term requires TEXT in its cgroup path or label, and a
+    /// `cap:NAME` term requires the capability in its effective or permitted set.
+    fn matches_terms(
+        p: &ProcessInfo,
+        terms: &[String],
+        port_owners: Option<&HashMap<u16, HashSet<i32>>>,
+    ) -> bool {
+        let fields = [
+            p.name.to_lowercase(),
+            p.cmd.to_lowercase(),
+            p.user.to_lowercase(),
+            p.pid.to_string(),
+        ];
+        terms.iter().all(|term| {
+            if let Some(port) = port_term(term) {
+                return port_owners
+                    .and_then(|owners| owners.get(&port))
+                    .is_some_and(|pids| pids.contains(&p.pid));
+            }
+            if let Some(text) = term.strip_prefix("cg:") {
+                return p.cgroup.as_deref().is_some_and(|path| {
+                    path.to_lowercase().contains(text)
+                        || cgroup_label(path).to_lowercase().contains(text)
+                });
+            }
+            if let Some(name) = term.strip_prefix("cap:") {
+                return p.capabilities.is_some_and(|caps| caps.holds(name));
+            }
+            fields.iter().any(|field| field.contains(term.as_str()))
+        })
+    }
+
+    /// Leaves filter mode, keeping a non-empty query for [`Self::restore_last_filter`].
+    fn end_filtering(&mut self) {
+        self.filter_mode = false;
+        if let Some(pane) = &mut self.compare {
+            pane.filter.clear();
+        }
+        if !self.syscall_filter.is_empty() {
+            self.last_syscall_filter = Some(std::mem::take(&mut self.syscall_filter));
+        }
+    }
+
+    /// Brings back the query from when filtering last ended. The query being
+    /// replaced takes its place, so pressing it again swaps back.
+    fn restore_last_filter(&mut self) {
+        let Some(last) = self.last_syscall_filter.take() else {
+            return;
+        };
+        let current = std::mem::replace(&mut self.syscall_filter, last);
+        if !current.is_empty() {
+            self.last_syscall_filter = Some(current);
+        }
+        self.update_filtered_syscalls();
+    }
+
+    /// Whether syscall `name` passes the fuzzy filter `query`, its terms
+    /// combined and the result inverted as currently set.
+    fn passes_filter(&self, matcher: &SkimMatcherV2, name: &str, query: &str) -> bool {
+        filter_score(matcher, name, query, self.filter_all_terms).is_some() != self.filter_inverted
+    }
+
+    /// Updates the filtered syscall list based on the fuzzy query.
+    ///
+    /// The match score of each result is kept for the score display.
+    fn update_filtered_syscalls(&mut self) {
+        let matcher = SkimMatcherV2::default();
+        self.filter_scores.clear();
+        let mut filtered = Vec::new();
+        for name in &self.unique_syscalls {
+            let score = if self.syscall_filter.trim().is_empty() {
+                0
+            } else {
+                let score =
+                    filter_score(&matcher, name, &self.syscall_filter, self.filter_all_terms);
+                // Inverted, the non-matches are kept, all scoring nothing.
+                match (score, self.filter_inverted) {
+                    (Some(score), false) => score,
+                    (None, true) => 0,
+                    _ => continue,
+                }
+            };
+            if self.shows_syscall(name) {
+                self.filter_scores.insert(name.clone(), score);
+                filtered.push(name.clone());
+            }
+        }
+        self.sort_syscalls(&mut filtered);
+        self.filtered_syscalls = filtered;
+    }
+
+    /// Whether the view filters (category etc.) let syscall `name` through.
+    fn shows_syscall(&self, name: &str) -> bool {
+        (!self.anomalies_only || self.is_anomalous(name))
+            && self.pid_lock.is_none_or(|pid| {
+                self.pid_syscall_counts
+                    .get(&pid)
+                    .is_some_and(|counts| counts.contains_key(name))
+            })
+            && self
+                .category_filter
+                .is_none_or(|category| syscalls::category(name) == category)
+            && self.errno_filter.as_ref().is_none_or(|errno| {
+                self.errno_syscalls
+                    .get(errno)
+                    .is_some_and(|names| names.contains(name))
+            })
+    }
+
+    /// Whether `name` is missing from the loaded known-normal set.
+    fn is_anomalous(&self, name: &str) -> bool {
+        self.normal_set
+            .as_ref()
+            .is_some_and(|normal| !normal.names.contains(name))
+    }
+
+    /// How many of the syscalls seen so far are not known to be normal.
+    fn anomaly_count(&self) -> usize {
+        self.unique_syscalls
+            .iter()
+            .filter(|name| self.is_anomalous(name))
+            .count()
+    }
+
+    /// Errnos seen so far with their counts, most frequent first.
+    fn sorted_errnos(&self) -> Vec<(&str, u64)> {
+        let mut errnos: Vec<(&str, u64)> = self
+            .errno_counts
+            .iter()
+            .map(|(errno, count)| (errno.as_str(), *count))
+            .collect();
+        errnos.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        errnos
+    }
+
+    /// Sums syscall counts per category, in [`Category::ALL`] order.
+    fn category_counts(&self) -> Vec<(Category, u64)> {
+        let mut totals: HashMap<Category, u64> = HashMap::new();
+        for (name, count) in &self.syscall_counts {
+            *totals.entry(syscalls::category(name)).or_insert(0) += count;
+        }
+        Category::ALL
+            .iter()
+            .map(|&c| (c, totals.get(&c).copied().unwrap_or(0)))
+            .collect()
+    }
+
+    /// Sorts syscall entries (names or raw lines) by the current ordering.
+    fn sort_syscalls(&self, entries: &mut [String]) {
+        fn name_of(entry: &str) -> &str {
+            entry.split('(').next().unwrap_or(entry)
+        }
+        match self.syscall_order {
+            SyscallOrder::Alphabetical => entries.sort(),
+            SyscallOrder::ByCount if self.auto_sort => entries.sort_by(|a, b| {
+                let count = |e: &String| self.count_since_baseline(name_of(e));
+                count(b).cmp(&count(a)).then_with(|| a.cmp(b))
+            }),
+            SyscallOrder::ByCount => {
+                let mut position: HashMap<&str, usize> = HashMap::new();
+                for (i, name) in self
+                    .frozen_order
+                    .iter()
+                    .chain(&self.syscall_log)
+                    .enumerate()
+                {
+                    position.entry(name.as_str()).or_insert(i);
+                }
+                entries.sort_by(|a, b| {
+                    let pos = |e: &String| position.get(name_of(e)).copied().unwrap_or(usize::MAX);
+                    pos(a).cmp(&pos(b)).then_with(|| a.cmp(b))
+                });
+            }
+            SyscallOrder::FirstSeen => {
+                let first_seen: HashMap<&str, usize> = self
+                    .syscall_log
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| (name.as_str(), i))
+                    .collect();
+                entries.sort_by(|a, b| {
+                    let pos =
+                        |e: &String| first_seen.get(name_of(e)).copied().unwrap_or(usize::MAX);
+                    pos(a).cmp(&pos(b)).then_with(|| a.cmp(b))
+                });
+            }
+        }
+    }
+
+    /// The color syscall `name` is shown in under the current color mode.
+    fn syscall_color(&self, name: &str) -> Color {
+        match self.color_mode {
+            ColorMode::Category => category_color(syscalls::category(name)),
+            ColorMode::ErrorRate => error_rate_color(
+                self.error_counts.get(name).copied().unwrap_or(0),
+                self.syscall_counts.get(name).copied().unwrap_or(0),
+            ),
+        }
+    }
+
+    /// Names of the syscalls seen so far, most called first.
+    fn live_count_order(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.unique_syscalls.iter().cloned().collect();
+        names.sort_by(|a, b| {
+            let count = |name: &String| self.count_since_baseline(name);
+            count(b).cmp(&count(a)).then_with(|| a.cmp(b))
+        });
+        names
+    }
+
+    /// Re-sorts the frozen count order by the current counts.
+    fn resort(&mut self) {
+        self.frozen_order = self.live_count_order();
+        self.order_stale = false;
+        if self.filter_mode {
+            self.update_filtered_syscalls();
+        }
+    }
+
+    /// Freezes the count order once there are counts to sort by, as at the
+    /// start of a session or after clearing, and notes whether a frozen order
+    /// no longer matches the counts.
+    fn refresh_order(&mut self) {
+        if self.auto_sort || self.syscall_order != SyscallOrder::ByCount {
+            self.order_stale = false;
+            return;
+        }
+        if self.frozen_order.is_empty() && !self.unique_syscalls.is_empty() {
+            self.resort();
+        }
+        self.order_stale = self.frozen_order != self.live_count_order();
+    }
+
+    /// Whether the frozen count order no longer matches the counts.
+    fn order_is_stale(&self) -> bool {
+        self.order_stale
+    }
+
+    /// Spawns an `strace` process for the current trace target.
+    ///
+    /// An attached trace is read from strace's stderr. A launched command shares
+    /// that stderr, so its trace is written to a FIFO instead and only strace's
+    /// own `strace: ` diagnostics are taken from stderr.
+    fn start_strace(&mut self) -> std::io::Result<()> {
+        self.exit_status = None;
+        self.strace_started = Some(Instant::now());
+        self.last_line_at = None;
+        self.strace_errors.clear();
+        self.proc_stats = None;
+        self.counter = None;
+        if let TraceTarget::Attach(pid) = self.trace_target {
+            if self.use_proc_stats() {
+                // Nothing to spawn: the counters are read on each tick.
+                self.proc_stats = Some(procstats::ProcStats::new(pid));
+                self.strace_command.clear();
+                self.attached = true;
+                return Ok(());
+            }
+        }
+        if !self.reading_log() {
+            tracers::check_limit(self.max_tracers)?;
+        }
+        let (tx, rx) = mpsc::channel();
+        self.counter = self.use_counter();
+        let mut child = match (&self.trace_target, &self.counter) {
+            (TraceTarget::Attach(pid), Some(counter)) => {
+                self.strace_command = counter.command(*pid);
+                let mut child = Command::new(&self.strace_command[0])
+                    .args(&self.strace_command[1..])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
+                // The counts come on stdout; errors join stderr below.
+                let stdout = child.stdout.take().expect("Failed to capture stdout");
+                let counts_tx = tx.clone();
+                thread::spawn(move || {
+                    for line in parser::lossy_lines(BufReader::new(stdout)) {
+                        if counts_tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                });
+                child
+            }
+            (TraceTarget::Attach(_) | TraceTarget::Group { .. }, _) => {
+                self.strace_command = self.trace_options.command(&self.trace_target, None);
+                Command::new(&self.strace_command[0])
+                    .args(&self.strace_command[1..])
+                    .stderr(Stdio::piped())
+                    .spawn()?
+            }
+            (TraceTarget::Checkpoint { .. }, _) => {
+                // Nothing to run: the data is filled in from the checkpoint.
+                self.strace_command.clear();
+                return Ok(());
+            }
+            (TraceTarget::Log(path), _) => {
+                // The log's lines go through the same channel strace's would.
+                self.strace_command.clear();
+                let input: Box<dyn Read + Send> = match path {
+                    Some(path) => Box::new(File::open(path)?),
+                    None => Box::new(std::io::stdin()),
+                };
+                thread::spawn(move || {
+                    // A replay file's lines are sent at their recorded offsets.
+                    let started = Instant::now();
+                    let lines = parser::lossy_lines(BufReader::new(input));
+                    for (offset, line) in replay::entries(lines) {
+                        let due = offset.map(|offset| started + offset);
+                        if let Some(wait) =
+                            due.and_then(|due| due.checked_duration_since(Instant::now()))
+                        {
+                            thread::sleep(wait);
+                        }
+                        if tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                });
+                self.strace_receiver = Some(rx);
+                return Ok(());
+            }
+            (TraceTarget::Launch(_), _) => {
+                self.target_pid = 0;
+                let fifo = create_trace_fifo()?;
+                // Opened before strace starts so strace's open of the writing end
+                // does not block; nothing has been read until strace connects.
+                let reader = OpenOptions::new()
+                    .read(true)
+                    .custom_flags(nix::libc::O_NONBLOCK)
+                    .open(&fifo)?;
+                self.strace_command = self.trace_options.command(&self.trace_target, Some(&fifo));
+                let spawned = Command::new(&self.strace_command[0])
+                    .args(&self.strace_command[1..])
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::piped())
+                    .spawn();
+                let child = match spawned {
+                    Ok(child) => child,
+                    Err(err) => {
+                        let _ = std::fs::remove_file(&fifo);
+                        return Err(err);
+                    }
+                };
+                let trace_tx = tx.clone();
+                let path = fifo.clone();
+                thread::spawn(move || read_trace_fifo(reader, &path, trace_tx));
+                self.trace_fifo = Some(fifo);
+                child
+            }
+        };
+
+        let stderr = child.stderr.take().expect("Failed to capture stderr");
+        let diagnostics_only = matches!(self.trace_target, TraceTarget::Launch(_));
+        thread::spawn(move || {
+            let reader = BufReader::new(stderr);
+            for line in parser::lossy_lines(reader) {
+                if diagnostics_only && !line.starts_with("strace: ") {
+                    continue;
+                }
+                let _ = tx.send(line);
+            }
+        });
+
+        if let Err(err) = tracers::register(child.id()) {
+            self.report_error(format!(
+                "Failed to record strace PID {}: {}",
+                child.id(),
+                err
+            ));
+        }
+        self.strace_child = Some(child);
+        self.strace_receiver = Some(rx);
+        Ok(())
+    }
+
+    /// The counting backend the next trace should use: the eBPF backend was
+    /// asked for, the target is a single running process, and eBPF is usable
+    /// here. Otherwise strace is used, saying why.
+    fn use_counter(&mut self) -> Option<Box<dyn counting::Counter>> {
+        if self.backend != TraceBackend::Bpf {
+            return None;
+        }
+        match self.trace_target {
+            TraceTarget::Attach(_) => {}
+            TraceTarget::Group { .. } | TraceTarget::Launch(_) => {
+                self.log_event("The eBPF backend traces single running processes; using strace");
+                return None;
+            }
+            TraceTarget::Log(_) | TraceTarget::Checkpoint { .. } => return None,
+        }
+        let counter = counting::counter()?;
+        match counter.unavailable() {
+            Some(reason) => {
+                self.log_event(format!(
+                    "{} backend unavailable ({}); using strace",
+                    counter.name(),
+                    reason
+                ));
+                // Not worth retrying for every trace of the session.
+                self.backend = TraceBackend::Strace;
+                None
+            }
+            None => Some(counter),
+        }
+    }
+
+    /// Whether the next trace of a single running process should only poll
+    /// its /proc counters: that backend was asked for, or strace (the
+    /// backend otherwise used) is not installed. Other targets need strace.
+    fn use_proc_stats(&mut self) -> bool {
+        match self.backend {
+            TraceBackend::Proc => true,
+            TraceBackend::Strace if !installed("strace") => {
+                self.log_event("strace is not installed; showing coarse /proc activity instead");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles the watched process's /proc counters becoming unreadable,
+    /// which almost always means it exited: like a trace ending, back to the
+    /// process list with a notice.
+    fn proc_stats_ended(&mut self) {
+        let text = format!(
+            "PID {} ({}) can no longer be read in /proc; it has probably exited",
+            self.target_pid, self.target_process_name
+        );
+        self.log_event(text.clone());
+        self.summary = Some(Summary {
+            title: "Trace ended",
+            text,
+        });
+        self.return_to_selection();
+    }
+
+    /// Offers to stop strace processes that an earlier, crashed run left
+    /// attached to their targets. Safe mode only reports them.
+    fn check_orphaned_tracers(&mut self) {
+        let orphans = tracers::orphans();
+        if orphans.is_empty() {
+            return;
+        }
+        let pids: Vec<String> = orphans.iter().map(i32::to_string).collect();
+        self.log_event(format!(
+            "Orphaned strace processes from an earlier run: PID {}",
+            pids.join(", ")
+        ));
+        if self.safe_mode {
+            self.log_event("Not stopping them in safe mode");
+        } else {
+            self.open_prompt(PromptKind::StopOrphans);
+        }
+    }
+
+    /// Offers to resume the newest session checkpointed by an earlier
+    /// instance, if there is one.
+    fn offer_checkpoint(&mut self) {
+        match Checkpoint::latest() {
+            None => {}
+            Some((_, Err(err))) => self.report_error(format!("Checkpoint is unreadable: {}", err)),
+            Some((path, Ok(checkpoint))) => {
+                self.pending_checkpoint = Some(path);
+                self.open_prompt(PromptKind::ResumeCheckpoint);
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.detail = Some(format!(
+                        "{} ({}): {} syscalls, {} calls, saved {}",
+                        checkpoint.target,
+                        checkpoint.name,
+                        checkpoint.calls.len(),
+                        checkpoint.total_calls(),
+                        checkpoint.saved_at
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Shows the data of a checkpointed session again, with the options it
+    /// was traced with. Nothing is traced. The file at `path` stays until the
+    /// resumed session is ended.
+    fn resume_checkpoint(&mut self, path: PathBuf, checkpoint: Checkpoint) {
+        let target = TraceTarget::Checkpoint {
+            description: checkpoint.target.clone(),
+            saved_at: checkpoint.saved_at.clone(),
+        };
+        if let Err(err) = self.begin_session(target, checkpoint.name.clone()) {
+            self.return_to_selection();
+            self.report_error(format!("Failed to resume the checkpoint: {}", err));
+            return;
+        }
+        self.checkpoint_path = Some(path);
+        // Nothing is captured, so there is nothing to time either.
+        self.capture_deadline = None;
+        self.trace_options = TraceOptions {
+            follow_forks: checkpoint.follow_forks,
+            trace_filter: checkpoint.trace_filter,
+            stack_traces: checkpoint.stack_traces,
+            // Checked as if typed, since they apply to the next trace.
+            extra_args: split_command_line(&checkpoint.extra_args)
+                .ok()
+                .filter(|args| check_strace_args(args).is_ok())
+                .unwrap_or_default(),
+        };
+        for (name, count) in checkpoint.calls {
+            self.syscall_counts.insert(name.clone(), count);
+            if self.unique_syscalls.insert(name.clone()) {
+                self.syscall_log.push(name);
+            }
+        }
+        self.error_counts = checkpoint.errors;
+        self.syscall_time = checkpoint.time;
+        for (errno, count, syscalls) in checkpoint.errnos {
+            self.errno_counts.insert(errno.clone(), count);
+            self.errno_syscalls
+                .insert(errno, syscalls.into_iter().collect());
+        }
+        for (offset, text) in checkpoint.notes {
+            self.notes.push((offset, text.clone()));
+            self.push_timeline(TimelineEntry::Note(offset, text));
+        }
+        self.update_filtered_syscalls();
+        self.log_event(format!(
+            "Resumed the checkpoint of {} saved {}; it is not traced",
+            checkpoint.target, checkpoint.saved_at
+        ));
+    }
+
+    /// Checkpoints the live session's data, replacing its last checkpoint.
+    /// Logs, resumed checkpoints and sessions without a syscall yet have
+    /// nothing worth keeping.
+    fn save_checkpoint(&mut self) {
+        self.checkpoint_saved = Instant::now();
+        if !matches!(self.mode, AppMode::SyscallMonitoring)
+            || self.reading_log()
+            || self.syscall_counts.is_empty()
+        {
+            return;
+        }
+        let mut errnos: Vec<(String, u64, Vec<String>)> = self
+            .errno_counts
+            .iter()
+            .map(|(errno, &count)| {
+                let mut syscalls: Vec<String> = self
+                    .errno_syscalls
+                    .get(errno)
+                    .map(|names| names.iter().cloned().collect())
+                    .unwrap_or_default();
+                syscalls.sort();
+                (errno.clone(), count, syscalls)
+            })
+            .collect();
+        errnos.sort();
+        let checkpoint = Checkpoint {
+            target: self.target_description(),
+            name: self.target_process_name.clone(),
+            saved_at: log_time(),
+            trace_filter: self.trace_options.trace_filter.clone(),
+            follow_forks: self.trace_options.follow_forks,
+            stack_traces: self.trace_options.stack_traces,
+            extra_args: shell_join(&self.trace_options.extra_args),
+            calls: self
+                .syscall_log
+                .iter()
+                .map(|name| {
+                    (
+                        name.clone(),
+                        self.syscall_counts.get(name).copied().unwrap_or(0),
+                    )
+                })
+                .collect(),
+            errors: self.error_counts.clone(),
+            time: self.syscall_time.clone(),
+            errnos,
+            notes: self.notes.clone(),
+        };
+        let Some(path) = self
+            .checkpoint_path
+            .clone()
+            .or_else(Checkpoint::session_path)
+        else {
+            return;
+        };
+        self.checkpoint_path = Some(path.clone());
+        if let Err(err) = checkpoint.save(&path) {
+            self.report_error(format!("Failed to checkpoint the session: {}", err));
+        }
+    }
+
+    /// Deletes the current session's checkpoint, as it was ended on purpose.
+    fn discard_checkpoint(&mut self) {
+        let Some(path) = self.checkpoint_path.take() else {
+            return;
+        };
+        if let Err(err) = Checkpoint::discard(&path) {
+            self.report_error(format!("Failed to discard the checkpoint: {}", err));
+        }
+    }
+
+    /// Warns and returns true if `pid` is protected from signals. Every path
+    /// that signals a process other than our own strace children asks first.
+    fn refuse_protected(&mut self, pid: i32) -> bool {
+        let Some(reason) = self.protection.reason(pid) else {
+            return false;
+        };
+        self.log_event(format!("Refused to signal PID {}: {}", pid, reason));
+        self.summary = Some(Summary {
+            title: "Protected process",
+            text: format!("{}, so it is not signalled (config key protected)", reason),
+        });
+        true
+    }
+
+    /// Stops the running strace process.
+    fn stop_strace(&mut self) {
+        // Polling stops too, but the graphs stay on screen.
+        if let Some(stats) = &mut self.proc_stats {
+            stats.ended = true;
+        }
+        if let Some(mut child) = self.strace_child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = tracers::unregister(child.id());
+        }
+        self.strace_receiver = None;
+        if let Some(fifo) = self.trace_fifo.take() {
+            let _ = std::fs::remove_file(fifo);
+        }
+    }
+}
+
+impl Drop for App {
+    /// Makes sure strace never outlives the app, whichever way it exits.
+    fn drop(&mut self) {
+        self.stop_strace();
+    }
+}
+
+/// Set by the handler for termination signals; the event loop then exits
+/// normally so strace is stopped and the terminal restored.
+static TERMINATE: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_terminate(_: nix::libc::c_int) {
+    TERMINATE.store(true, Ordering::Relaxed);
+}
+
+/// Routes SIGTERM, SIGHUP and SIGINT to [`TERMINATE`] instead of killing the
+/// process outright, which would leave strace attached to its target.
+fn install_signal_handlers() -> nix::Result<()> {
+    let action = SigAction::new(
+        SigHandler::Handler(request_terminate),
+        SaFlags::empty(),
+        SigSet::empty(),
+    );
+    for sig in [Signal::SIGTERM, Signal::SIGHUP, Signal::SIGINT] {
+        // SAFETY: the handler only stores to an atomic, which is async-signal-safe.
+        unsafe { signal::sigaction(sig, &action) }?;
+    }
+    Ok(())
+}
+
+/// Creates a uniquely named FIFO in the temporary directory for a launched trace.
+fn create_trace_fifo() -> std::io::Result<PathBuf> {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "syscall-monitor-{}-{}.trace",
+        std::process::id(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    mkfifo(&path, Mode::S_IRUSR | Mode::S_IWUSR)?;
+    Ok(path)
+}
+
+/// Forwards lines from a launched trace's FIFO until strace closes it.
+///
+/// Until strace opens its end, a read would report end of file, so the FIFO is
+/// polled instead; on Linux it only signals a hang-up after a writer has
+/// connected. The thread also gives up if the FIFO is removed before then,
+/// which happens when tracing is stopped early.
+fn read_trace_fifo(reader: File, path: &Path, tx: Sender<String>) {
+    loop {
+        let mut fds = [PollFd::new(reader.as_raw_fd(), PollFlags::POLLIN)];
+        match poll(&mut fds, 200) {
+            Ok(n) if n > 0 => break,
+            Ok(_) | Err(nix::errno::Errno::EINTR) => {
+                if !path.exists() {
+                    return;
+                }
+            }
+            Err(_) => return,
+        }
+    }
+    if fcntl(reader.as_raw_fd(), FcntlArg::F_SETFL(OFlag::empty())).is_err() {
+        return;
+    }
+    for line in parser::lossy_lines(BufReader::new(reader)) {
+        if tx.send(line).is_err() {
+            return;
+        }
+    }
+}
+
+/// The hint appended to a failure to start strace: privileges are the usual
+/// cause, but not when the tracer limit refused it.
+fn root_hint(err: &std::io::Error) -> &'static str {
+    if err.kind() == std::io::ErrorKind::QuotaExceeded {
+        ""
+    } else {
+        " (Are you root?)"
+    }
+}
+
+/// Explains why a setuid or setgid process is hard to trace and what to do
+/// instead, or `None` if `pid` runs with the IDs of whoever started it.
+fn setuid_help(pid: i32) -> Option<String> {
+    let ids = procfs::credentials(pid).ok().filter(|ids| ids.elevated())?;
+    let change = if ids.real_uid != ids.effective_uid {
+        format!(
+            "setuid (real UID {}, effective UID {})",
+            ids.real_uid, ids.effective_uid
+        )
+    } else {
+        format!(
+            "setgid (real GID {}, effective GID {})",
+            ids.real_gid, ids.effective_gid
+        )
+    };
+    Some(format!(
+        "PID {} runs {}. The kernel marks such processes undumpable and only lets tracers with CAP_SYS_PTRACE attach, so attaching fails even for the user who started it: run the inspector as root. Without root, launch the program under trace with Ctrl+R instead; the kernel then ignores its setuid bit, so it runs with your own IDs and may fail where it needs its privileges.",
+        pid, change
+    ))
+}
+
+/// Parses a `:8080` process filter term into its port.
+fn port_term(term: &str) -> Option<u16> {
+    term.strip_prefix(':')?.parse().ok()
+}
+
+/// Turns a cgroup path into a short label naming the container or systemd
+/// unit it belongs to, e.g. `docker:3f2a9c1b7d4e` or `nginx.service`; other
+/// paths are shown as they are.
+fn cgroup_label(path: &str) -> String {
+    let short = |id: &str| id.chars().take(12).collect::<String>();
+    let last = path.rsplit('/').next().unwrap_or(path);
+    let unit = last.strip_suffix(".scope").unwrap_or(last);
+    // Container runtimes under systemd name the scope `<runtime>-<id>.scope`,
+    // and without systemd they nest the container ID under `/<runtime>/`.
+    for (prefix, runtime) in [
+        ("docker-", "docker"),
+        ("cri-containerd-", "containerd"),
+        ("crio-", "cri-o"),
+        ("libpod-", "podman"),
+    ] {
+        if let Some(id) = unit.strip_prefix(prefix) {
+            return format!("{}:{}", runtime, short(id));
+        }
+    }
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    for (dir, runtime) in [("docker", "docker"), ("containerd", "containerd")] {
+        if let Some(i) = parts.iter().position(|p| *p == dir) {
+            if let Some(id) = parts.get(i + 1) {
+                return format!("{}:{}", runtime, short(id));
+            }
+        }
+    }
+    if let Some(name) = parts
+        .iter()
+        .find_map(|p| p.strip_prefix("lxc.payload."))
+        .or_else(|| {
+            let i = parts.iter().position(|p| *p == "lxc")?;
+            parts.get(i + 1).copied()
+        })
+    {
+        return format!("lxc:{}", name);
+    }
+    if parts.iter().any(|p| p.starts_with("kubepods")) {
+        return format!("k8s:{}", short(last));
+    }
+    if last.ends_with(".service") || last.ends_with(".scope") || last.ends_with(".slice") {
+        return last.to_string();
+    }
+    path.to_string()
+}
+
+/// Lists the PIDs in a process group or session, leaving out this tool itself
+/// (a session started from its terminal includes it). Entries that cannot be
+/// read are skipped.
+fn group_members(kind: GroupKind, id: i32) -> Vec<i32> {
+    // 0 stands for a group or session outside this PID namespace.
+    if id <= 0 {
+        return Vec::new();
+    }
+    let own = std::process::id() as i32;
+    let mut members: Vec<i32> = procfs::pids()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|&pid| pid != own)
+        .filter(|&pid| {
+            procfs::group_and_session(pid).is_ok_and(|(pgid, sid)| match kind {
+                GroupKind::ProcessGroup => pgid == id,
+                GroupKind::Session => sid == id,
+            })
+        })
+        .collect();
+    members.sort_unstable();
+    members
+}
+
+/// Describes the syscall `pid` is currently blocked in, from `/proc/<pid>/syscall`.
+fn read_current_syscall(pid: i32) -> String {
+    match procfs::syscall_state(pid) {
+        Ok(procfs::SyscallState::Running) => "running".to_string(),
+        Ok(procfs::SyscallState::NotInSyscall) => "blocked (not in a syscall)".to_string(),
+        Ok(procfs::SyscallState::In(nr)) => match syscalls::syscall_name(nr) {
+            Some(name) => format!("in {}", name),
+            None => format!("in syscall {}", nr),
+        },
+        Err(_) => "unavailable".to_string(),
+    }
+}
+
+/// Writes `contents` to `path` and waits for it to reach the disk, so an
+/// export confirmed just before quitting is complete once the tool exits.
+fn write_synced(path: &str, contents: &str) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()
+}
+
+/// Joins command-line arguments for display, quoting any that the shell would split.
+fn shell_join(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_=./,:%+@".contains(c))
+            {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Rejects extra strace arguments that would take over what the inspector
+/// manages or change what the traced process does: where the trace goes
+/// (`-o`), what is traced (`-p`, or a command after `--`), strace running
+/// detached from the inspector (`-D`) or detaching by itself (`-b`), strace
+/// killing what it traces when it is stopped (`--kill-on-exit`), the user and
+/// environment a launched command runs with (`-u`, `-E`), and injecting faults
+/// or results into syscalls. Anything else is passed on unchecked.
+fn check_strace_args(args: &[String]) -> Result<(), String> {
+    // Short options that take a value, which may be glued to them.
+    const WITH_VALUE: &str = "abeEIoOpPsSuUX";
+    const DETACHED: &str = "strace would run apart from the inspector, which could not stop it";
+    const DETACHING: &str = "strace would detach by itself and end the trace";
+    let refuse = |arg: &str, why: &str| Err(format!("{} is not allowed: {}", arg, why));
+    let check_expression = |arg: &str, expression: &str| {
+        if expression.starts_with("inject=") || expression.starts_with("fault=") {
+            refuse(arg, "it tampers with the traced process's syscalls")
+        } else {
+            Ok(())
+        }
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            return refuse(arg, "it would launch a command");
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = long.split_once('=').unwrap_or((long, ""));
+            match name {
+                "output" | "output-separately" | "output-append-mode" => {
+                    return refuse(arg, "the inspector reads strace's output itself")
+                }
+                "attach" => return refuse(arg, "pick processes in the process list"),
+                "daemonize" => return refuse(arg, DETACHED),
+                "detach-on" => return refuse(arg, DETACHING),
+                "kill-on-exit" => {
+                    return refuse(arg, "stopping the trace would kill the traced process")
+                }
+                "user" | "env" => return refuse(arg, "it changes how a command runs"),
+                "inject" | "fault" => {
+                    return refuse(arg, "it tampers with the traced process's syscalls")
+                }
+                "trace" | "expr" => check_expression(arg, value)?,
+                _ => {}
+            }
+            continue;
+        }
+        let Some(cluster) = arg.strip_prefix('-') else {
+            continue;
+        };
+        for (i, flag) in cluster.char_indices() {
+            match flag {
+                'o' | 'A' => return refuse(arg, "the inspector reads strace's output itself"),
+                'p' => return refuse(arg, "pick processes in the process list"),
+                'D' => return refuse(arg, DETACHED),
+                'b' => return refuse(arg, DETACHING),
+                'u' | 'E' => return refuse(arg, "it changes how a command runs"),
+                _ => {}
+            }
+            if WITH_VALUE.contains(flag) {
+                let glued = &cluster[i + flag.len_utf8()..];
+                let value = if glued.is_empty() {
+                    args.next().map_or("", String::as_str)
+                } else {
+                    glued
+                };
+                if flag == 'e' {
+                    check_expression(arg, value)?;
+                }
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits a command line into arguments, honouring single quotes, double quotes
+/// and backslash escapes the way a POSIX shell does (without any expansion).
+fn split_command_line(input: &str) -> Result<Vec<String>, &'static str> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Distinguishes an empty quoted argument (`''`) from no argument at all.
+    let mut in_word = false;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated single quote"),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("unterminated double quote"),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated double quote"),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some(c) => current.push(c),
+                    None => return Err("trailing backslash"),
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Reads the syscall names of a known-normal set: the first field of each
+/// line, so both a summary export (`read,42,file`) and a plain list with one
+/// name per line work. The CSV header, blank lines and `#` comments are
+/// skipped.
+fn parse_normal_set(contents: &str) -> HashSet<String> {
+    contents
+        .lines()
+        .map(|line| line.split(',').next().unwrap_or_default().trim())
+        .filter(|name| !name.is_empty() && !name.starts_with('#') && *name != "syscall")
+        .map(str::to_string)
+        .collect()
+}
+
+/// Keeps a list selection within `len` items, moving it to the last one if the
+/// list shrank past it (0 for an empty list).
+fn clamp_selection(selected: usize, len: usize) -> usize {
+    selected.min(len.saturating_sub(1))
+}
+
+/// Describes how a process ended, e.g. `exited with code 1` or `killed by SIGSEGV`.
+fn describe_exit_status(status: ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exited with code {}", code);
+    }
+    match status.signal() {
+        Some(sig) => match Signal::try_from(sig) {
+            Ok(sig) => format!("killed by {}", sig.as_str()),
+            Err(_) => format!("killed by signal {}", sig),
+        },
+        None => "ended".to_string(),
+    }
+}
+
+/// Whether `program` is an executable file in one of the `PATH` directories.
+fn installed(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| Path::new(&dir).join(program).is_file())
+    })
+}
+
+/// A note's time into the session, e.g. `+12.3s`.
+fn note_offset(offset: Duration) -> String {
+    format!("+{:.1}s", offset.as_secs_f64())
+}
+
+/// A note as a comment line of an export, which the known-normal set loader
+/// and most log tools skip.
+fn note_line(offset: Duration, text: &str) -> String {
+    format!("# note {}: {}\n", note_offset(offset), text)
+}
+
+/// Formats a byte count with a binary unit suffix, e.g. `12.3M`.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+/// Formats a syscall latency with a unit that keeps it short, e.g. `12.3µs`.
+fn format_latency(latency: Duration) -> String {
+    let secs = latency.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{:.2}s", secs)
+    } else if secs >= 1e-3 {
+        format!("{:.1}ms", secs * 1e3)
+    } else {
+        format!("{:.1}µs", secs * 1e6)
+    }
+}
+
+/// Parses a duration such as `30`, `30s`, `5m` or `1h` (bare numbers are seconds).
+fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let (number, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => input.split_at(idx),
+        None => (input, "s"),
+    };
+    let number: u64 = number.parse().ok()?;
+    let secs = match unit.trim() {
+        "s" => number,
+        "m" => number.checked_mul(60)?,
+        "h" => number.checked_mul(3600)?,
+        _ => return None,
+    };
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Formats the current local time as `HH:MM:SS`.
+fn clock_time() -> String {
+    let tm = local_time();
+    format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec)
+}
+
+/// Local date and time for log lines, as `YYYY-MM-DD HH:MM:SS`.
+fn log_time() -> String {
+    let tm = local_time();
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Local date and time for file names, as `YYYYMMDD-HHMMSS`.
+fn file_timestamp() -> String {
+    let tm = local_time();
+    format!(
+        "{}{:02}{:02}-{:02}{:02}{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// The current local time, broken down.
+fn local_time() -> nix::libc::tm {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as nix::libc::time_t)
+        .unwrap_or(0);
+    // SAFETY: `localtime_r` only writes into the zeroed `tm` we hand it.
+    unsafe {
+        let mut tm: nix::libc::tm = std::mem::zeroed();
+        nix::libc::localtime_r(&now, &mut tm);
+        tm
+    }
+}
+
+/// Prints command-line usage to stdout.
+fn print_usage() {
+    println!("Usage: syscall-monitor [OPTIONS] [PID]");
+    println!();
+    println!("Options:");
+    println!("  --safe             Read-only mode: disable killing and signalling processes");
+    println!("  -i, --input FILE   Show an strace log captured elsewhere (`-` reads stdin)");
+    println!("  --record FILE      Save the first session's lines with their timing, for --input");
+    println!(
+        "  --backend NAME     strace (default); bpf: count syscalls with eBPF, at less overhead;"
+    );
+    println!(
+        "                     proc: only graph coarse /proc activity, for where ptrace is not allowed"
+    );
+    println!("  --json             Without the TUI, stream each syscall to stdout as a JSON line");
+    println!("  --summary          Without the TUI, print a table of syscall counts at the end");
+    println!(
+        "  -p, --pid PID      The process to trace, skipping the selection screen (same as PID)"
+    );
+    println!("  -h, --help         Show this help");
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = Config::load();
+    let mut input: Option<Option<PathBuf>> = None;
+    let mut batch_output: Option<batch::Output> = None;
+    let mut pid: Option<i32> = None;
+    let mut record: Option<PathBuf> = None;
+    let mut trace_backend = TraceBackend::Strace;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--safe" => config.safe_mode = true,
+            "-i" | "--input" => {
+                let Some(path) = args.next() else {
+                    eprintln!("{} needs a file name (or - for standard input)", arg);
+                    std::process::exit(2);
+                };
+                if path == "-" {
+                    input = Some(None);
+                } else if let Err(err) = File::open(&path) {
+                    eprintln!("Cannot read {}: {}", path, err);
+                    std::process::exit(1);
+                } else {
+                    input = Some(Some(PathBuf::from(path)));
+                }
+            }
+            "--record" => match args.next() {
+                Some(path) => record = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("{} needs a file name", arg);
+                    std::process::exit(2);
+                }
+            },
+            "--backend" => match args.next().as_deref() {
+                Some("strace") => trace_backend = TraceBackend::Strace,
+                Some("bpf" | "ebpf") if counting::counter().is_none() => {
+                    eprintln!("This build has no eBPF backend; rebuild with --features bpf");
+                    std::process::exit(2);
+                }
+                Some("bpf" | "ebpf") => trace_backend = TraceBackend::Bpf,
+                Some("proc") => trace_backend = TraceBackend::Proc,
+                _ => {
+                    eprintln!("{} needs strace, bpf or proc", arg);
+                    std::process::exit(2);
+                }
+            },
+            "--json" | "--summary" => {
+                let output = if arg == "--json" {
+                    batch::Output::Json
+                } else {
+                    batch::Output::Summary
+                };
+                if batch_output.is_some_and(|o| o != output) {
+                    eprintln!("--json and --summary cannot be combined");
+                    std::process::exit(2);
+                }
+                batch_output = Some(output);
+            }
+            "-p" | "--pid" => match args.next().and_then(|p| p.parse().ok()) {
+                Some(p) if p > 0 => pid = Some(p),
+                _ => {
+                    eprintln!("{} needs a process ID", arg);
+                    std::process::exit(2);
+                }
+            },
+            "-h" | "--help" => {
+                print_usage();
+                return Ok(());
+            }
+            other if !other.starts_with('-') && pid.is_none() => match other.parse() {
+                Ok(p) if p > 0 => pid = Some(p),
+                _ => {
+                    eprintln!("Not a process ID: {}", other);
+                    print_usage();
+                    std::process::exit(2);
+                }
+            },
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                print_usage();
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if let Some(output) = batch_output {
+        if record.is_some() {
+            eprintln!("--record is only used in the TUI");
+            std::process::exit(2);
+        }
+        if trace_backend != TraceBackend::Strace {
+            eprintln!("Batch mode always uses strace");
+            std::process::exit(2);
+        }
+        let source = match (pid, input) {
+            (Some(pid), None) => batch::Source::Attach(pid),
+            (None, Some(path)) => batch::Source::Log(path),
+            _ => {
+                eprintln!("Batch mode needs exactly one of --pid PID or --input FILE");
+                std::process::exit(2);
+            }
+        };
+        // Only a trace is stopped on a signal (ending it with the summary);
+        // reading a log, which may block on stdin for good, is left to be
+        // killed by it as usual.
+        if let batch::Source::Attach(_) = source {
+            install_signal_handlers()?;
+        }
+        if let Err(err) = batch::run(source, output, config.max_tracers) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if pid.is_some() && input.is_some() {
+        eprintln!("A process ID and --input cannot be combined");
+        std::process::exit(2);
+    }
+    if let (Some(record), Some(Some(input))) = (&record, &input) {
+        // Creating the recording would truncate the log before it is read.
+        let file_id = |path: &Path| std::fs::metadata(path).map(|m| (m.dev(), m.ino())).ok();
+        if file_id(record).is_some() && file_id(record) == file_id(input) {
+            eprintln!("--record cannot write to the file --input reads");
+            std::process::exit(2);
+        }
+    }
+    let recorder = match record.map(replay::Recorder::create).transpose() {
+        Ok(recorder) => recorder,
+        Err(err) => {
+            eprintln!("Cannot record the session: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // Set up terminal.
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    if config.mouse_capture {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Update every 200ms.
-    let tick_rate = Duration::from_millis(200);
-    let mut app = App::new();
-    let res = run_app(&mut terminal, &mut app, tick_rate);
+    // Update every 200ms.
+    let tick_rate = Duration::from_millis(200);
+    install_signal_handlers()?;
+    let mut app = App::new(&config, &UiState::load());
+    app.check_orphaned_tracers();
+    app.recorder = recorder;
+    app.backend = trace_backend;
+    if let Some(path) = input {
+        app.open_log(path);
+    }
+    if let Some(pid) = pid {
+        app.attach_pid(pid);
+    } else if app.prompt.is_none() && matches!(app.mode, AppMode::ProcessSelection) {
+        app.offer_checkpoint();
+    }
+    let res = run_app(&mut terminal, &mut app, tick_rate);
+    // A termination signal can arrive between confirming an export and
+    // writing it.
+    app.write_pending_export();
+    app.save_checkpoint();
+    app.stop_strace();
+    app.finish_recording();
+    app.restore_profile_settings();
+    // Losing the saved UI state is not worth failing the exit over.
+    let _ = app.ui_state().save();
+
+    // Restore terminal.
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    if let Err(err) = res {
+        println!("{:?}", err);
+    }
+    Ok(())
+}
+
+/// The main event loop.
+fn run_app<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    tick_rate: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Declare last_tick inside run_app.
+    let mut last_tick = Instant::now();
+    let mut last_draw = Instant::now();
+
+    loop {
+        if TERMINATE.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        // Input is polled every tick, but the screen is only redrawn when the
+        // state changed or it has been idle for a while.
+        if app.dirty || last_draw.elapsed() >= IDLE_REDRAW {
+            draw(terminal, app)?;
+            app.dirty = false;
+            last_draw = Instant::now();
+        }
+        // Written before any further input is read, so a quit typed right
+        // after confirming an export waits for the file to be complete.
+        if app.write_pending_export() {
+            app.dirty = true;
+            continue;
+        }
+
+        let timeout = tick_rate
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0));
+        if crossterm::event::poll(timeout)? {
+            let event = event::read()?;
+            // Any input may change what is shown, including a resize.
+            app.dirty = true;
+            if let CEvent::Key(_) | CEvent::Mouse(_) = event {
+                app.last_input = Instant::now();
+            }
+            if let CEvent::Key(key) = event {
+                // Works everywhere, so a snapshot can show any popup.
+                if key.code == KeyCode::Char('g') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    app.snapshot_requested = true;
+                } else if app.show_tutorial {
+                    app.show_tutorial = false;
+                    app.tutorial_seen = true;
+                    // Saved now so a crash cannot bring the tutorial back.
+                    let _ = app.ui_state().save();
+                } else if app.summary.is_some() {
+                    app.summary = None;
+                } else if app.session_report.is_some() {
+                    // The counts are kept until the next session starts, so
+                    // they can still be exported from here.
+                    app.session_report = None;
+                    if key.code == KeyCode::Char(app.keymap.key("export")) {
+                        app.open_export_prompt(ExportKind::Summary);
+                    }
+                } else if app.prompt.is_some() {
+                    app.handle_prompt_key(key);
+                } else if app.column_editor.is_some() {
+                    app.handle_column_key(key.code);
+                } else if app.legend_focus.is_some() {
+                    app.handle_legend_key(key.code);
+                } else if app.errno_focus.is_some() {
+                    app.handle_errno_key(key.code);
+                } else if app.pid_picker.is_some() {
+                    app.handle_pid_picker_key(key.code);
+                } else if app.resources_scroll.is_some() {
+                    app.handle_resources_key(key.code);
+                } else if app.maps_view.is_some() {
+                    app.handle_maps_key(key);
+                } else if app.leaderboard.is_some() {
+                    app.handle_leaderboard_key(key);
+                } else if app.man_page.is_some() {
+                    app.handle_man_page_key(key.code);
+                } else {
+                    match app.mode {
+                        AppMode::ProcessSelection => match key.code {
+                            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.show_events = !app.show_events;
+                            }
+                            KeyCode::Char('w')
+                                if key.modifiers.contains(KeyModifiers::CONTROL)
+                                    && !app.process_filter.is_empty() =>
+                            {
+                                app.start_watch(app.process_filter.clone());
+                            }
+                            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.open_prompt(PromptKind::CaptureDuration);
+                            }
+                            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.process_sort = app.process_sort.next();
+                                app.update_filtered_processes();
+                            }
+                            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.open_prompt(PromptKind::LaunchCommand);
+                            }
+                            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.set_mouse_capture(!app.mouse_capture);
+                            }
+                            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.column_editor = Some(0);
+                            }
+                            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.copy_process_cwd();
+                            }
+                            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.show_process_capabilities();
+                            }
+                            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.toggle_compare_candidate();
+                            }
+                            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.toggle_tree_filter();
+                            }
+                            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.open_leaderboard();
+                            }
+                            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.show_memory_map();
+                            }
+                            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.hide_own = !app.hide_own;
+                                app.update_filtered_processes();
+                                app.log_event(if app.hide_own {
+                                    "Hiding this inspector and its strace processes"
+                                } else {
+                                    "Showing this inspector and its strace processes"
+                                });
+                            }
+                            KeyCode::Char(c) if c == app.keymap.key("quit") => return Ok(()),
+                            KeyCode::Char(c) => {
+                                app.process_filter.push(c);
+                                app.update_filtered_processes();
+                            }
+                            KeyCode::Backspace => {
+                                app.process_filter.pop();
+                                app.update_filtered_processes();
+                            }
+                            KeyCode::Down
+                                if app.selected_process + 1 < app.filtered_processes.len() =>
+                            {
+                                app.selected_process += 1;
+                            }
+                            KeyCode::Up if app.selected_process > 0 => {
+                                app.selected_process -= 1;
+                            }
+                            KeyCode::Enter => {
+                                if let Some(proc) = app.highlighted_process().cloned() {
+                                    app.choose_attach_scope(proc);
+                                }
+                            }
+                            _ => {}
+                        },
+                        AppMode::Watching => {
+                            if let KeyCode::Esc | KeyCode::Char('q') = key.code {
+                                app.log_event(format!("Stopped watching for '{}'", app.watch_name));
+                                app.return_to_selection();
+                            }
+                        }
+                        AppMode::SyscallMonitoring => {
+                            if app.filter_mode {
+                                // Fuzzy filtering mode.
+                                match key.code {
+                                    KeyCode::Char('d')
+                                        if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                    {
+                                        app.show_filter_scores = !app.show_filter_scores;
+                                    }
+                                    KeyCode::Char('z')
+                                        if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                    {
+                                        app.restore_last_filter();
+                                    }
+                                    KeyCode::Char('v')
+                                        if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                    {
+                                        app.filter_inverted = !app.filter_inverted;
+                                        app.update_filtered_syscalls();
+                                    }
+                                    KeyCode::Char(c) => match &mut app.compare {
+                                        Some(pane) if app.compare_focus => {
+                                            pane.filter.push(c);
+                                            pane.selected = 0;
+                                        }
+                                        _ => {
+                                            app.syscall_filter.push(c);
+                                            app.update_filtered_syscalls();
+                                        }
+                                    },
+                                    KeyCode::Backspace => match &mut app.compare {
+                                        Some(pane) if app.compare_focus => {
+                                            pane.filter.pop();
+                                        }
+                                        _ => {
+                                            app.syscall_filter.pop();
+                                            app.update_filtered_syscalls();
+                                        }
+                                    },
+                                    KeyCode::Tab if app.compare.is_some() => {
+                                        app.compare_focus = !app.compare_focus;
+                                    }
+                                    KeyCode::Enter | KeyCode::Esc => app.end_filtering(),
+                                    KeyCode::Down => app.move_selection(1),
+                                    KeyCode::Up => app.move_selection(-1),
+                                    _ => {}
+                                }
+                            } else {
+                                // Live monitoring mode. Letter keys are
+                                // matched by the action bound to them.
+                                let action = match key.code {
+                                    KeyCode::Char(c)
+                                        if !key.modifiers.contains(KeyModifiers::CONTROL) =>
+                                    {
+                                        app.keymap.action(c)
+                                    }
+                                    _ => None,
+                                };
+                                match (action, key.code) {
+                                    (Some("quit" | "back"), _) => {
+                                        app.stop_strace();
+                                        app.log_event(format!(
+                                            "Detached from {}",
+                                            app.target_description()
+                                        ));
+                                        // Built before the next session
+                                        // clears the counts it reads.
+                                        app.session_report = Some(app.session_report());
+                                        app.return_to_selection();
+                                    }
+                                    (
+                                        Some(
+                                            "kill" | "timed_capture" | "trace_filter"
+                                            | "follow_forks" | "stack_traces" | "restart",
+                                        ),
+                                        _,
+                                    ) if app.reading_log() => {
+                                        app.log_event("Not available without a live trace");
+                                    }
+                                    (Some("kill"), _) if app.safe_mode => {
+                                        app.log_event("Kill is disabled in safe mode");
+                                    }
+                                    (Some("kill"), _) if app.target_pid <= 0 => {
+                                        app.log_event(
+                                            "PID of the launched command is not known yet",
+                                        );
+                                    }
+                                    (Some("kill"), _) if app.refuse_protected(app.target_pid) => {}
+                                    (Some("kill"), _) => {
+                                        let pid = app.target_pid;
+                                        match signal::kill(Pid::from_raw(pid), Signal::SIGKILL) {
+                                            Ok(()) => app
+                                                .log_event(format!("Sent SIGKILL to PID {}", pid)),
+                                            Err(err) => app.report_error(format!(
+                                                "Failed to kill PID {}: {}",
+                                                pid, err
+                                            )),
+                                        }
+                                        app.return_to_selection();
+                                    }
+                                    (Some("filter"), _) => {
+                                        app.filter_mode = true;
+                                        app.update_filtered_syscalls();
+                                    }
+                                    (Some("details"), _) => {
+                                        app.show_detailed = !app.show_detailed;
+                                        app.dashboard = false;
+                                    }
+                                    (Some("dashboard"), _) => {
+                                        app.dashboard = !app.dashboard;
+                                        app.show_detailed = false;
+                                        app.show_stats = false;
+                                    }
+                                    (_, KeyCode::Char(c @ ('+' | '=' | '-'))) if app.dashboard => {
+                                        let step: i16 = if c == '-' { -10 } else { 10 };
+                                        app.dashboard_split = app
+                                            .dashboard_split
+                                            .saturating_add_signed(step)
+                                            .clamp(DASHBOARD_SPLIT_MIN, DASHBOARD_SPLIT_MAX);
+                                    }
+                                    (Some("stats"), _) => {
+                                        app.show_stats = !app.show_stats;
+                                        app.dashboard = false;
+                                        app.show_detailed = false;
+                                        app.selected_syscall = 0;
+                                    }
+                                    (_, KeyCode::Left | KeyCode::Right)
+                                        if app.show_stats && !app.show_detailed =>
+                                    {
+                                        let step = if key.code == KeyCode::Left { -1 } else { 1 };
+                                        let hidden = (StatsColumn::ALL.len() - 1)
+                                            .saturating_sub(app.stats_columns_fit);
+                                        // Too narrow for every column: the
+                                        // arrows scroll the ones after the
+                                        // name, and Shift sorts instead.
+                                        if hidden > 0
+                                            && !key.modifiers.contains(KeyModifiers::SHIFT)
+                                        {
+                                            app.stats_hscroll = app
+                                                .stats_hscroll
+                                                .saturating_add_signed(step)
+                                                .min(hidden);
+                                        } else {
+                                            app.stats_sort = app.stats_sort.shifted(step);
+                                        }
+                                    }
+                                    (_, KeyCode::Down) => app.move_selection(1),
+                                    (_, KeyCode::Up) => app.move_selection(-1),
+                                    (Some("events"), _) => {
+                                        app.show_events = !app.show_events;
+                                    }
+                                    (Some("mouse"), _) => {
+                                        app.set_mouse_capture(!app.mouse_capture);
+                                    }
+                                    (Some("command"), _) => {
+                                        app.show_command = !app.show_command;
+                                    }
+                                    (Some("sort"), _) => {
+                                        app.syscall_order = app.syscall_order.next();
+                                        if app.syscall_order == SyscallOrder::ByCount {
+                                            app.resort();
+                                        }
+                                    }
+                                    (Some("resort"), _) => app.resort(),
+                                    (Some("colors"), _) => {
+                                        app.color_mode = app.color_mode.next();
+                                    }
+                                    (Some("auto_sort"), _) => {
+                                        app.auto_sort = !app.auto_sort;
+                                        if !app.auto_sort {
+                                            app.resort();
+                                        }
+                                        app.log_event(if app.auto_sort {
+                                            "Auto-sort on: the count order follows the counts"
+                                        } else {
+                                            "Auto-sort off: the count order changes only with u"
+                                        });
+                                    }
+                                    (Some("show_all"), _) => {
+                                        app.show_all = !app.show_all;
+                                    }
+                                    (Some("timed_capture"), _) => {
+                                        app.open_prompt(PromptKind::CaptureDuration);
+                                    }
+                                    (Some("trace_filter"), _) => {
+                                        app.open_prompt(PromptKind::TraceFilter);
+                                    }
+                                    (Some("note"), _) => app.open_prompt(PromptKind::Note),
+                                    (Some("manual"), _) => app.show_man_page(),
+                                    (Some("strace_args"), _) => {
+                                        let current = shell_join(&app.trace_options.extra_args);
+                                        app.open_prompt_with(PromptKind::StraceArgs, current, None);
+                                    }
+                                    (Some("follow_forks"), _) => {
+                                        app.trace_options.follow_forks =
+                                            !app.trace_options.follow_forks;
+                                        app.options_changed = true;
+                                    }
+                                    (Some("restart"), _) => {
+                                        app.open_prompt(PromptKind::RestartConfirm);
+                                    }
+                                    (Some("save_profile"), _) => app.save_profile(),
+                                    (Some("export"), _) => app.start_export(),
+                                    (Some("expand"), _) => {
+                                        app.expand_line = !app.expand_line;
+                                    }
+                                    (Some("relative_times"), _) => {
+                                        app.relative_times = !app.relative_times;
+                                    }
+                                    (Some("stack_traces"), _) => {
+                                        app.trace_options.stack_traces =
+                                            !app.trace_options.stack_traces;
+                                        app.options_changed = true;
+                                    }
+                                    (Some("callers"), _) => {
+                                        app.show_callers = !app.show_callers;
+                                    }
+                                    (Some("copy"), _) => app.copy_selection(),
+                                    (Some("follow_fd"), _) => {
+                                        let current = app
+                                            .fd_filter
+                                            .map(|fd| fd.to_string())
+                                            .unwrap_or_default();
+                                        app.open_prompt_with(PromptKind::FdFilter, current, None);
+                                    }
+                                    (Some("lock_pid"), _) => {
+                                        // Start on the locked PID's row, or on "all".
+                                        let current = app.pid_lock.and_then(|pid| {
+                                            app.pid_totals().iter().position(|(p, _)| *p == pid)
+                                        });
+                                        app.pid_picker = Some(current.map_or(0, |i| i + 1));
+                                    }
+                                    (Some("unlock_pid"), _) => app.unlock_pid(),
+                                    (Some("baseline"), _) => {
+                                        app.baseline = Some(Baseline {
+                                            counts: app.syscall_counts.clone(),
+                                            errors: app.error_counts.clone(),
+                                            marked_at: clock_time(),
+                                        });
+                                        app.log_event(
+                                            "Baseline marked; counts now show calls since",
+                                        );
+                                    }
+                                    (Some("what_changed"), _) if app.baseline.is_some() => {
+                                        app.show_changes = !app.show_changes;
+                                    }
+                                    (Some("what_changed"), _) => {
+                                        app.log_event("Mark a baseline with z first, then press W to see what changed");
+                                    }
+                                    (Some("resources"), _) if app.counter.is_some() => {
+                                        app.log_event("Not available with a counting backend, which sees no arguments");
+                                    }
+                                    (Some("resources"), _) => app.resources_scroll = Some(0),
+                                    (Some("normal_set"), _) => {
+                                        let current = app
+                                            .normal_set
+                                            .as_ref()
+                                            .map(|normal| normal.path.clone())
+                                            .unwrap_or_default();
+                                        app.open_prompt_with(PromptKind::NormalSet, current, None);
+                                    }
+                                    (Some("only_not_normal"), _) if app.normal_set.is_some() => {
+                                        app.anomalies_only = !app.anomalies_only;
+                                        app.selected_syscall = 0;
+                                        app.update_filtered_syscalls();
+                                    }
+                                    (Some("only_not_normal"), _) => {
+                                        app.log_event("Load known-normal syscalls with N first");
+                                    }
+                                    (Some("clear_baseline"), _) if app.baseline.is_some() => {
+                                        app.baseline = None;
+                                        app.show_changes = false;
+                                        app.log_event("Baseline cleared");
+                                    }
+                                    (Some("pin"), _) if !app.show_detailed => {
+                                        app.toggle_pin();
+                                    }
+                                    (Some("categories"), _) => {
+                                        app.show_categories = !app.show_categories;
+                                    }
+                                    (_, KeyCode::Tab) if app.compare.is_some() => {
+                                        app.compare_focus = !app.compare_focus;
+                                    }
+                                    (Some("compare_sync"), _) if app.compare.is_some() => {
+                                        app.compare_sync = !app.compare_sync;
+                                        app.log_event(if app.compare_sync {
+                                            "Scrolling moves both panes"
+                                        } else {
+                                            "Scrolling moves the focused pane only"
+                                        });
+                                    }
+                                    (_, KeyCode::Tab | KeyCode::BackTab) => {
+                                        // Focusing the legend opens it if needed.
+                                        app.show_categories = true;
+                                        let current = app.category_filter.and_then(|c| {
+                                            Category::ALL.iter().position(|&a| a == c)
+                                        });
+                                        app.legend_focus = Some(current.unwrap_or(0));
+                                    }
+                                    (Some("errnos"), _) => {
+                                        // Opens the errno panel focused, keeping
+                                        // the highlight on the current filter.
+                                        app.show_errnos = true;
+                                        let current = app.errno_filter.as_deref().and_then(|e| {
+                                            app.sorted_errnos().iter().position(|(a, _)| *a == e)
+                                        });
+                                        app.errno_focus = Some(current.unwrap_or(0));
+                                    }
+                                    (_, KeyCode::Char('0')) => app.category_filter = None,
+                                    (_, KeyCode::Char(c @ '1'..='8')) => {
+                                        let category = Category::ALL[c as usize - '1' as usize];
+                                        app.category_filter =
+                                            if app.category_filter == Some(category) {
+                                                None
+                                            } else {
+                                                Some(category)
+                                            };
+                                    }
+                                    (Some("sampling"), _) => {
+                                        let idx = SAMPLE_RATES
+                                            .iter()
+                                            .position(|&r| r == app.sample_rate)
+                                            .unwrap_or(0);
+                                        app.sample_rate =
+                                            SAMPLE_RATES[(idx + 1) % SAMPLE_RATES.len()];
+                                        app.log_event(format!(
+                                            "Raw line sampling set to 1/{}",
+                                            app.sample_rate
+                                        ));
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Update on tick.
+        if last_tick.elapsed() >= tick_rate {
+            app.poll_config();
+            app.poll_leaderboard();
+            if let AppMode::Watching = app.mode {
+                app.poll_watch();
+            }
+            if let AppMode::SyscallMonitoring = app.mode {
+                if app.checkpoint_saved.elapsed() >= checkpoint::INTERVAL {
+                    app.save_checkpoint();
+                }
+                if app.capture_deadline.is_some_and(|d| Instant::now() >= d) {
+                    app.finish_timed_capture();
+                }
+                if let Some(idle) = app.idle_timeout {
+                    if app.strace_child.is_some()
+                        && !app.reading_log()
+                        && app.last_input.elapsed() >= idle
+                    {
+                        app.detach_for_inactivity(idle);
+                    }
+                }
+                let current = read_current_syscall(app.target_pid);
+                if current != app.current_syscall {
+                    app.current_syscall = current;
+                    app.dirty = true;
+                }
+                if !app.filter_mode {
+                    app.drain_strace_output();
+                    app.drain_compare();
+                }
+                app.refresh_order();
+                if app.target_pid == 0 {
+                    app.resolve_launched_pid();
+                }
+                if let Some(child) = &mut app.strace_child {
+                    if let Ok(Some(status)) = child.try_wait() {
+                        app.trace_ended(status);
+                    }
+                }
+                if let Some(stats) = &mut app.proc_stats {
+                    if !stats.ended {
+                        let samples = stats.samples();
+                        if !stats.poll() {
+                            app.proc_stats_ended();
+                        } else if stats.samples() != samples {
+                            app.dirty = true;
+                        }
+                    }
+                }
+            }
+            last_tick = Instant::now();
+        }
+    }
+}
+
+/// Draws the current screen and any popups over it.
+fn draw<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+) -> std::io::Result<()> {
+    if let Some(page) = &mut app.man_page {
+        let area = man_page_area(terminal.size()?);
+        page.fit(
+            area.width.saturating_sub(2) as usize,
+            area.height.saturating_sub(2) as usize,
+        );
+    }
+    let frame = terminal.draw(|f| {
+        match app.mode {
+            AppMode::ProcessSelection => draw_process_selection(f, app),
+            AppMode::SyscallMonitoring => draw_syscall_monitoring(f, app),
+            AppMode::Watching => draw_watching(f, app),
+        }
+        if let Some(view) = &app.maps_view {
+            draw_memory_map(f, app, view);
+        }
+        if let Some(board) = &app.leaderboard {
+            draw_leaderboard(f, board);
+        }
+        if let Some(page) = &app.man_page {
+            draw_man_page(f, page);
+        }
+        if let Some(prompt) = &app.prompt {
+            draw_prompt(f, prompt);
+        }
+        if let Some(lines) = &app.session_report {
+            draw_session_report(f, app, lines);
+        }
+        if let Some((kind, target)) = &app.pending_export {
+            draw_notice(
+                f,
+                "Exporting",
+                &format!("Writing the {} to {}…", kind.label(), target),
+            );
+        }
+        if let Some(summary) = &app.summary {
+            draw_summary(f, summary);
+        }
+        if app.show_tutorial {
+            draw_tutorial(f, app);
+        }
+    })?;
+    if app.snapshot_requested {
+        app.snapshot_requested = false;
+        app.save_snapshot(frame.buffer);
+    }
+    Ok(())
+}
+
+/// Key hints of the process selection screen's instruction bar.
+const SELECTION_HINTS: &[&str] = &[
+    "Up/Down: Navigate",
+    "Type: Filter",
+    "Enter: Select",
+    "Ctrl+R: Launch command",
+    "Ctrl+W: Watch for filter",
+    "Ctrl+S: Sort",
+    "Ctrl+O: Columns",
+    "Ctrl+D: Copy cwd",
+    "Ctrl+P: Capabilities",
+    "Ctrl+L: Memory map",
+    "Ctrl+B: Busiest",
+    "Ctrl+F: Descendants",
+    "Ctrl+A: Show/hide self",
+    "Ctrl+T: Timed capture",
+    "Ctrl+E: Events",
+    "Ctrl+N: Mouse capture",
+    "Ctrl+G: Snapshot",
+    "q: Quit",
+];
+
+/// Renders the process selection screen.
+fn draw_process_selection<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &mut App) {
+    let size = f.size();
+    let compact = is_compact(size);
+    let mut constraints = vec![Constraint::Length(bar_height(compact))];
+    if app.error_banner.is_some() {
+        constraints.push(Constraint::Length(bar_height(compact)));
+    }
+    constraints.push(Constraint::Min(5));
+    if app.show_events {
+        constraints.push(Constraint::Length(EVENTS_PANEL_HEIGHT));
+    }
+    constraints.push(Constraint::Length(bar_height(compact)));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(layout_margin(compact))
+        .constraints(constraints)
+        .split(size);
+
+    let filter_text = if compact {
+        format!("Filter: {}", app.process_filter)
+    } else {
+        app.process_filter.clone()
+    };
+    let filter = Paragraph::new(filter_text).block(bar_block(
+        compact,
+        "Filter (all space-separated terms must match name, command, user or PID; :PORT for listeners, cg:TEXT for cgroups, cap:NAME for capabilities)",
+    ));
+    f.render_widget(filter, chunks[0]);
+    if let Some(banner) = &app.error_banner {
+        let banner = Paragraph::new(banner.as_str())
+            .style(Style::default().fg(Color::Red))
+            .block(bar_block(compact, "Error"));
+        f.render_widget(banner, chunks[1]);
+    }
+    let list_area = chunks[chunks.len() - 2 - usize::from(app.show_events)];
+
+    let columns: Vec<ProcessColumn> = app
+        .process_columns
+        .iter()
+        .filter(|(_, shown)| *shown)
+        .map(|(c, _)| *c)
+        .collect();
+    let items: Vec<ListItem> = app
+        .filtered_processes
+        .iter()
+        .map(|&i| {
+            let p = &app.processes[i];
+            let cells: Vec<String> = columns.iter().map(|c| c.cell(p)).collect();
+            ListItem::new(cells.join(" ").trim_end().to_string())
+        })
+        .collect();
+
+    let header: Vec<String> = columns.iter().map(|c| c.pad(c.header())).collect();
+    let tree = app
+        .tree_root
+        .map(|root| format!("[tree of PID {}, Ctrl+F: all] ", root))
+        .unwrap_or_default();
+    let title = format!(
+        "Processes [{}] {}{}",
+        app.process_sort.label(),
+        tree,
+        header.join(" ").trim_end()
+    );
+    if items.is_empty() {
+        let message = if !procfs::available() {
+            "Process list unavailable: /proc is not mounted or not readable".to_string()
+        } else if app.process_filter.is_empty() {
+            "No processes found".to_string()
+        } else {
+            format!("No processes match '{}'", app.process_filter)
+        };
+        draw_empty_state(f, list_area, title, &message);
+    } else {
+        let process_list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().bg(Color::Blue));
+
+        // The persistent ListState keeps its scroll offset between draws.
+        let total = app.filtered_processes.len();
+        let visible = list_area.height.saturating_sub(2) as usize;
+        app.process_list_offset = list_offset(
+            app.process_list_offset,
+            app.selected_process,
+            total,
+            visible,
+        );
+        app.process_list_state.select(Some(app.selected_process));
+        f.render_stateful_widget(process_list, list_area, &mut app.process_list_state);
+        f.render_widget(
+            ScrollIndicator {
+                offset: app.process_list_offset,
+                visible,
+                total,
+            },
+            list_area,
+        );
+    }
+
+    if app.show_events {
+        draw_events_panel(f, app, chunks[chunks.len() - 2]);
+    }
+
+    let full_hints: Vec<String> = SELECTION_HINTS
+        .iter()
+        .map(|hint| app.keymap.relabel(hint))
+        .collect();
+    let instructions = Paragraph::new(if compact {
+        format!(
+            "Enter select ^R launch ^W watch ^S sort ^O columns ^T timed ^E events ^N mouse {} quit",
+            app.keymap.key("quit")
+        )
+    } else {
+        full_hints.join(" | ")
+    })
+    .block(bar_block(compact, instructions_title(app)));
+    f.render_widget(instructions, chunks[chunks.len() - 1]);
+
+    if let Some(selected) = app.column_editor {
+        draw_column_editor(f, app, selected);
+    }
+}
+
+/// Renders the process list column editor popup.
+fn draw_column_editor<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    selected: usize,
+) {
+    let size = f.size();
+    let width = 56.min(size.width);
+    let height = (app.process_columns.len() as u16 + 4).min(size.height);
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let mut items: Vec<ListItem> = app
+        .process_columns
+        .iter()
+        .map(|(column, shown)| {
+            ListItem::new(format!(
+                "[{}] {}",
+                if *shown { "x" } else { " " },
+                column.header()
+            ))
+        })
+        .collect();
+    items.push(ListItem::new(""));
+    items.push(
+        ListItem::new("Space: show/hide | u/d: move | r: reset | Esc: close")
+            .style(Style::default().fg(Color::DarkGray)),
+    );
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Columns"))
+        .highlight_style(Style::default().bg(Color::Blue));
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// The key hints of the monitoring screen's instruction bar, with whether
+/// each currently applies.
+fn monitoring_hints(app: &App) -> Vec<(&'static str, bool)> {
+    let kill_enabled = !app.safe_mode && app.protection.reason(app.target_pid).is_none();
+    if app.filter_mode {
+        vec![
+            ("Type to filter", true),
+            ("Ctrl+Z: Last filter", app.last_syscall_filter.is_some()),
+            ("k: Kill process", kill_enabled),
+            ("t: Toggle details", true),
+            ("q or b: Back", true),
+        ]
+    } else {
+        vec![
+            ("f: Filter syscalls", true),
+            ("k: Kill process", kill_enabled),
+            ("t: Toggle details", true),
+            ("v: Stats table", true),
+            ("D: Dashboard", true),
+            ("x: Expand line", app.show_detailed),
+            ("y: Copy", true),
+            ("p: Pin", !app.show_detailed),
+            ("z: Mark baseline", true),
+            ("N: Known-normal set", true),
+            (
+                if app.anomalies_only {
+                    "h: All syscalls"
+                } else {
+                    "h: Only not normal"
+                },
+                app.normal_set.is_some(),
+            ),
+            ("W: What changed", app.baseline.is_some()),
+            ("G: Resources", app.counter.is_none()),
+            ("Ctrl+G: Snapshot", true),
+            ("d: Follow fd", true),
+            ("i: Lock to PID", true),
+            (
+                if app.relative_times {
+                    "r: Absolute times"
+                } else {
+                    "r: Relative times"
+                },
+                app.show_detailed,
+            ),
+            ("s: Sort", true),
+            (
+                if app.color_mode == ColorMode::Category {
+                    "C: Color by errors"
+                } else {
+                    "C: Color by category"
+                },
+                true,
+            ),
+            ("u: Re-sort", app.order_is_stale()),
+            (
+                if app.auto_sort {
+                    "U: Freeze order"
+                } else {
+                    "U: Auto-sort"
+                },
+                app.syscall_order == SyscallOrder::ByCount,
+            ),
+            ("a: Show all", true),
+            ("g: Categories", true),
+            ("Tab: Legend", true),
+            ("o: Errnos", true),
+            ("n: Sampling", true),
+            ("T: Timed capture", true),
+            ("c: Command", true),
+            ("e: Trace filter", true),
+            ("A: strace args", true),
+            ("j: Note", true),
+            ("F: Follow forks", true),
+            ("K: Stack traces", true),
+            ("w: Callers", !app.show_detailed),
+            ("M: Man page", true),
+            ("R: Restart", true),
+            ("P: Save profile", true),
+            ("E: Export", true),
+            ("l: Events", true),
+            ("m: Mouse capture", true),
+            ("q or b: Back", true),
+        ]
+    }
+}
+
+/// Keys the first-run tutorial explains, looked up in the instruction bars'
+/// hints by the text before their colon.
+const TUTORIAL_SELECTION_KEYS: [&str; 5] = ["Type", "Enter", "Ctrl+R", "Ctrl+W", "q"];
+const TUTORIAL_MONITORING_KEYS: [&str; 7] = ["f", "t", "v", "k", "x", "e", "q or b"];
+
+/// Renders the first-run tutorial: what the two screens are for and their
+/// most important keys.
+fn draw_tutorial<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
+    let monitoring = monitoring_hints(app);
+    let lookup = |hints: &[&'static str], keys: &[&str]| -> Vec<Spans<'static>> {
+        keys.iter()
+            .filter_map(|key| {
+                hints
+                    .iter()
+                    .find(|hint| hint.split(':').next() == Some(*key))
+            })
+            .map(|hint| Spans::from(format!("  {}", hint)))
+            .collect()
+    };
+    let heading = |text: &'static str| {
+        Spans::from(Span::styled(
+            text,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    };
+    let mut lines = vec![
+        Spans::from("syscall-monitor shows the system calls a process makes, traced with strace."),
+        Spans::default(),
+        heading("1. Process selection: choose what to trace"),
+    ];
+    lines.extend(lookup(SELECTION_HINTS, &TUTORIAL_SELECTION_KEYS));
+    lines.push(Spans::default());
+    lines.push(heading("2. Monitoring: its syscalls, live"));
+    let monitoring: Vec<&'static str> = monitoring.iter().map(|(hint, _)| *hint).collect();
+    lines.extend(lookup(&monitoring, &TUTORIAL_MONITORING_KEYS));
+    lines.push(Spans::default());
+    lines.push(Spans::from(
+        "Every key is listed in the bar at the bottom of each screen.",
+    ));
+    let size = f.size();
+    let width = 80.min(size.width);
+    let height = (lines.len() as u16 + 2).min(size.height);
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Welcome (any key to close)"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// Renders the syscall monitoring screen.
+fn draw_syscall_monitoring<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &mut App) {
+    let size = f.size();
+    let compact = is_compact(size);
+    let bar = Constraint::Length(bar_height(compact));
+    // Header, then the one-line summary of the active options.
+    let mut constraints = vec![bar, Constraint::Length(1)];
+    let capture = app.capture_progress();
+    if capture.is_some() {
+        constraints.push(bar); // timed capture progress
+    }
+    if app.error_banner.is_some() {
+        constraints.push(bar); // error banner
+    }
+    if app.show_command {
+        constraints.push(bar); // strace command
+    }
+    if app.show_categories {
+        constraints.push(bar); // category summary
+    }
+    if app.show_errnos {
+        constraints.push(bar); // errno frequencies
+    }
+    constraints.push(Constraint::Min(5)); // syscall list
+    if app.filter_mode {
+        constraints.push(bar); // filter input
+    }
+    if app.show_events {
+        constraints.push(Constraint::Length(EVENTS_PANEL_HEIGHT)); // event log
+    }
+    constraints.push(bar); // instructions
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(layout_margin(compact))
+        .constraints(constraints)
+        .split(size);
+    // Panels are laid out top to bottom in the order they are rendered below.
+    let mut areas = chunks.iter().copied();
+
+    let mut header_text = match &app.trace_target {
+        TraceTarget::Attach(_) => format!(
+            "Monitoring syscalls for PID: {} ({}) | now: {}",
+            app.target_pid, app.target_process_name, app.current_syscall
+        ),
+        TraceTarget::Group { kind, id, members } => format!(
+            "Monitoring syscalls for {} {} ({} processes, PID {} now: {})",
+            kind.label(),
+            id,
+            members.len(),
+            app.target_pid,
+            app.current_syscall
+        ),
+        TraceTarget::Launch(argv) => {
+            let pid = if app.target_pid > 0 {
+                app.target_pid.to_string()
+            } else {
+                "?".to_string()
+            };
+            format!(
+                "Launched: {} (PID {}) | now: {}",
+                shell_join(argv),
+                pid,
+                app.current_syscall
+            )
+        }
+        TraceTarget::Log(_) => format!(
+            "Reading log: {} | {} lines",
+            app.target_description(),
+            app.lines_seen
+        ),
+        TraceTarget::Checkpoint {
+            description,
+            saved_at,
+        } => format!(
+            "Resumed: {} ({}) | checkpoint saved {}, not traced",
+            description, app.target_process_name, saved_at
+        ),
+    };
+    if let Some(exit_status) = &app.exit_status {
+        header_text.push_str(&format!(" | {}", exit_status));
+    }
+    if app.strace_child.is_some() {
+        // Before the first line arrives, idle time counts from strace's start.
+        let idle = app
+            .last_line_at
+            .or(app.strace_started)
+            .map_or(Duration::ZERO, |t| t.elapsed());
+        if idle >= IDLE_AFTER {
+            header_text.push_str(&format!(" | idle for {}s", idle.as_secs()));
+        } else {
+            header_text.push_str(" | receiving");
+        }
+    }
+    if let Some(profile) = &app.active_profile {
+        header_text.push_str(&format!(" | profile: {}", profile));
+    }
+    if app.safe_mode {
+        header_text.push_str(" | safe mode");
+    }
+    if let Some(counter) = &app.counter {
+        header_text.push_str(&format!(" | {}: counts only", counter.name()));
+    }
+    if app.proc_stats.is_some() {
+        header_text.push_str(" | /proc stats only: coarse, no syscalls");
+    }
+    if let Some(pid) = app.pid_lock {
+        header_text.push_str(&format!(" | only {} (I: all)", app.pid_label(pid)));
+    }
+    if app.options_changed {
+        header_text.push_str(" | options changed, R to restart");
+    }
+    let status = if let TraceTarget::Checkpoint { .. } = app.trace_target {
+        Span::styled(" [checkpoint]", Style::default().fg(Color::DarkGray))
+    } else if app.reading_log() {
+        if app.strace_receiver.is_some() {
+            Span::styled(" [reading…]", Style::default().fg(Color::Yellow))
+        } else {
+            Span::styled(" [end of log]", Style::default().fg(Color::DarkGray))
+        }
+    } else if app.proc_stats.as_ref().is_some_and(|stats| !stats.ended) {
+        Span::styled(" [polling /proc]", Style::default().fg(Color::Green))
+    } else if app.strace_child.is_none() {
+        Span::styled(" [detached]", Style::default().fg(Color::DarkGray))
+    } else if app.attached {
+        Span::styled(" [attached]", Style::default().fg(Color::Green))
+    } else {
+        Span::styled(" [attaching…]", Style::default().fg(Color::Yellow))
+    };
+    let mut spans = vec![Span::raw(header_text)];
+    // The anomaly count stands out: the point of loading a normal set.
+    if app.normal_set.is_some() {
+        let anomalies = app.anomaly_count();
+        let style = if anomalies > 0 {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Green)
+        };
+        spans.push(Span::styled(
+            format!(" | {} not in normal set", anomalies),
+            style,
+        ));
+    }
+    spans.push(status);
+    let mut header = Paragraph::new(Spans::from(spans)).block(bar_block(compact, ""));
+    if compact {
+        header = header.style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    f.render_widget(header, areas.next().unwrap());
+    let options = Paragraph::new(format!(" {}", option_tokens(app).join(" ")))
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(options, areas.next().unwrap());
+
+    if let Some((elapsed, total)) = capture {
+        let left = total.saturating_sub(elapsed);
+        let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        let gauge = Gauge::default()
+            .block(bar_block(compact, "Timed capture"))
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .ratio((elapsed.as_secs_f64() / total.as_secs_f64()).clamp(0.0, 1.0))
+            .label(format!(
+                "{}s of {}s, {}s left",
+                elapsed.as_secs(),
+                total.as_secs(),
+                secs
+            ));
+        f.render_widget(gauge, areas.next().unwrap());
+    }
+
+    if let Some(banner) = &app.error_banner {
+        let banner = Paragraph::new(banner.as_str())
+            .style(Style::default().fg(Color::Red))
+            .block(bar_block(compact, "Error"));
+        f.render_widget(banner, areas.next().unwrap());
+    }
+
+    if app.show_command {
+        let command = shell_join(&app.strace_command);
+        let command = Paragraph::new(if compact {
+            format!("$ {}", command)
+        } else {
+            command
+        })
+        .block(bar_block(compact, "strace command"));
+        f.render_widget(command, areas.next().unwrap());
+    }
+
+    if app.show_categories {
+        draw_category_panel(f, app, areas.next().unwrap(), compact);
+    }
+    if app.show_errnos {
+        draw_errno_panel(f, app, areas.next().unwrap(), compact);
+    }
+
+    let list_area = areas.next().unwrap();
+    if let Some(stats) = &app.proc_stats {
+        draw_proc_stats(f, stats, list_area);
+    } else if app.dashboard && !app.show_detailed {
+        draw_dashboard(f, app, list_area);
+    } else if app.show_stats && !app.show_detailed {
+        draw_stats_table(f, app, list_area);
+    } else {
+        draw_syscall_list(f, app, list_area);
+    }
+    if let Some((blocked, top, top_share)) = app.blocked_estimate() {
+        // Drawn over the list's bottom border, like a title at the foot.
+        let footer = area_footer(list_area);
+        let text = format!(
+            "≈{:.0}% blocked in syscalls ({:.0}% in {}), ≈{:.0}% running, estimated from -T",
+            blocked,
+            top_share,
+            top,
+            100.0 - blocked
+        );
+        f.render_widget(Paragraph::new(text), footer);
+    }
+
+    if app.filter_mode {
+        let query = match &app.compare {
+            Some(pane) if app.compare_focus => &pane.filter,
+            _ => &app.syscall_filter,
+        };
+        let filter_text = if compact {
+            format!("Filter: {}", query)
+        } else {
+            query.clone()
+        };
+        let matching = match (app.filter_inverted, app.filter_all_terms) {
+            (false, false) => "any term matches",
+            (false, true) => "all terms must match",
+            (true, false) => "INVERTED: hiding syscalls any term matches",
+            (true, true) => "INVERTED: hiding syscalls all terms match",
+        };
+        let title = format!(
+            "Syscall Fuzzy Filter, {} (Ctrl+V: {}, Enter/Esc to resume)",
+            matching,
+            if app.filter_inverted {
+                "un-invert"
+            } else {
+                "invert"
+            }
+        );
+        let filter_input = Paragraph::new(filter_text).block(bar_block(compact, &title));
+        f.render_widget(filter_input, areas.next().unwrap());
+    }
+
+    if app.show_events {
+        draw_events_panel(f, app, areas.next().unwrap());
+    }
+
+    let labels: Vec<(String, bool)> = monitoring_hints(app)
+        .into_iter()
+        .map(|(hint, enabled)| (app.keymap.relabel(hint), enabled))
+        .collect();
+    let hints: Vec<(&str, bool)> = labels
+        .iter()
+        .map(|(hint, enabled)| (hint.as_str(), *enabled))
+        .collect();
+    let instr = Paragraph::new(hint_spans(&hints, compact))
+        .block(bar_block(compact, instructions_title(app)));
+    f.render_widget(instr, areas.next().unwrap());
+
+    if app.expand_line && app.show_detailed {
+        draw_expanded_line(f, app);
+    }
+    if app.show_callers && !app.show_detailed {
+        draw_callers(f, app);
+    }
+    if app.show_changes {
+        draw_changes(f, app);
+    }
+    if let Some(scroll) = app.resources_scroll {
+        draw_resources(f, app, scroll);
+    }
+    if let Some(selected) = app.pid_picker {
+        draw_pid_picker(f, app, selected);
+    }
+}
+
+/// Renders the PID picker popup: "all PIDs" and then every traced PID with
+/// its call count.
+fn draw_pid_picker<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    selected: usize,
+) {
+    let totals = app.pid_totals();
+    let size = f.size();
+    let width = 72.min(size.width);
+    let height = (totals.len() as u16 + 5).min(size.height);
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let marker = |locked: bool| if locked { "● " } else { "  " };
+    let mut items = vec![ListItem::new(format!(
+        "{}All PIDs",
+        marker(app.pid_lock.is_none())
+    ))];
+    items.extend(totals.iter().map(|(pid, calls)| {
+        let (kind, name) = match app.pid_kinds.get(pid) {
+            Some(PidKind::Thread(name)) => ("thread", name.as_str()),
+            Some(PidKind::Process(name)) => ("process", name.as_str()),
+            _ => ("", ""),
+        };
+        ListItem::new(format!(
+            "{}{:<8} {:<7} {:<16} {:>7} call{} {}",
+            marker(app.pid_lock == Some(*pid)),
+            pid,
+            kind,
+            name,
+            calls,
+            if *calls == 1 { " " } else { "s" },
+            app.top_syscall(*pid)
+                .map(|name| format!("(mostly {})", name))
+                .unwrap_or_default()
+        ))
+    }));
+    items.push(ListItem::new(""));
+    items.push(
+        ListItem::new("Enter: show only it | Esc: close")
+            .style(Style::default().fg(Color::DarkGray)),
+    );
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Lock to PID or thread"),
+        )
+        .highlight_style(Style::default().bg(Color::Blue));
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Renders the leaderboard of the busiest listed processes in a popup.
+fn draw_leaderboard<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    board: &leaderboard::Leaderboard,
+) {
+    let size = f.size();
+    let width = 72.min(size.width);
+    let height = (board.entries.len().max(1) as u16 + 3).min(size.height.saturating_sub(2));
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let header = Row::new(["PID", "NAME", "SWITCHES/S", "USER CPU", "SYS CPU"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let mut rows: Vec<Row> = board
+        .entries
+        .iter()
+        .map(|entry| {
+            Row::new([
+                Cell::from(format!("{:>7}", entry.pid)),
+                Cell::from(entry.name.clone()),
+                Cell::from(format!("{:>10}", entry.switches)),
+                Cell::from(format!("{:>7}%", entry.user_cpu)),
+                Cell::from(format!("{:>7}%", entry.system_cpu)),
+            ])
+        })
+        .collect();
+    if rows.is_empty() {
+        rows.push(Row::new(["", "Measuring…"]));
+    }
+    let widths = [
+        Constraint::Length(7),
+        Constraint::Min(16),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Busiest processes, from /proc every {}s (Enter: trace, Esc: close)",
+            leaderboard::INTERVAL.as_secs()
+        )))
+        .widths(&widths)
+        .highlight_style(Style::default().bg(Color::Blue));
+    let mut state = TableState::default();
+    if !board.entries.is_empty() {
+        state.select(Some(board.selected));
+    }
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Renders the syscall list or the timeline into `list_area`.
+fn draw_syscall_list<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &mut App,
+    list_area: ratatui::layout::Rect,
+) {
+    // While comparing, the list view shares its area with the second process.
+    let comparing = app.compare.is_some() && !app.show_detailed;
+    let (list_area, pane_area) = if comparing {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(list_area);
+        (halves[0], Some(halves[1]))
+    } else {
+        (list_area, None)
+    };
+    let border = if comparing && !app.compare_focus {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let (items, title) = if app.show_detailed {
+        timeline_items(app, list_area.width.saturating_sub(2) as usize)
+    } else {
+        syscall_items(app)
+    };
+    let item_count = items.len();
+    if app.selected_syscall >= item_count {
+        app.selected_syscall = item_count.saturating_sub(1);
+    }
+    if item_count == 0 {
+        draw_empty_state(f, list_area, title, empty_list_message(app));
+    } else {
+        let syscall_list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(title),
+            )
+            .highlight_style(Style::default().bg(Color::Blue));
+        let visible = list_area.height.saturating_sub(2) as usize;
+        app.syscall_list_offset = list_offset(
+            app.syscall_list_offset,
+            app.selected_syscall,
+            item_count,
+            visible,
+        );
+        app.syscall_list_state.select(Some(app.selected_syscall));
+        f.render_stateful_widget(syscall_list, list_area, &mut app.syscall_list_state);
+        f.render_widget(
+            ScrollIndicator {
+                offset: app.syscall_list_offset,
+                visible,
+                total: item_count,
+            },
+            list_area,
+        );
+    }
+    if let Some(area) = pane_area {
+        draw_compare_pane(f, app, area);
+    }
+}
+
+/// Renders the dashboard: the syscall list in the top share of `area` and
+/// the newest timeline lines below it.
+fn draw_dashboard<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &mut App,
+    area: ratatui::layout::Rect,
+) {
+    let halves = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(app.dashboard_split),
+            Constraint::Percentage(100 - app.dashboard_split),
+        ])
+        .split(area);
+    draw_syscall_list(f, app, halves[0]);
+    let (items, title) = timeline_items(app, halves[1].width.saturating_sub(2) as usize);
+    let title = format!("{} (+/-: resize)", title);
+    if items.is_empty() {
+        draw_empty_state(f, halves[1], title, empty_list_message(app));
+    } else {
+        // The list keeps the selection; the timeline just follows the newest lines.
+        let timeline = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(timeline, halves[1]);
+    }
+}
+
+/// Renders the /proc counters of a process watched without tracing as one
+/// sparkline per metric, newest samples on the right.
+fn draw_proc_stats<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    stats: &procstats::ProcStats,
+    area: ratatui::layout::Rect,
+) {
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Coarse activity from /proc, sampled every {}s (approximate; no syscalls are seen)",
+        procstats::INTERVAL.as_secs()
+    ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Ratio(1, procstats::METRICS.len() as u32);
+            procstats::METRICS.len()
+        ])
+        .split(inner);
+    for (((name, unit), history), &row) in procstats::METRICS
+        .iter()
+        .zip(&stats.history)
+        .zip(rows.iter())
+    {
+        // The newest samples that fit, so the graph scrolls left.
+        let width = row.width as usize;
+        let data: Vec<u64> = history
+            .iter()
+            .skip(history.len().saturating_sub(width))
+            .copied()
+            .collect();
+        let title = match data.last() {
+            Some(now) => format!(
+                "{}: ≈{}{} now, ≈{}{} average, {}{} peak",
+                name,
+                now,
+                unit,
+                data.iter().sum::<u64>() / data.len() as u64,
+                unit,
+                data.iter().max().copied().unwrap_or(0),
+                unit
+            ),
+            None => format!("{}: waiting for a second sample…", name),
+        };
+        let sparkline = Sparkline::default()
+            .block(Block::default().title(title))
+            .data(&data)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, row);
+    }
+}
+
+/// Renders the comparison process's syscall list into `area`, filtered and
+/// ordered like the main list. Syscalls only one side has called are
+/// marked with `*` on that side.
+fn draw_compare_pane<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &mut App,
+    area: ratatui::layout::Rect,
+) {
+    let Some(pane) = &app.compare else {
+        return;
+    };
+    let matcher = SkimMatcherV2::default();
+    let filtering = app.filter_mode && !pane.filter.trim().is_empty();
+    let mut names: Vec<&String> = pane
+        .counts
+        .keys()
+        .filter(|name| {
+            app.category_filter
+                .is_none_or(|category| syscalls::category(name) == category)
+        })
+        .filter(|name| !filtering || app.passes_filter(&matcher, name, &pane.filter))
+        .collect();
+    let count = |name: &String| pane.counts.get(name).copied().unwrap_or(0);
+    match app.syscall_order {
+        SyscallOrder::Alphabetical => names.sort(),
+        SyscallOrder::ByCount => {
+            names.sort_by(|a, b| count(b).cmp(&count(a)).then_with(|| a.cmp(b)))
+        }
+        SyscallOrder::FirstSeen => {
+            let position = |name: &String| pane.first_seen.iter().position(|n| n == name);
+            names.sort_by_key(|name| position(name));
+        }
+    }
+    // Pins go first, as on the main side.
+    names.sort_by_key(|name| !app.pinned.contains(*name));
+    let items: Vec<ListItem> = names
+        .iter()
+        .map(|name| {
+            let marker = if app.pinned.contains(*name) {
+                PIN_MARKER
+            } else {
+                ""
+            };
+            let mut text = format!("{}{} ({})", marker, name, count(name));
+            let failed = pane.errors.get(*name).copied().unwrap_or(0);
+            if failed > 0 {
+                text.push_str(&format!(" [{} failed]", failed));
+            }
+            if !app.syscall_counts.contains_key(*name) {
+                text.push_str(" *");
+            }
+            let color = match app.color_mode {
+                ColorMode::Category => category_color(syscalls::category(name)),
+                ColorMode::ErrorRate => error_rate_color(failed, count(name)),
+            };
+            ListItem::new(text).style(Style::default().fg(color))
+        })
+        .collect();
+    let mut title = format!("PID {} ({})", pane.pid, pane.name);
+    if let Some(reason) = &pane.ended {
+        title.push_str(&format!(" [ended: {}]", reason));
+    }
+    if filtering {
+        title.push_str(&format!(" [filter: {}]", pane.filter));
+    }
+    title.push_str(if app.compare_sync {
+        " [scroll locked, L: unlock]"
+    } else {
+        " [L: lock scroll]"
+    });
+    let border = if app.compare_focus {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let total = items.len();
+    let Some(pane) = &mut app.compare else {
+        return;
+    };
+    if pane.selected >= total {
+        pane.selected = total.saturating_sub(1);
+    }
+    if total == 0 {
+        let message = if pane.counts.is_empty() {
+            "Waiting for syscalls…"
+        } else {
+            "No syscalls match the current filters"
+        };
+        draw_empty_state(f, area, title, message);
+        return;
+    }
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border)
+                .title(title),
+        )
+        .highlight_style(Style::default().bg(Color::Blue));
+    let visible = area.height.saturating_sub(2) as usize;
+    pane.list_offset = list_offset(pane.list_offset, pane.selected, total, visible);
+    pane.list_state.select(Some(pane.selected));
+    f.render_stateful_widget(list, area, &mut pane.list_state);
+    f.render_widget(
+        ScrollIndicator {
+            offset: pane.list_offset,
+            visible,
+            total,
+        },
+        area,
+    );
+}
+
+/// `hscroll` kept so the last of the stats table's columns after the name
+/// is still shown, and how many of those columns fit in `inner` columns.
+///
+/// The name column stays put; the others scroll past it when they do not all
+/// fit, each taking its width plus the column spacing. One of them is always
+/// shown, squeezed if need be, so there is something to scroll.
+fn stats_column_window(inner: u16, hscroll: usize) -> (usize, usize) {
+    let scrollable = StatsColumn::ALL.len() - 1;
+    let fit = (inner.saturating_sub(STATS_NAME_WIDTH) / (STATS_COLUMN_WIDTH + 1)) as usize;
+    let fit = fit.clamp(1, scrollable);
+    (hscroll.min(scrollable - fit), fit)
+}
+
+/// Renders the stats table, one row per syscall, into `list_area`.
+fn draw_stats_table<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &mut App,
+    list_area: ratatui::layout::Rect,
+) {
+    let rows = stats_rows(app);
+    let scrollable = StatsColumn::ALL.len() - 1;
+    (app.stats_hscroll, app.stats_columns_fit) =
+        stats_column_window(list_area.width.saturating_sub(2), app.stats_hscroll);
+    let columns: Vec<StatsColumn> = StatsColumn::ALL
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(i, _)| {
+            i == 0 || (i > app.stats_hscroll && i <= app.stats_hscroll + app.stats_columns_fit)
+        })
+        .map(|(_, column)| column)
+        .collect();
+    let mut title = if app.stats_columns_fit < scrollable {
+        format!(
+            "Syscall Stats [by {}] [columns {}-{} of {}] (Left/Right: scroll, Shift+Left/Right: sort column)",
+            app.stats_sort.header().to_lowercase(),
+            app.stats_hscroll + 1,
+            app.stats_hscroll + app.stats_columns_fit,
+            scrollable
+        )
+    } else {
+        format!(
+            "Syscall Stats [by {}] (Left/Right: sort column)",
+            app.stats_sort.header().to_lowercase()
+        )
+    };
+    if let Some(category) = app.category_filter {
+        title.push_str(&format!(" [{}]", category.label()));
+    }
+    if let Some(errno) = &app.errno_filter {
+        title.push_str(&format!(" [{}]", errno));
+    }
+    if app.selected_syscall >= rows.len() {
+        app.selected_syscall = rows.len().saturating_sub(1);
+    }
+    if rows.is_empty() {
+        draw_empty_state(f, list_area, title, empty_list_message(app));
+        return;
+    }
+    let header = Row::new(columns.iter().map(|&column| {
+        let label = if column == app.stats_sort {
+            // Names sort A-Z, numbers largest first.
+            let arrow = if column == StatsColumn::Name {
+                '▲'
+            } else {
+                '▼'
+            };
+            format!("{} {}", column.header(), arrow)
+        } else {
+            column.header().to_string()
+        };
+        Cell::from(label).style(Style::default().add_modifier(Modifier::BOLD))
+    }));
+    let body = rows.iter().map(|row| {
+        Row::new(columns.iter().map(|&column| {
+            match column {
+                StatsColumn::Name => Cell::from(if app.pinned.contains(&row.name) {
+                    format!("{}{}", PIN_MARKER, row.name)
+                } else {
+                    row.name.clone()
+                })
+                .style(Style::default().fg(app.syscall_color(&row.name))),
+                StatsColumn::Count => Cell::from(row.count.to_string()),
+                StatsColumn::Errors => Cell::from(row.errors.to_string()),
+                StatsColumn::ErrorRate => Cell::from(format!("{:.1}", row.error_rate())),
+                StatsColumn::AvgTime => {
+                    Cell::from(row.avg_time.map_or_else(|| "-".to_string(), format_latency))
+                }
+            }
+        }))
+    });
+    let widths: Vec<Constraint> = columns.iter().map(|c| c.width()).collect();
+    let table = Table::new(body)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&widths)
+        .highlight_style(Style::default().bg(Color::Blue));
+    app.stats_state.select(Some(app.selected_syscall));
+    f.render_stateful_widget(table, list_area, &mut app.stats_state);
+    f.render_widget(
+        ScrollIndicator {
+            offset: app.stats_state.offset(),
+            // Borders and the header row.
+            visible: list_area.height.saturating_sub(3) as usize,
+            total: rows.len(),
+        },
+        list_area,
+    );
+}
+
+/// What an empty syscall list or stats table says instead.
+fn empty_list_message(app: &App) -> &'static str {
+    if app.timeline.is_empty() && app.unique_syscalls.is_empty() {
+        if app.strace_child.is_some() {
+            "Waiting for syscalls…"
+        } else {
+            "No syscalls were captured"
+        }
+    } else {
+        "No syscalls match the current filters"
+    }
+}
+
+/// Builds the stats table rows for every syscall that passes the filters,
+/// ordered by the selected column: names A-Z, numbers largest first.
+fn stats_rows(app: &App) -> Vec<StatsRow> {
+    let names: Vec<&String> = if app.filter_mode {
+        app.filtered_syscalls.iter().collect()
+    } else {
+        app.unique_syscalls
+            .iter()
+            .filter(|s| app.shows_syscall(s))
+            .collect()
+    };
+    let mut rows: Vec<StatsRow> = names
+        .into_iter()
+        .map(|name| {
+            let count = app.syscall_counts.get(name).copied().unwrap_or(0);
+            let avg_time = app
+                .syscall_time
+                .get(name)
+                .filter(|_| count > 0)
+                // In floating point: a count past u32::MAX would truncate.
+                .map(|total| Duration::from_secs_f64(total.as_secs_f64() / count as f64));
+            StatsRow {
+                name: name.clone(),
+                count,
+                errors: app.error_counts.get(name).copied().unwrap_or(0),
+                avg_time,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        let order = match app.stats_sort {
+            StatsColumn::Name => a.name.cmp(&b.name),
+            StatsColumn::Count => b.count.cmp(&a.count),
+            StatsColumn::Errors => b.errors.cmp(&a.errors),
+            StatsColumn::ErrorRate => b.error_rate().total_cmp(&a.error_rate()),
+            StatsColumn::AvgTime => b.avg_time.cmp(&a.avg_time),
+        };
+        order.then_with(|| a.name.cmp(&b.name))
+    });
+    // Stable, so pinned and unpinned rows each keep the column's order.
+    rows.sort_by_key(|row| !app.pinned.contains(&row.name));
+    rows
+}
+
+/// The syscall under the highlight in the list or stats table, if any.
+fn selected_syscall_name(app: &App) -> Option<String> {
+    if app.show_stats {
+        stats_rows(app)
+            .into_iter()
+            .nth(app.selected_syscall)
+            .map(|row| row.name)
+    } else {
+        visible_syscalls(app)
+            .0
+            .into_iter()
+            .nth(app.selected_syscall)
+    }
+}
+
+/// Title of the instructions bar, which also shows whether mouse capture is on
+/// (the hints themselves are often cut off on narrower terminals).
+fn instructions_title(app: &App) -> &'static str {
+    if app.mouse_capture {
+        "Instructions [mouse capture on]"
+    } else {
+        "Instructions [mouse capture off: the terminal selects text]"
+    }
+}
+
+/// The bottom border row of a bordered panel, inset past its corners.
+fn area_footer(area: ratatui::layout::Rect) -> ratatui::layout::Rect {
+    ratatui::layout::Rect {
+        x: area.x + 1,
+        y: area.y + area.height.saturating_sub(1),
+        width: area.width.saturating_sub(2),
+        height: area.height.min(1),
+    }
+}
+
+/// Whether `size` calls for the compact layout: no outer margin, and one-line
+/// bars without borders in place of the bordered three-line ones.
+fn is_compact(size: ratatui::layout::Rect) -> bool {
+    size.width < COMPACT_WIDTH || size.height < COMPACT_HEIGHT
+}
+
+fn layout_margin(compact: bool) -> u16 {
+    if compact {
+        0
+    } else {
+        1
+    }
+}
+
+/// Height of a single-line bar such as a header, input or instructions.
+fn bar_height(compact: bool) -> u16 {
+    if compact {
+        1
+    } else {
+        3
+    }
+}
+
+/// The block around a single-line bar: bordered and titled normally, bare when compact.
+fn bar_block(compact: bool, title: &str) -> Block<'_> {
+    if compact {
+        Block::default()
+    } else {
+        Block::default().borders(Borders::ALL).title(title)
+    }
+}
+
+/// Returns where a list of one-line items starts after scrolling `selected`
+/// into a `visible`-row viewport that started at `offset`, the way ratatui's
+/// `List` does it: the view only moves when the selection leaves it. ratatui
+/// keeps its own offset private, so the scroll indicator tracks it with this.
+fn list_offset(offset: usize, selected: usize, total: usize, visible: usize) -> usize {
+    if total == 0 || visible == 0 {
+        return 0;
+    }
+    let offset = offset.min(total - 1);
+    let selected = selected.min(total - 1);
+    if selected >= offset + visible {
+        selected + 1 - visible
+    } else if selected < offset {
+        selected
+    } else {
+        offset
+    }
+}
+
+/// A scroll position indicator drawn over the right border of a bordered
+/// list: a thumb whose size and position show which share of the `total`
+/// rows is in view. Nothing is drawn when everything fits.
+struct ScrollIndicator {
+    offset: usize,
+    visible: usize,
+    total: usize,
+}
+
+impl Widget for ScrollIndicator {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.total <= self.visible || area.width == 0 || area.height < 3 {
+            return;
+        }
+        let track = area.height as usize - 2;
+        let thumb = (track * self.visible / self.total).clamp(1, track);
+        let max_offset = self.total - self.visible;
+        let start = ((track - thumb) * self.offset.min(max_offset)).div_ceil(max_offset);
+        let x = area.right() - 1;
+        for row in start..start + thumb {
+            buf.get_mut(x, area.top() + 1 + row as u16).set_symbol("█");
+        }
+    }
+}
+
+/// Renders a bordered panel with a centered placeholder message, for empty lists.
+fn draw_empty_state<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    area: ratatui::layout::Rect,
+    title: String,
+    message: &str,
+) {
+    let padding = area.height.saturating_sub(3) / 2;
+    let mut lines = vec![Spans::from(""); padding as usize];
+    lines.push(Spans::from(Span::styled(
+        message.to_string(),
+        Style::default().fg(Color::DarkGray),
+    )));
+    let placeholder = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(placeholder, area);
+}
+
+/// Renders per-category totals; the number before each one selects it as a filter.
+fn draw_category_panel<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    area: ratatui::layout::Rect,
+    compact: bool,
+) {
+    // Each entry doubles as the legend for its color. While a category is
+    // selected only its entry keeps its color, matching the filtered list.
+    let mut spans = Vec::new();
+    for (i, (category, count)) in app.category_counts().into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+        }
+        let text = format!("{} ■ {}: {}", i + 1, category.label(), count);
+        let mut style = match app.category_filter {
+            Some(selected) if selected != category => Style::default().fg(Color::DarkGray),
+            Some(_) => Style::default()
+                .fg(category_color(category))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            None => Style::default().fg(category_color(category)),
+        };
+        if app.legend_focus == Some(i) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(text, style));
+    }
+    let title = if app.legend_focus.is_some() {
+        "Legend (Left/Right: choose, Enter: filter, 0: clear, Esc: done)"
+    } else {
+        "Categories (1-8: filter, 0: clear, Tab: legend)"
+    };
+    let panel = Paragraph::new(Spans::from(spans)).block(bar_block(compact, title));
+    f.render_widget(panel, area);
+}
+
+/// Renders the errno frequency table, most frequent first, highlighting the
+/// errno the views are narrowed to.
+fn draw_errno_panel<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    area: ratatui::layout::Rect,
+    compact: bool,
+) {
+    let errnos = app.sorted_errnos();
+    let mut spans = Vec::new();
+    if errnos.is_empty() {
+        spans.push(Span::styled(
+            "No failed syscalls yet",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    for (i, (errno, count)) in errnos.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  "));
+        }
+        let mut style = if app.errno_filter.as_deref() == Some(errno) {
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default()
+        };
+        if app.errno_focus == Some(i) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(format!("{}: {}", errno, count), style));
+    }
+    let title = if app.errno_focus.is_some() {
+        "Errnos (Left/Right: choose, Enter: filter, 0: clear, Esc: done, o: hide)"
+    } else {
+        "Errnos (o: choose)"
+    };
+    let panel = Paragraph::new(Spans::from(spans)).block(bar_block(compact, title));
+    f.render_widget(panel, area);
+}
+
+/// Builds the unique-syscall list items (with counts) and the panel title.
+fn syscall_items(app: &App) -> (Vec<ListItem<'static>>, String) {
+    let (syscalls, total) = visible_syscalls(app);
+    let mut title = format!("Syscalls [{}]", app.syscall_order.label());
+    if app.color_mode == ColorMode::ErrorRate {
+        title.push_str(" [colored by error rate]");
+    }
+    if app.order_is_stale() {
+        title.push_str(" [order frozen, u: re-sort]");
+    }
+    if app.baseline.is_some() {
+        title.push_str(" [since baseline, Z: clear]");
+    }
+    if let Some(category) = app.category_filter {
+        title.push_str(&format!(" [{}]", category.label()));
+    }
+    if let Some(errno) = &app.errno_filter {
+        title.push_str(&format!(" [{}]", errno));
+    }
+    if app.anomalies_only {
+        title.push_str(" [not in normal set, h: all]");
+    }
+    if syscalls.len() < total {
+        title.push_str(&format!(" (showing top {} of {})", syscalls.len(), total));
+    }
+    let items = syscalls
+        .into_iter()
+        .map(|s| {
+            let count = app.calls_of(&s);
+            let color = app.syscall_color(&s);
+            let marker = if app.pinned.contains(&s) {
+                PIN_MARKER
+            } else {
+                ""
+            };
+            let mut text = if app.baseline.is_some() && app.pid_lock.is_none() {
+                format!(
+                    "{}{} +{} ({} total)",
+                    marker,
+                    s,
+                    app.count_since_baseline(&s),
+                    count
+                )
+            } else {
+                format!("{}{} ({})", marker, s, count)
+            };
+            if app
+                .compare
+                .as_ref()
+                .is_some_and(|pane| !pane.counts.contains_key(&s))
+            {
+                text.push_str(" *");
+            }
+            if app.filter_mode && app.show_filter_scores {
+                if let Some(score) = app.filter_scores.get(&s) {
+                    text.push_str(&format!(" [score {}]", score));
+                }
+            }
+            let mut style = Style::default().fg(color);
+            if app.is_anomalous(&s) {
+                text.push_str(" [not normal]");
+                style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+            }
+            ListItem::new(text).style(style)
+        })
+        .collect();
+    (items, title)
+}
+
+/// Returns the syscall names the list view shows, in display order, and how
+/// many passed the filters before the top-N cap.
+fn visible_syscalls(app: &App) -> (Vec<String>, usize) {
+    let syscalls: Vec<String> = if app.filter_mode {
+        app.filtered_syscalls.clone()
+    } else {
+        let mut v: Vec<String> = app
+            .unique_syscalls
+            .iter()
+            .filter(|s| app.shows_syscall(s))
+            .cloned()
+            .collect();
+        app.sort_syscalls(&mut v);
+        v
+    };
+    // Pins go first and do not count against the top-N cap.
+    let (mut pinned, mut rest): (Vec<String>, Vec<String>) =
+        syscalls.into_iter().partition(|s| app.pinned.contains(s));
+    let total = pinned.len() + rest.len();
+    if !app.show_all && app.top_n > 0 && app.syscall_order == SyscallOrder::ByCount {
+        rest.truncate(app.top_n);
+    }
+    pinned.append(&mut rest);
+    (pinned, total)
+}
+
+/// Returns the timeline entries that pass the current filters, newest first.
+fn visible_timeline(app: &App) -> Vec<&TimelineEntry> {
+    let matcher = SkimMatcherV2::default();
+    let filtering = app.filter_mode && !app.syscall_filter.trim().is_empty();
+    app.timeline
+        .iter()
+        .rev()
+        .filter(|entry| match entry {
+            // Notes stay in view whatever is filtered, to correlate with.
+            TimelineEntry::Note(..) => true,
+            TimelineEntry::NewPid(_) => {
+                !filtering
+                    && app.category_filter.is_none()
+                    && app.errno_filter.is_none()
+                    && app.fd_filter.is_none()
+                    && app.pid_lock.is_none()
+                    && !app.anomalies_only
+            }
+            TimelineEntry::Line(text) => {
+                // Filtered by syscall name, as the list is.
+                if filtering
+                    && !parser::parse_syscall(text).is_some_and(|call| {
+                        app.passes_filter(&matcher, call.name, &app.syscall_filter)
+                    })
+                {
+                    return false;
+                }
+                if app.pid_lock.is_some_and(|pid| {
+                    parser::split_prefix(text).0.or_else(|| app.untagged_pid()) != Some(pid)
+                }) {
+                    return false;
+                }
+                if app.fd_filter.is_some_and(|fd| !uses_fd(text, fd)) {
+                    return false;
+                }
+                if app
+                    .errno_filter
+                    .as_deref()
+                    .is_some_and(|errno| parser::errno(text) != Some(errno))
+                {
+                    return false;
+                }
+                (app.category_filter.is_none() && !app.anomalies_only)
+                    || parser::parse_syscall(text).is_some_and(|c| app.shows_syscall(c.name))
+            }
+        })
+        .collect()
+}
+
+/// Builds the raw timeline items, newest first, and the panel title.
+///
+/// Lines are cut to `width` columns; process lifecycle syscalls are highlighted
+/// and a separator marks where each new PID first appears.
+fn timeline_items(app: &App, width: usize) -> (Vec<ListItem<'static>>, String) {
+    let lifecycle = Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    let items = visible_timeline(app)
+        .into_iter()
+        .map(|entry| match entry {
+            TimelineEntry::NewPid(pid) => ListItem::new(format!("──── PID {} appears ────", pid))
+                .style(Style::default().fg(Color::DarkGray)),
+            TimelineEntry::Note(offset, text) => ListItem::new(truncate_to_width(
+                &format!("──── note {}: {} ────", note_offset(*offset), text),
+                width,
+            ))
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            TimelineEntry::Line(text) => {
+                let item = ListItem::new(truncate_to_width(&display_times(app, text), width));
+                match parser::parse_syscall(text) {
+                    Some(call) if LIFECYCLE_SYSCALLS.contains(&call.name) => item.style(lifecycle),
+                    Some(call) => item.style(Style::default().fg(app.syscall_color(call.name))),
+                    None => item,
+                }
+            }
+        })
+        .collect();
+    let mut title = "Timeline [newest first]".to_string();
+    if let Some(fd) = app.fd_filter {
+        title.push_str(&format!(" [fd {}, d: change]", fd));
+    }
+    (items, title)
+}
 
-    // Restore terminal.
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+/// Whether a syscall line operates on `fd` (passes it as the first argument)
+/// or opens it (returns it).
+fn uses_fd(line: &str, fd: i32) -> bool {
+    let Some(call) = parser::parse_syscall(line) else {
+        return false;
+    };
+    (syscalls::takes_fd(call.name) && parser::fd_argument(line) == Some(fd))
+        || (syscalls::returns_fd(call.name) && parser::returned_fd(line) == Some(fd))
+}
 
-    if let Err(err) = res {
-        println!("{:?}", err);
+/// Rewrites a line's timestamp relative to the first one captured (`+1.250000`)
+/// when relative times are shown; otherwise the line is left as strace wrote it.
+fn display_times<'a>(app: &App, text: &'a str) -> Cow<'a, str> {
+    let (Some(first), true) = (app.first_timestamp, app.relative_times) else {
+        return Cow::Borrowed(text);
+    };
+    let Some((range, seconds)) = parser::timestamp(text) else {
+        return Cow::Borrowed(text);
+    };
+    let mut elapsed = seconds - first;
+    // Times of day wrap around at midnight.
+    if elapsed < 0.0 && text[range.clone()].contains(':') {
+        elapsed += 86_400.0;
     }
-    Ok(())
+    Cow::Owned(format!(
+        "{}+{:.6}{}",
+        &text[..range.start],
+        elapsed,
+        &text[range.end..]
+    ))
 }
 
-/// The main event loop.
-fn run_app<B: ratatui::backend::Backend>(
-    terminal: &mut Terminal<B>,
-    app: &mut App,
-    tick_rate: Duration,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Declare last_tick inside run_app.
-    let mut last_tick = Instant::now();
+/// Shortens `text` to at most `width` terminal columns, ending in `…` when cut.
+fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
 
-    loop {
-        terminal.draw(|f| match app.mode {
-            AppMode::ProcessSelection => draw_process_selection(f, app),
-            AppMode::SyscallMonitoring => draw_syscall_monitoring(f, app),
-        })?;
+/// Renders the full text of the selected timeline line in a wrapped popup.
+fn draw_expanded_line<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
+    let (text, hints) = match visible_timeline(app).get(app.selected_syscall) {
+        Some(TimelineEntry::Line(text)) => {
+            (display_times(app, text).into_owned(), args::decode(text))
+        }
+        Some(TimelineEntry::NewPid(pid)) => (format!("PID {} first appears here", pid), Vec::new()),
+        Some(TimelineEntry::Note(offset, text)) => (
+            format!("Note at {}: {}", note_offset(*offset), text),
+            Vec::new(),
+        ),
+        None => return,
+    };
+    let size = f.size();
+    let width = size.width.saturating_sub(8);
+    // Word wrapping breaks early on long tokens, so leave a couple of spare rows.
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let mut rows = text.width().div_ceil(inner) as u16 + 2;
+    let mut lines = vec![Spans::from(text)];
+    if !hints.is_empty() {
+        lines.push(Spans::default());
+        for (label, value) in hints {
+            let hint = format!("{}: {}", label, value);
+            rows += hint.width().div_ceil(inner) as u16;
+            lines.push(Spans::from(vec![
+                Span::styled(format!("{}: ", label), Style::default().fg(Color::Cyan)),
+                Span::raw(value),
+            ]));
+        }
+        rows += 1;
+    }
+    let height = (rows + 2).min(size.height);
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Full line (x: close, y: copy)"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
 
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            if let CEvent::Key(key) = event::read()? {
-                match app.mode {
-                    AppMode::ProcessSelection => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char(c) => {
-                            app.process_filter.push(c);
-                            app.update_filtered_processes();
-                        }
-                        KeyCode::Backspace => {
-                            app.process_filter.pop();
-                            app.update_filtered_processes();
-                        }
-                        KeyCode::Down => {
-                            if app.selected_process + 1 < app.filtered_processes.len() {
-                                app.selected_process += 1;
-                            }
-                        }
-                        KeyCode::Up => {
-                            if app.selected_process > 0 {
-                                app.selected_process -= 1;
-                            }
-                        }
-                        KeyCode::Enter => {
-                            if !app.filtered_processes.is_empty() {
-                                let proc = &app.filtered_processes[app.selected_process];
-                                app.target_pid = proc.pid;
-                                app.target_process_name = proc.name.clone();
-                                app.mode = AppMode::SyscallMonitoring;
-                                app.unique_syscalls.clear();
-                                app.syscall_log.clear();
-                                app.filter_mode = false;
-                                app.syscall_filter.clear();
-                                app.filtered_syscalls.clear();
-                                app.start_strace(proc.pid);
-                            }
-                        }
-                        _ => {}
-                    },
-                    AppMode::SyscallMonitoring => {
-                        if app.filter_mode {
-                            // Fuzzy filtering mode.
-                            match key.code {
-                                KeyCode::Char(c) => {
-                                    app.syscall_filter.push(c);
-                                    app.update_filtered_syscalls();
-                                }
-                                KeyCode::Backspace => {
-                                    app.syscall_filter.pop();
-                                    app.update_filtered_syscalls();
-                                }
-                                KeyCode::Enter | KeyCode::Esc => {
-                                    app.filter_mode = false;
-                                    app.syscall_filter.clear();
-                                }
-                                _ => {}
-                            }
-                        } else {
-                            // Live monitoring mode.
-                            match key.code {
-                                KeyCode::Char('q') | KeyCode::Char('b') => {
-                                    app.stop_strace();
-                                    app.mode = AppMode::ProcessSelection;
-                                    app.processes = App::get_processes();
-                                    app.update_filtered_processes();
-                                }
-                                KeyCode::Char('k') => {
-                                    let pid = app.target_pid;
-                                    let _ = signal::kill(Pid::from_raw(pid), Signal::SIGKILL);
-                                    app.stop_strace();
-                                    app.mode = AppMode::ProcessSelection;
-                                    app.processes = App::get_processes();
-                                    app.update_filtered_processes();
-                                }
-                                KeyCode::Char('f') => {
-                                    app.filter_mode = true;
-                                    app.update_filtered_syscalls();
-                                }
-                                KeyCode::Char('t') => {
-                                    app.show_detailed = !app.show_detailed;
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-            }
+/// Renders the distinct call stacks of the highlighted syscall, most frequent
+/// first, in a popup.
+fn draw_callers<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
+    let Some(name) = selected_syscall_name(app) else {
+        return;
+    };
+    let mut stacks: Vec<(&Vec<String>, u64)> = app
+        .stacks
+        .get(&name)
+        .map(|stacks| {
+            stacks
+                .iter()
+                .map(|(frames, count)| (frames, *count))
+                .collect()
+        })
+        .unwrap_or_default();
+    stacks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let mut lines = Vec::new();
+    if stacks.is_empty() {
+        let message = if app.trace_options.stack_traces {
+            format!("No stacks captured for {} yet", name)
+        } else {
+            "Stack traces are off: press K, then R to restart strace with -k".to_string()
+        };
+        lines.push(Spans::from(Span::styled(
+            message,
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (frames, count) in &stacks {
+        lines.push(Spans::from(Span::styled(
+            format!("{} call{}", count, if *count == 1 { "" } else { "s" }),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.extend(
+            frames
+                .iter()
+                .map(|frame| Spans::from(format!("  {}", frame))),
+        );
+    }
+    let size = f.size();
+    let width = size.width.saturating_sub(8);
+    let height = (lines.len() as u16 + 2).min(size.height.saturating_sub(4));
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let title = format!(
+        "Callers of {}: {} distinct stack{} (w: close)",
+        name,
+        stacks.len(),
+        if stacks.len() == 1 { "" } else { "s" }
+    );
+    let popup = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// Renders the report of what changed since the baseline in a popup, each
+/// section's heading in bold.
+fn draw_changes<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
+    let Some(report) = app.change_report() else {
+        return;
+    };
+    let mut lines = Vec::new();
+    for (i, (heading, rows)) in report.sections().into_iter().enumerate() {
+        if i > 0 {
+            lines.push(Spans::from(""));
         }
+        lines.push(Spans::from(Span::styled(
+            heading,
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.extend(rows.into_iter().map(Spans::from));
+    }
+    let size = f.size();
+    let width = size.width.saturating_sub(8).min(90);
+    let height = (lines.len() as u16 + 2).min(size.height.saturating_sub(4));
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let title = format!("{} (W: close, E: export)", app.changes_title());
+    let popup = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
 
-        // Update on tick.
-        if last_tick.elapsed() >= tick_rate {
-            if let AppMode::SyscallMonitoring = app.mode {
-                if !app.filter_mode {
-                    if let Some(rx) = &app.strace_receiver {
-                        let lines: Vec<String> = rx.try_iter().collect();
-                        for line in lines {
-                            app.process_strace_line(&line);
-                        }
-                    }
-                }
-                if let Some(child) = &mut app.strace_child {
-                    if let Ok(Some(_)) = child.try_wait() {
-                        // Process ended.
-                        app.stop_strace();
-                        app.mode = AppMode::ProcessSelection;
-                        app.processes = App::get_processes();
-                        app.update_filtered_processes();
-                    }
+/// Renders the summary of the session just detached from.
+fn draw_session_report<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    lines: &[String],
+) {
+    let size = f.size();
+    let width = size.width.saturating_sub(8).min(72);
+    let height = (lines.len() as u16 + 2).min(size.height.saturating_sub(4));
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let title = format!(
+        "Session with PID {} ({}) ({}: export, other keys: close)",
+        app.target_pid,
+        app.target_process_name,
+        app.keymap.key("export")
+    );
+    let text: Vec<Spans> = lines
+        .iter()
+        .map(|line| Spans::from(line.as_str()))
+        .collect();
+    let popup = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// Scores syscall `name` against a filter query of space-separated fuzzy
+/// terms. With `all_terms` every term must match and the scores add up;
+/// otherwise one matching term is enough and the best score counts, so
+/// `read write send` lists all three families. An empty query matches
+/// everything with a score of 0.
+fn filter_score(matcher: &SkimMatcherV2, name: &str, query: &str, all_terms: bool) -> Option<i64> {
+    let scores = query
+        .split_whitespace()
+        .map(|term| matcher.fuzzy_match(name, term));
+    if all_terms {
+        scores.sum()
+    } else {
+        scores
+            .flatten()
+            .max()
+            .or_else(|| query.trim().is_empty().then_some(0))
+    }
+}
+
+/// Renders the resources popup: every file and socket the trace touched,
+/// most used first, with the calls made on it.
+fn draw_resources<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App, scroll: u16) {
+    let mut lines = Vec::new();
+    if app.resources.is_empty() {
+        lines.push(Spans::from("No calls on files or sockets captured yet"));
+    }
+    for (name, activity) in app.resources.sorted() {
+        let mut heading = vec![Span::styled(
+            name.to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        heading.push(Span::raw(format!(
+            ": {} {}",
+            activity.total(),
+            calls_word(activity.total())
+        )));
+        if activity.failures > 0 {
+            heading.push(Span::styled(
+                format!(", {} failed", activity.failures),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        lines.push(Spans::from(heading));
+        let mut calls: Vec<(&String, &u64)> = activity.calls.iter().collect();
+        calls.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let calls: Vec<String> = calls
+            .into_iter()
+            .map(|(name, count)| format!("{} {}", name, count))
+            .collect();
+        lines.push(Spans::from(format!("  {}", calls.join(", "))));
+    }
+    let size = f.size();
+    let width = size.width.saturating_sub(8);
+    let height = (lines.len() as u16 + 2).min(size.height.saturating_sub(4));
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let title = format!(
+        "Resources of {} (Up/Down: scroll, {}/Esc: close)",
+        app.target_description(),
+        app.keymap.key("resources")
+    );
+    let popup = Paragraph::new(lines)
+        .scroll((scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn calls_word(count: u64) -> &'static str {
+    if count == 1 {
+        "call"
+    } else {
+        "calls"
+    }
+}
+
+/// Whether a mapped path is a shared object, such as `libc.so.6`.
+fn is_shared_object(path: &str) -> bool {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    path.starts_with('/') && (file.ends_with(".so") || file.contains(".so."))
+}
+
+/// Renders the memory map popup: the number of regions, their total size and
+/// the shared objects mapped, or every mapping once expanded.
+fn draw_memory_map<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    view: &MapsView,
+) {
+    let maps = app
+        .memory_maps
+        .get(&view.pid)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = Vec::new();
+    let total: u64 = maps.iter().map(procfs::Mapping::size).sum();
+    lines.push(Spans::from(Span::styled(
+        format!(
+            "{} region{}, {} mapped",
+            maps.len(),
+            if maps.len() == 1 { "" } else { "s" },
+            format_bytes(total)
+        ),
+        bold,
+    )));
+    lines.push(Spans::from(""));
+    if view.expanded {
+        for mapping in maps {
+            lines.push(Spans::from(format!(
+                "{:012x}-{:012x} {} {:>7} {}",
+                mapping.start,
+                mapping.end,
+                mapping.perms,
+                format_bytes(mapping.size()),
+                mapping.path
+            )));
+        }
+    } else {
+        // Each library is mapped several times (code, data, read-only data).
+        let mut libraries: BTreeMap<&str, u64> = BTreeMap::new();
+        for mapping in maps.iter().filter(|m| is_shared_object(&m.path)) {
+            *libraries.entry(mapping.path.as_str()).or_insert(0) += mapping.size();
+        }
+        lines.push(Spans::from(Span::styled(
+            format!("Shared objects ({})", libraries.len()),
+            bold,
+        )));
+        for (path, size) in &libraries {
+            lines.push(Spans::from(format!(
+                "  {:>7} {}",
+                format_bytes(*size),
+                path
+            )));
+        }
+        let anonymous = maps.iter().filter(|m| m.path.is_empty()).count();
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(format!(
+            "{} anonymous region{}; Enter lists every mapping",
+            anonymous,
+            if anonymous == 1 { "" } else { "s" }
+        )));
+    }
+    let size = f.size();
+    let width = size.width.saturating_sub(8);
+    let height = (lines.len() as u16 + 2).min(size.height.saturating_sub(4));
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let title = format!(
+        "Memory map of PID {} ({}) (Enter: {}, Up/Down: scroll, Esc: close)",
+        view.pid,
+        view.name,
+        if view.expanded {
+            "summary"
+        } else {
+            "all mappings"
+        }
+    );
+    let popup = Paragraph::new(lines)
+        .scroll((view.scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// Where the manual page popup goes on a screen of `size`.
+fn man_page_area(size: Rect) -> Rect {
+    let width = size.width.saturating_sub(8);
+    let height = size.height.saturating_sub(4);
+    Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Renders the manual page popup, with the search text highlighted.
+fn draw_man_page<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, page: &manpage::ManPage) {
+    let area = man_page_area(f.size());
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let rows = area.height.saturating_sub(2) as usize;
+    let needle = page.search.as_deref().map(str::to_lowercase);
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let lines: Vec<Spans> = page
+        .lines
+        .iter()
+        .skip(page.scroll)
+        .take(rows)
+        .map(|line| {
+            let shown: String = line.chars().skip(page.hscroll).collect();
+            let Some(needle) = needle.as_deref().filter(|n| !n.is_empty()) else {
+                return Spans::from(shown);
+            };
+            // Lowercasing can change byte lengths outside ASCII, so match on
+            // the characters instead of byte offsets.
+            let chars: Vec<char> = shown.chars().collect();
+            let lower: Vec<char> = shown.chars().flat_map(char::to_lowercase).collect();
+            let wanted: Vec<char> = needle.chars().collect();
+            if lower.len() != chars.len() {
+                return Spans::from(shown);
+            }
+            let mut spans = Vec::new();
+            let mut start = 0;
+            let mut i = 0;
+            while i + wanted.len() <= chars.len() {
+                if lower[i..i + wanted.len()] == wanted[..] {
+                    spans.push(Span::raw(chars[start..i].iter().collect::<String>()));
+                    spans.push(Span::styled(
+                        chars[i..i + wanted.len()].iter().collect::<String>(),
+                        highlight,
+                    ));
+                    i += wanted.len();
+                    start = i;
+                } else {
+                    i += 1;
                 }
             }
-            last_tick = Instant::now();
-        }
+            spans.push(Span::raw(chars[start..].iter().collect::<String>()));
+            Spans::from(spans)
+        })
+        .collect();
+    let mut title = format!(
+        "{}(2) line {}/{} (Up/Down/PgUp/PgDn: scroll, /: search, n/N: next/previous",
+        page.name,
+        (page.scroll + 1).min(page.lines.len()),
+        page.lines.len()
+    );
+    if page.hscroll > 0 || page.has_wide_lines(inner_width) {
+        title.push_str(", Left/Right: wide lines");
     }
+    title.push_str(", Esc: close)");
+    let popup = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
 }
 
-/// Renders the process selection screen.
-fn draw_process_selection<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
+/// Renders the screen shown while waiting for a watched process to appear.
+fn draw_watching<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
     let size = f.size();
+    let compact = is_compact(size);
+    let mut constraints = vec![Constraint::Min(3)];
+    if app.show_events {
+        constraints.push(Constraint::Length(EVENTS_PANEL_HEIGHT));
+    }
+    constraints.push(Constraint::Length(bar_height(compact)));
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .margin(1)
-        .constraints(
-            [
-                Constraint::Length(3),
-                Constraint::Min(5),
-                Constraint::Length(3),
-            ]
-            .as_ref(),
-        )
+        .margin(layout_margin(compact))
+        .constraints(constraints)
         .split(size);
 
-    let filter = Paragraph::new(app.process_filter.as_ref())
-        .block(Block::default().borders(Borders::ALL).title("Fuzzy Filter"));
-    f.render_widget(filter, chunks[0]);
+    let waiting = Paragraph::new(format!(
+        "Waiting for a new process matching '{}'…",
+        app.watch_name
+    ))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL).title("Watch"));
+    f.render_widget(waiting, chunks[0]);
 
-    let items: Vec<ListItem> = app
-        .filtered_processes
-        .iter()
-        .map(|p| ListItem::new(format!("{} - {} [{}]", p.pid, p.name, p.cmd)))
-        .collect();
+    if app.show_events {
+        draw_events_panel(f, app, chunks[chunks.len() - 2]);
+    }
 
-    let process_list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Processes"))
-        .highlight_style(Style::default().bg(Color::Blue));
+    let instr = Paragraph::new("Esc or q: Cancel").block(bar_block(compact, "Instructions"));
+    f.render_widget(instr, chunks[chunks.len() - 1]);
+}
 
-    // Use ratatui's built-in ListState.
-    let mut state = ListState::default();
-    state.select(Some(app.selected_process));
-    f.render_stateful_widget(process_list, chunks[1], &mut state);
+/// Renders a text prompt in a box near the bottom of the screen.
+fn draw_prompt<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, prompt: &Prompt) {
+    let size = f.size();
+    let width = size.width.saturating_sub(4).min(100);
+    let height = 3 + prompt.error.is_some() as u16 + prompt.detail.is_some() as u16;
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width.saturating_sub(width)) / 2,
+        y: size.y + size.height.saturating_sub(height + 3),
+        width,
+        height: height.min(size.height),
+    };
+    let mut lines = Vec::new();
+    if let Some(error) = &prompt.error {
+        lines.push(Spans::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    if let Some(detail) = &prompt.detail {
+        lines.push(Spans::from(Span::styled(
+            detail.as_str(),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Spans::from(format!("{}_", prompt.input)));
+    let input = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(prompt.kind.title()),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(input, area);
+}
 
-    let instructions = Paragraph::new("Up/Down: Navigate | Type: Filter | Enter: Select | q: Quit")
-        .block(Block::default().borders(Borders::ALL).title("Instructions"));
-    f.render_widget(instructions, chunks[2]);
+/// Renders the end-of-session summary in a centered, wrapped popup.
+fn draw_summary<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, summary: &Summary) {
+    draw_notice(
+        f,
+        &format!("{} (any key to dismiss)", summary.title),
+        &summary.text,
+    );
 }
 
-/// Renders the syscall monitoring screen.
-fn draw_syscall_monitoring<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, app: &App) {
+/// Renders `text` in a centered, wrapped popup titled `title`.
+fn draw_notice<B: ratatui::backend::Backend>(f: &mut ratatui::Frame<B>, title: &str, text: &str) {
     let size = f.size();
-    let chunks = if app.filter_mode {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints(
-                [
-                    Constraint::Length(3), // header
-                    Constraint::Min(5),    // syscall list
-                    Constraint::Length(3), // filter input
-                    Constraint::Length(3), // instructions
-                ]
-                .as_ref(),
-            )
-            .split(size)
-    } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints(
-                [
-                    Constraint::Length(3), // header
-                    Constraint::Min(5),    // syscall list
-                    Constraint::Length(3), // instructions
-                ]
-                .as_ref(),
-            )
-            .split(size)
+    let width = (text.width() as u16 + 4)
+        .clamp(40, 100)
+        .min(size.width.saturating_sub(4));
+    // Word wrapping breaks early on long tokens, so leave a spare row.
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let rows = text.width().div_ceil(inner) as u16;
+    let rows = if rows > 1 { rows + 1 } else { rows };
+    let height = (rows + 2).min(size.height);
+    let area = ratatui::layout::Rect {
+        x: size.x + (size.width.saturating_sub(width)) / 2,
+        y: size.y + (size.height.saturating_sub(height)) / 2,
+        width,
+        height,
     };
+    let popup = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
 
-    let header = Paragraph::new(format!(
-        "Monitoring syscalls for PID: {} ({})",
-        app.target_pid, app.target_process_name
-    ))
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(header, chunks[0]);
+/// Short tokens for the options shaping the session, for the status line
+/// under the monitoring header: the running tracer's flags (taken from its
+/// command line, so they match what it was started with rather than pending
+/// changes), then the sampling, timing and view filters in effect, e.g.
+/// `-tt -T -f smpl1/10 T30s cat:network`.
+fn option_tokens(app: &App) -> Vec<String> {
+    let mut tokens = Vec::new();
+    if let Some(counter) = &app.counter {
+        tokens.push(counter.name().to_lowercase());
+    } else if app.proc_stats.is_some() {
+        tokens.push("proc".to_string());
+    } else if !app.reading_log() {
+        let mut args = app.strace_command.iter().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                // The target and output file, not options.
+                "-p" | "-o" => {
+                    args.next();
+                }
+                "--" => break,
+                "-e" => match args.next().map(String::as_str) {
+                    Some("trace=all") | None => {}
+                    Some(expression) => tokens.push(expression.to_string()),
+                },
+                _ => tokens.push(arg.clone()),
+            }
+        }
+    }
+    if app.sample_rate > 1 {
+        tokens.push(format!("smpl1/{}", app.sample_rate));
+    }
+    if let Some(duration) = app.capture_duration {
+        tokens.push(format!("T{}s", duration.as_secs()));
+    }
+    if app.relative_times {
+        tokens.push("rel-time".to_string());
+    }
+    if let Some(category) = app.category_filter {
+        tokens.push(format!("cat:{}", category.label().to_lowercase()));
+    }
+    if let Some(errno) = &app.errno_filter {
+        tokens.push(format!("errno:{}", errno));
+    }
+    if let Some(fd) = app.fd_filter {
+        tokens.push(format!("fd:{}", fd));
+    }
+    if let Some(pid) = app.pid_lock {
+        tokens.push(format!("pid:{}", pid));
+    }
+    if app.anomalies_only {
+        tokens.push("not-normal".to_string());
+    }
+    if tokens.is_empty() {
+        tokens.push("no options".to_string());
+    }
+    tokens
+}
 
-    let syscalls: Vec<String> = if app.filter_mode {
-        app.filtered_syscalls.clone()
-    } else {
-        let mut v: Vec<String> = if app.show_detailed {
-            app.detailed_syscalls.iter().cloned().collect()
+/// Joins key hints with ` | `, drawing disabled ones in gray.
+///
+/// In the compact layout only the keys are shown (the part of each hint before
+/// its colon), separated by spaces.
+fn hint_spans<'a>(hints: &[(&'a str, bool)], compact: bool) -> Spans<'a> {
+    let mut spans = Vec::new();
+    for (i, &(hint, enabled)) in hints.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(if compact { " " } else { " | " }));
+        }
+        let hint = if compact {
+            hint.split(':').next().unwrap_or(hint)
         } else {
-            app.unique_syscalls.iter().cloned().collect()
+            hint
         };
-        v.sort();
-        v
-    };
+        if enabled {
+            spans.push(Span::raw(hint));
+        } else {
+            spans.push(Span::styled(hint, Style::default().fg(Color::DarkGray)));
+        }
+    }
+    Spans::from(spans)
+}
 
-    let items: Vec<ListItem> = syscalls
+/// Renders the most recent event log entries that fit in `area`.
+fn draw_events_panel<B: ratatui::backend::Backend>(
+    f: &mut ratatui::Frame<B>,
+    app: &App,
+    area: ratatui::layout::Rect,
+) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let skip = app.events.len().saturating_sub(visible);
+    let items: Vec<ListItem> = app
+        .events
         .iter()
-        .rev()
-        .map(|s| ListItem::new(s.as_str()))
+        .skip(skip)
+        .map(|e| ListItem::new(e.as_str()))
         .collect();
-    let syscall_list =
-        List::new(items).block(Block::default().borders(Borders::ALL).title("Syscalls"));
-    f.render_widget(syscall_list, chunks[1]);
+    let events = List::new(items).block(Block::default().borders(Borders::ALL).title("Events"));
+    f.render_widget(events, area);
+}
 
-    if app.filter_mode {
-        let filter_input = Paragraph::new(app.syscall_filter.as_ref()).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Syscall Fuzzy Filter (Enter/Esc to resume)"),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, buffer::Buffer};
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn process(pid: i32, name: &str, cmd: &str) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            parent: None,
+            name: name.to_string(),
+            cmd: cmd.to_string(),
+            start_time: 0,
+            thread_count: Some(1),
+            user: "root".to_string(),
+            memory: 0,
+            cgroup: None,
+            cwd: None,
+            capabilities: None,
+        }
+    }
+
+    /// An app listing only `processes`, instead of the ones running.
+    fn app_with(processes: Vec<ProcessInfo>) -> App {
+        let mut app = App::new(&Config::default(), &UiState::default());
+        app.processes = processes;
+        app.update_filtered_processes();
+        app
+    }
+
+    /// The text of every row of `buffer`.
+    fn rows(buffer: &Buffer) -> Vec<String> {
+        let width = buffer.area.width as usize;
+        buffer
+            .content
+            .chunks(width)
+            .map(|row| row.iter().map(|cell| cell.symbol.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn list_offset_moves_only_when_the_selection_leaves_the_view() {
+        assert_eq!(list_offset(0, 5, 50, 10), 0);
+        assert_eq!(list_offset(0, 12, 50, 10), 3);
+        assert_eq!(list_offset(3, 8, 50, 10), 3);
+        assert_eq!(list_offset(3, 1, 50, 10), 1);
+        // A list that shrank below the offset starts over at its last item.
+        assert_eq!(list_offset(40, 45, 5, 10), 4);
+        assert_eq!(list_offset(7, 7, 0, 10), 0);
+    }
+
+    #[test]
+    fn process_list_keeps_its_scroll_between_draws() {
+        let mut app = app_with(
+            (0..50)
+                .map(|i| process(1000 + i, &format!("proc{:02}", i), "sleep"))
+                .collect(),
         );
-        f.render_widget(filter_input, chunks[2]);
-        let instr =
-            Paragraph::new("Type to filter | k: Kill process | t: Toggle details | q or b: Back")
-                .block(Block::default().borders(Borders::ALL).title("Instructions"));
-        f.render_widget(instr, chunks[3]);
-    } else {
-        let instr = Paragraph::new(
-            "f: Filter syscalls | k: Kill process | t: Toggle details | q or b: Back",
-        )
-        .block(Block::default().borders(Borders::ALL).title("Instructions"));
-        f.render_widget(instr, chunks[2]);
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        let mut shown = |app: &mut App| {
+            let frame = terminal.draw(|f| draw_process_selection(f, app)).unwrap();
+            rows(frame.buffer).join("\n")
+        };
+        app.selected_process = 49;
+        let screen = shown(&mut app);
+        assert!(screen.contains("proc49") && !screen.contains("proc00"));
+        let offset = app.process_list_offset;
+        assert!(offset > 0);
+        // Moving up inside the view leaves it where it is, rather than
+        // scrolling from the top again to just reach the highlight.
+        app.selected_process = 48;
+        let screen = shown(&mut app);
+        assert!(screen.contains("proc49"), "{}", screen);
+        assert_eq!(app.process_list_offset, offset);
+    }
+
+    #[test]
+    fn blank_process_names_fall_back_to_the_command_or_pid() {
+        assert_eq!(App::display_name("nginx", "/usr/sbin/nginx -g", 7), "nginx");
+        assert_eq!(
+            App::display_name("", "/usr/sbin/nginx -g", 7),
+            "/usr/sbin/nginx"
+        );
+        assert_eq!(App::display_name("  ", "  python3 app.py", 7), "python3");
+        assert_eq!(App::display_name("", "", 7), "[pid 7]");
+        assert_eq!(App::display_name(" ", " \t", 7), "[pid 7]");
+    }
+
+    #[test]
+    fn every_filter_term_must_match_some_field() {
+        let mut nginx = process(4321, "nginx", "nginx: worker process");
+        nginx.user = "www-data".to_string();
+        let matches = |query: &str| {
+            let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+            App::matches_terms(&nginx, &terms, None)
+        };
+        assert!(matches("nginx"));
+        // Each term may be found in a different field.
+        assert!(matches("nginx worker"));
+        assert!(matches("WWW 432 worker"));
+        assert!(matches("www-data nginx"));
+        assert!(!matches("nginx apache"));
+        assert!(!matches("worker 999"));
+        assert!(matches(""));
+    }
+
+    #[test]
+    fn selection_is_clamped_to_the_list() {
+        assert_eq!(clamp_selection(3, 10), 3);
+        assert_eq!(clamp_selection(9, 10), 9);
+        assert_eq!(clamp_selection(10, 10), 9);
+        assert_eq!(clamp_selection(42, 1), 0);
+        assert_eq!(clamp_selection(42, 0), 0);
+
+        // A narrower filter keeps the highlight on the list's last row.
+        let mut app = app_with(
+            (0..20)
+                .map(|i| process(100 + i, if i < 5 { "bash" } else { "sleep" }, ""))
+                .collect(),
+        );
+        app.selected_process = 15;
+        for (filter, len) in [("bash", 5), ("sleep", 15), ("nothing", 0), ("", 20)] {
+            app.process_filter = filter.to_string();
+            app.update_filtered_processes();
+            assert_eq!(app.filtered_processes.len(), len);
+            assert!(app.selected_process < len.max(1), "'{}'", filter);
+        }
+    }
+
+    #[test]
+    fn highlighted_process_survives_a_shrinking_list() {
+        let names = ["bash", "bashful", "sleep", "sshd", "systemd"];
+        let mut app = app_with(
+            (0..40)
+                .map(|i| process(100 + i, names[i as usize % names.len()], ""))
+                .collect(),
+        );
+        // Type a filter a key at a time with the highlight on the last row,
+        // checking after each key what Enter would attach to.
+        for filter in ["b", "ba", "bas", "bash", "bashf", "bashfz", "x", ""] {
+            app.selected_process = app.filtered_processes.len().saturating_sub(1);
+            app.process_filter = filter.to_string();
+            app.update_filtered_processes();
+            match app.highlighted_process() {
+                Some(p) => assert!(p.name.contains(filter), "'{}': {}", filter, p.name),
+                None => assert!(app.filtered_processes.is_empty(), "'{}'", filter),
+            }
+        }
+        // Processes that exit before the list is filtered again leave the
+        // indices stale, which must not panic either.
+        app.selected_process = app.filtered_processes.len() - 1;
+        while !app.processes.is_empty() {
+            app.processes
+                .truncate(app.processes.len() - 3.min(app.processes.len()));
+            if let Some(p) = app.highlighted_process() {
+                assert!(p.pid < 100 + app.processes.len() as i32);
+            }
+        }
+        assert!(app.highlighted_process().is_none());
+    }
+
+    #[test]
+    fn filter_terms_match_with_or_unless_all_are_required() {
+        let matcher = SkimMatcherV2::default();
+        let names = ["read", "write", "sendto", "openat", "readv", "close"];
+        let matching = |query: &str, all_terms: bool| -> Vec<&str> {
+            names
+                .iter()
+                .copied()
+                .filter(|name| filter_score(&matcher, name, query, all_terms).is_some())
+                .collect()
+        };
+        assert_eq!(
+            matching("read write send", false),
+            ["read", "write", "sendto", "readv"]
+        );
+        assert!(matching("read write send", true).is_empty());
+        assert_eq!(matching("read v", true), ["readv"]);
+        assert_eq!(matching("", false), names);
+        assert_eq!(matching("", true), names);
+        // Under AND the scores add up, so more matching terms rank higher.
+        let one = filter_score(&matcher, "readv", "read", true).unwrap();
+        let both = filter_score(&matcher, "readv", "read v", true).unwrap();
+        assert!(both > one);
+    }
+
+    #[test]
+    fn timeline_is_filtered_like_the_syscall_list() {
+        let mut app = app_with(Vec::new());
+        for line in [
+            "read(3, \"a\", 1) = 1",
+            "write(1, \"a\", 1) = 1",
+            "sendto(4, \"a\", 1, 0, NULL, 0) = 1",
+            "openat(AT_FDCWD, \"/read\", O_RDONLY) = 5",
+        ] {
+            app.timeline
+                .push_back(TimelineEntry::Line(line.to_string()));
+        }
+        let names = |app: &App| -> Vec<String> {
+            visible_timeline(app)
+                .into_iter()
+                .filter_map(|entry| match entry {
+                    TimelineEntry::Line(text) => {
+                        parser::parse_syscall(text).map(|call| call.name.to_string())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        app.filter_mode = true;
+        app.syscall_filter = "read write send".to_string();
+        // Newest first; a syscall's arguments do not count as a match.
+        assert_eq!(names(&app), ["sendto", "write", "read"]);
+        app.filter_all_terms = true;
+        assert!(names(&app).is_empty());
+        app.syscall_filter = "send to".to_string();
+        assert_eq!(names(&app), ["sendto"]);
+
+        // Inverted, the timeline hides what the list hides.
+        app.unique_syscalls = ["read", "write", "sendto", "openat"]
+            .map(String::from)
+            .into();
+        app.filter_all_terms = false;
+        app.filter_inverted = true;
+        app.syscall_filter = "read".to_string();
+        app.update_filtered_syscalls();
+        let mut listed = app.filtered_syscalls.clone();
+        listed.sort();
+        assert_eq!(listed, ["openat", "sendto", "write"]);
+        assert_eq!(names(&app), ["openat", "sendto", "write"]);
+        // A query of only spaces filters nothing, inverted or not.
+        app.syscall_filter = "  ".to_string();
+        app.update_filtered_syscalls();
+        assert_eq!(app.filtered_syscalls.len(), 4);
+        assert_eq!(names(&app).len(), 4);
+    }
+
+    #[test]
+    fn exports_confirmed_right_before_quitting_are_complete() {
+        let mut app = app_with(Vec::new());
+        let line = format!("openat(AT_FDCWD, \"/{}\", O_RDONLY) = 3", "x".repeat(200));
+        for _ in 0..20_000 {
+            app.timeline.push_back(TimelineEntry::Line(line.clone()));
+        }
+        let expected = app.timeline_text().len() as u64;
+        assert!(expected > 4_000_000);
+        let path =
+            std::env::temp_dir().join(format!("syscall-monitor-test-{}", std::process::id()));
+        let target = path.to_str().unwrap().to_string();
+
+        app.open_export_prompt(ExportKind::Timeline);
+        app.prompt.as_mut().unwrap().input = target.clone();
+        app.handle_prompt_key(event::KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        // Confirming only queues the export; the loop writes it before it
+        // reads the next key, and shutdown writes it if that key was a quit.
+        assert!(matches!(
+            &app.pending_export,
+            Some((ExportKind::Timeline, queued)) if *queued == target
+        ));
+        assert!(!path.exists());
+        assert!(app.write_pending_export());
+        assert!(app.pending_export.is_none());
+        let reported = app.summary.take().map(|summary| summary.text);
+        drop(app);
+
+        let written = std::fs::metadata(&path).map(|m| m.len());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written.unwrap(), expected);
+        assert!(reported
+            .unwrap()
+            .starts_with(&format!("Wrote {} bytes", expected)));
+    }
+
+    #[test]
+    fn strace_args_that_take_over_the_trace_are_refused() {
+        for args in [
+            &["-o", "out"][..],
+            &["-p1"],
+            &["-D"],
+            &["-DD"],
+            &["-fDDD"],
+            &["--daemonize=grandchild"],
+            &["-b", "execve"],
+            &["--detach-on=execve"],
+            &["--kill-on-exit"],
+            &["-e", "inject=read:error=EIO"],
+            &["--", "ls"],
+        ] {
+            assert!(check_strace_args(&strings(args)).is_err(), "{:?}", args);
+        }
+        for args in [
+            &["-y", "-yy", "-X", "raw"][..],
+            &["-s", "256"],
+            &["-e", "trace=file"],
+        ] {
+            assert!(check_strace_args(&strings(args)).is_ok(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn stats_columns_window_keeps_one_column_to_scroll() {
+        // Name, then each column and its spacing.
+        let wide = STATS_NAME_WIDTH + 4 * (STATS_COLUMN_WIDTH + 1);
+        assert_eq!(stats_column_window(wide, 0), (0, 4));
+        assert_eq!(stats_column_window(wide + 30, 3), (0, 4));
+        assert_eq!(stats_column_window(wide - 1, 0), (0, 3));
+        assert_eq!(stats_column_window(wide - 1, 5), (1, 3));
+        assert_eq!(stats_column_window(STATS_NAME_WIDTH + 11, 2), (2, 1));
+        // Too narrow for even one: it is squeezed in rather than none shown.
+        for inner in [0, 5, STATS_NAME_WIDTH, STATS_NAME_WIDTH + 10] {
+            assert_eq!(stats_column_window(inner, 0), (0, 1));
+            assert_eq!(stats_column_window(inner, 9), (3, 1));
+        }
+    }
+
+    #[test]
+    fn top_n_of_zero_shows_every_syscall() {
+        let mut app = app_with(Vec::new());
+        for (name, count) in [("read", 30), ("write", 20), ("close", 10)] {
+            app.unique_syscalls.insert(name.to_string());
+            app.syscall_counts.insert(name.to_string(), count);
+        }
+        app.syscall_order = SyscallOrder::ByCount;
+        app.resort();
+        app.show_all = false;
+        app.top_n = 2;
+        assert_eq!(
+            visible_syscalls(&app),
+            (vec!["read".into(), "write".into()], 3)
+        );
+        app.top_n = 0;
+        assert_eq!(visible_syscalls(&app).0, ["read", "write", "close"]);
     }
 }
@@ -0,0 +1,252 @@
+//! The manual page viewer: `man 2` for the highlighted syscall, read into a
+//! scrollable popup instead of handing the terminal over to a pager.
+//!
+//! The page is formatted by `man` itself at the popup's width, so prose wraps
+//! where `man` would wrap it. Lines it cannot break, such as wide examples,
+//! stay whole and are scrolled horizontally.
+//!
+//! `man` runs while the UI waits, as formatting a page takes a moment, but
+//! it is given up on after [`TIMEOUT`] in case it hangs, for example on a
+//! stale lock of its cache.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::installed;
+
+/// How long `man` may take before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A loaded manual page and where it is scrolled to.
+pub(crate) struct ManPage {
+    /// The syscall the page is for.
+    pub(crate) name: String,
+    pub(crate) lines: Vec<String>,
+    /// The first line shown.
+    pub(crate) scroll: usize,
+    /// Columns cut off the left of every line.
+    pub(crate) hscroll: usize,
+    /// Lines shown at once, for paging.
+    pub(crate) page: usize,
+    /// Columns shown at once, which bound how far lines scroll to the left.
+    width: usize,
+    /// The last text searched for with `/`.
+    pub(crate) search: Option<String>,
+    /// The line of the last match, which `n` and `N` go on from.
+    matched: Option<usize>,
+}
+
+impl ManPage {
+    /// Runs `man 2 name` formatted `width` columns wide, for a popup showing
+    /// `page` lines at once.
+    pub(crate) fn load(name: &str, width: u16, page: usize) -> Result<Self, String> {
+        if !installed("man") {
+            return Err("man is not installed".to_string());
+        }
+        let mut child = Command::new("man")
+            .args(["2", name])
+            .env("MANWIDTH", width.to_string())
+            .env("MANPAGER", "cat")
+            .env("PAGER", "cat")
+            .env("GROFF_NO_SGR", "1")
+            .env_remove("MAN_KEEP_FORMATTING")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("man failed: {}", err))?;
+        // Read on threads of their own, so a long page cannot fill a pipe
+        // and stall `man` while its exit is waited for.
+        let stdout = read_all(child.stdout.take());
+        let stderr = read_all(child.stderr.take());
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started.elapsed() < TIMEOUT => {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    // The readers are left behind: a formatter `man` started
+                    // may still hold the pipes open.
+                    return Err(format!(
+                        "man 2 {} did not finish within {} seconds",
+                        name,
+                        TIMEOUT.as_secs()
+                    ));
+                }
+                Err(err) => return Err(format!("man failed: {}", err)),
+            }
+        };
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+        let text = String::from_utf8_lossy(&stdout);
+        if !status.success() || text.trim().is_empty() {
+            let stderr = String::from_utf8_lossy(&stderr);
+            let reason = stderr.trim();
+            return Err(if reason.is_empty() {
+                format!("No manual entry for {} in section 2", name)
+            } else {
+                reason.to_string()
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            lines: text.lines().map(plain).collect(),
+            scroll: 0,
+            hscroll: 0,
+            page: page.max(1),
+            width: width as usize,
+            search: None,
+            matched: None,
+        })
+    }
+
+    /// Scrolls down by `lines`, or up if negative, keeping the last page full.
+    pub(crate) fn scroll_by(&mut self, lines: isize) {
+        let last = self.lines.len().saturating_sub(self.page);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(last);
+        self.matched = None;
+    }
+
+    /// Scrolls right by `columns`, or left if negative, no further than
+    /// shows the end of the widest line.
+    pub(crate) fn scroll_sideways(&mut self, columns: isize) {
+        let last = self.max_width().saturating_sub(self.width);
+        self.hscroll = self.hscroll.saturating_add_signed(columns).min(last);
+    }
+
+    /// Fits the page to a popup `width` columns wide showing `page` lines,
+    /// after the terminal was resized.
+    pub(crate) fn fit(&mut self, width: usize, page: usize) {
+        self.width = width;
+        self.page = page.max(1);
+        self.scroll_sideways(0);
+        let last = self.lines.len().saturating_sub(self.page);
+        self.scroll = self.scroll.min(last);
+    }
+
+    /// The width of the widest line, in columns.
+    pub(crate) fn max_width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether any line is wider than `width` columns.
+    pub(crate) fn has_wide_lines(&self, width: usize) -> bool {
+        self.max_width() > width
+    }
+
+    /// Scrolls to the next line containing the search text after the last
+    /// match, or the top line if scrolled since (from that line itself if
+    /// `include_current`), or to the previous one before it, wrapping around
+    /// the page.
+    /// Returns whether there was a match at all.
+    pub(crate) fn find(&mut self, forward: bool, include_current: bool) -> bool {
+        let Some(needle) = self.search.as_deref().map(str::to_lowercase) else {
+            return false;
+        };
+        let count = self.lines.len();
+        let from = self.matched.unwrap_or(self.scroll);
+        let start = if include_current { 0 } else { 1 };
+        let found = (start..=count).find_map(|step| {
+            let index = if forward {
+                (from + step) % count.max(1)
+            } else {
+                (from + count * 2 - step) % count.max(1)
+            };
+            self.lines
+                .get(index)
+                .filter(|line| line.to_lowercase().contains(&needle))
+                .map(|_| index)
+        });
+        match found {
+            Some(index) => {
+                // Near the end the match is shown lower down instead, so the
+                // last page stays full.
+                self.scroll = index.min(self.lines.len().saturating_sub(self.page));
+                self.matched = Some(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Reads `pipe` to its end on a thread of its own.
+fn read_all<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// `line` without the overstrikes `man` uses for bold and underlined text
+/// when its output is not cleaned up on the way.
+fn plain(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    for c in line.chars() {
+        if c == '\u{8}' {
+            out.pop();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(lines: &[&str], width: usize) -> ManPage {
+        ManPage {
+            name: "read".to_string(),
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            scroll: 0,
+            hscroll: 0,
+            page: 2,
+            width,
+            search: None,
+            matched: None,
+        }
+    }
+
+    #[test]
+    fn sideways_scroll_stops_at_the_widest_line() {
+        let mut page = page(&["short", &"x".repeat(30), "mid line"], 10);
+        assert_eq!(page.max_width(), 30);
+        for _ in 0..10 {
+            page.scroll_sideways(8);
+        }
+        assert_eq!(page.hscroll, 20);
+        page.scroll_sideways(-8);
+        assert_eq!(page.hscroll, 12);
+        // A wider popup needs less scrolling to show the end of the line.
+        page.fit(25, 2);
+        assert_eq!(page.hscroll, 5);
+        page.fit(40, 2);
+        assert_eq!(page.hscroll, 0);
+        page.scroll_sideways(8);
+        assert_eq!(page.hscroll, 0);
+    }
+
+    #[test]
+    fn fitting_keeps_the_last_page_full() {
+        let mut page = page(&["a", "b", "c", "d"], 10);
+        page.scroll_by(isize::MAX);
+        assert_eq!(page.scroll, 2);
+        page.fit(10, 3);
+        assert_eq!(page.scroll, 1);
+    }
+}
@@ -0,0 +1,48 @@
+//! Alerts for events worth looking up from another window for: the traced
+//! process exiting, or a syscall from the `notify_syscalls` list showing up.
+//!
+//! Two independent channels are available, both off by default: the terminal
+//! bell, and a desktop notification sent through `notify-send`. Where neither
+//! a bell nor a notification daemon exists, the alert quietly goes nowhere.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::config::Config;
+
+/// Sends alerts over the channels enabled in the config.
+pub(crate) struct Notifier {
+    bell: bool,
+    desktop: bool,
+}
+
+impl Notifier {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            bell: config.notify_bell,
+            desktop: config.notify_desktop,
+        }
+    }
+
+    /// Rings the bell and/or posts `message` as a desktop notification.
+    pub(crate) fn notify(&self, message: &str) {
+        if self.bell {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+        }
+        if self.desktop {
+            let spawned = Command::new("notify-send")
+                .arg("syscall-monitor")
+                .arg(message)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            // Reaped in the background so a slow daemon never stalls the UI.
+            if let Ok(mut child) = spawned {
+                thread::spawn(move || child.wait());
+            }
+        }
+    }
+}
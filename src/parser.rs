@@ -0,0 +1,317 @@
+//! Parsing of the lines strace writes for each traced syscall.
+//!
+//! A line may start with a `[pid N]` tag (when following forks) and a
+//! timestamp (`-t`, `-tt`, `-ttt` or `-r`), and may end with the time spent in
+//! the call (`-T`, e.g. `<0.000123>`). With several threads traced, strace
+//! splits a call that is interrupted by another into an unfinished first half
+//! and a resumed second half:
+//!
+//! ```text
+//! [pid 123] read(3,  <unfinished ...>
+//! [pid 124] write(1, "x", 1) = 1
+//! [pid 123] <... read resumed>"abc", 3) = 3
+//! ```
+//!
+//! With `-k`, each call is followed by its stack, one ` > ` line per frame.
+
+use std::io::BufRead;
+use std::ops::Range;
+use std::time::Duration;
+
+/// Splits strace output into lines, stopping at the first read error.
+///
+/// strace passes bytes from string arguments through unescaped in some modes,
+/// so a line need not be valid UTF-8; invalid sequences become U+FFFD instead
+/// of losing the line. Only `\n` ends a line, a trailing `\r` is dropped, and
+/// other control characters are flattened to spaces so they cannot disturb
+/// the terminal.
+pub(crate) fn lossy_lines(reader: impl BufRead) -> impl Iterator<Item = String> {
+    reader.split(b'\n').map_while(Result::ok).map(|bytes| {
+        let bytes = bytes.strip_suffix(b"\r").unwrap_or(&bytes);
+        String::from_utf8_lossy(bytes)
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect()
+    })
+}
+
+/// Which part of a syscall a line describes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CallKind {
+    /// The whole call, from arguments to return value.
+    Complete,
+    /// The first half of a call that strace interrupted, ending in `<unfinished ...>`.
+    Unfinished,
+    /// The second half of an interrupted call, starting with `<... name resumed>`.
+    Resumed,
+}
+
+/// The parts of a syscall line the inspector uses.
+pub(crate) struct Syscall<'a> {
+    /// PID from the `[pid N]` tag, if any.
+    pub pid: Option<i32>,
+    pub name: &'a str,
+    pub kind: CallKind,
+}
+
+/// Strips a leading `[pid N]` tag and timestamp off a line, returning the PID
+/// and the rest.
+pub(crate) fn split_prefix(line: &str) -> (Option<i32>, &str) {
+    let (pid, rest) = split_pid_prefix(line.trim());
+    (pid, strip_timestamp(rest))
+}
+
+/// Parses a syscall line; other lines (signals, exits, diagnostics) yield `None`.
+pub(crate) fn parse_syscall(line: &str) -> Option<Syscall<'_>> {
+    let (pid, call) = split_prefix(line);
+    if let Some(rest) = call.strip_prefix("<... ") {
+        let (name, _) = rest.split_once(" resumed>")?;
+        return is_syscall_name(name).then_some(Syscall {
+            pid,
+            name,
+            kind: CallKind::Resumed,
+        });
+    }
+    let (name, _) = call.split_once('(')?;
+    if !is_syscall_name(name) {
+        return None;
+    }
+    let kind = if call.trim_end().ends_with("<unfinished ...>") {
+        CallKind::Unfinished
+    } else {
+        CallKind::Complete
+    };
+    Some(Syscall { pid, name, kind })
+}
+
+/// Returns the frame of a ` > /usr/lib/libc.so.6(read+0x14) [0x10e4b4]` line,
+/// which `-k` prints below each call for every level of its stack.
+pub(crate) fn stack_frame(line: &str) -> Option<&str> {
+    let (_, rest) = split_prefix(line);
+    rest.strip_prefix("> ").map(str::trim)
+}
+
+/// Returns the time spent in the call, from the `<0.000123>` that `-T` appends
+/// to the line carrying the return value.
+pub(crate) fn duration(line: &str) -> Option<Duration> {
+    let (_, secs) = line.trim_end().strip_suffix('>')?.rsplit_once('<')?;
+    if !secs.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Duration::try_from_secs_f64(secs.parse().ok()?).ok()
+}
+
+/// Returns the error name of a failed call, from results such as
+/// `= -1 ENOENT (No such file or directory)` or `= ? ERESTARTSYS (...)`.
+pub(crate) fn errno(line: &str) -> Option<&str> {
+    let (_, result) = line.rsplit_once(" = ")?;
+    let mut tokens = result.split_whitespace();
+    let value = tokens.next()?;
+    if !value.starts_with('-') && value != "?" {
+        return None;
+    }
+    let name = tokens.next()?;
+    let is_errno = name.len() > 1
+        && name.starts_with('E')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    is_errno.then_some(name)
+}
+
+/// Returns the first argument of a call as a file descriptor, e.g. 3 for
+/// `read(3, ...)` or, with `-y`, `read(3</etc/passwd>, ...)`. Whether that
+/// argument is an fd at all depends on the syscall.
+pub(crate) fn fd_argument(line: &str) -> Option<i32> {
+    let (_, call) = split_prefix(line);
+    let (_, args) = call.split_once('(')?;
+    leading_number(args)
+}
+
+/// Returns a call's non-negative result as a file descriptor, e.g. 3 for
+/// `openat(AT_FDCWD, "/etc/passwd", O_RDONLY) = 3` or `= 3</etc/passwd>`.
+pub(crate) fn returned_fd(line: &str) -> Option<i32> {
+    let (_, result) = line.rsplit_once(" = ")?;
+    leading_number(result)
+}
+
+/// Parses the digits `text` starts with.
+fn leading_number(text: &str) -> Option<i32> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+/// Joins the halves of a split call into the line strace would have printed had
+/// the call not been interrupted, keeping the prefix of the unfinished half:
+/// `read(3,  <unfinished ...>` and `<... read resumed>"abc", 3) = 3` become
+/// `read(3, "abc", 3) = 3`.
+pub(crate) fn join_split_call(unfinished: &str, resumed: &str) -> Option<String> {
+    let head = unfinished
+        .trim_end()
+        .strip_suffix("<unfinished ...>")?
+        .trim_end();
+    let (_, tail) = resumed.split_once(" resumed>")?;
+    let tail = tail.trim_start();
+    let separator = if head.ends_with(',') && !tail.starts_with(')') {
+        " "
+    } else {
+        ""
+    };
+    Some(format!("{}{}{}", head, separator, tail))
+}
+
+/// Splits a leading `[pid N]` tag off a line, returning the PID and the rest.
+fn split_pid_prefix(line: &str) -> (Option<i32>, &str) {
+    if let Some(rest) = line.strip_prefix("[pid ") {
+        if let Some((pid, call)) = rest.split_once(']') {
+            if let Ok(pid) = pid.trim().parse() {
+                return (Some(pid), call.trim_start());
+            }
+        }
+    }
+    (None, line)
+}
+
+/// Finds the timestamp of a line, returning where it sits in `line` and its
+/// value in seconds: since midnight for `12:00:00.123456` (`-t`/`-tt`), since
+/// the epoch for `-ttt`, or since the previous call for `-r`.
+pub(crate) fn timestamp(line: &str) -> Option<(Range<usize>, f64)> {
+    let trimmed = line.trim_start();
+    let (_, rest) = split_pid_prefix(trimmed);
+    let start = line.len() - rest.len();
+    let stamp = rest.split(' ').next()?;
+    if strip_timestamp(rest).len() == rest.len() {
+        return None;
+    }
+    let mut seconds = 0.0;
+    for part in stamp.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some((start..start + stamp.len(), seconds))
+}
+
+/// Removes a leading timestamp such as `12:00:00`, `12:00:00.123456`,
+/// `1700000000.123456` or a relative `0.000123`.
+fn strip_timestamp(line: &str) -> &str {
+    let Some((first, rest)) = line.split_once(' ') else {
+        return line;
+    };
+    let looks_like_time = first.starts_with(|c: char| c.is_ascii_digit())
+        && first
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ':' || c == '.');
+    if looks_like_time {
+        rest.trim_start()
+    } else {
+        line
+    }
+}
+
+/// Whether `name` could be a syscall name: a lowercase identifier.
+fn is_syscall_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pid_prefix() {
+        let call = parse_syscall("[pid  4321] openat(AT_FDCWD, \"/etc\", O_RDONLY) = 3").unwrap();
+        assert_eq!(call.pid, Some(4321));
+        assert_eq!(call.name, "openat");
+        assert!(call.kind == CallKind::Complete);
+
+        let call = parse_syscall("[pid 123] read(3, \"hello\", 5) = 5").unwrap();
+        assert_eq!(call.pid, Some(123));
+        assert_eq!(call.name, "read");
+
+        let call = parse_syscall("read(0, \"\", 1) = 0").unwrap();
+        assert_eq!(call.pid, None);
+        assert_eq!(call.name, "read");
+
+        assert!(parse_syscall("[pid 12] +++ exited with 0 +++").is_none());
+        assert!(parse_syscall("--- SIGCHLD {si_signo=SIGCHLD} ---").is_none());
+    }
+
+    #[test]
+    fn errno_of_failed_calls() {
+        let line = "openat(AT_FDCWD, \"x\", O_RDONLY) = -1 ENOENT (No such file or directory)";
+        let call = parse_syscall(line).unwrap();
+        assert_eq!(call.name, "openat");
+        assert!(call.kind == CallKind::Complete);
+        assert_eq!(errno(line), Some("ENOENT"));
+        assert_eq!(
+            errno("read(3, 0x7ffd, 10) = ? ERESTARTSYS (To be restarted)"),
+            Some("ERESTARTSYS")
+        );
+        assert_eq!(errno("read(3, \"abc\", 3) = 3"), None);
+        assert_eq!(errno("lseek(3, -1, SEEK_CUR) = -1 EINVAL"), Some("EINVAL"));
+        // A timeout is a successful return, explained in parentheses.
+        assert_eq!(
+            errno("poll([{fd=3, events=POLLIN}], 1, 1) = 0 (Timeout) <0.001>"),
+            None
+        );
+    }
+
+    #[test]
+    fn timestamp_and_duration() {
+        let line = "12:00:00.123 poll([{fd=3, events=POLLIN}], 1, 1) = 0 (Timeout) <0.001>";
+        let call = parse_syscall(line).unwrap();
+        assert_eq!(call.name, "poll");
+        assert!(call.kind == CallKind::Complete);
+        assert_eq!(duration(line), Some(Duration::from_millis(1)));
+        assert_eq!(duration("close(3) = 0"), None);
+        assert_eq!(duration("write(1, \"<x>\", 3) = 3"), None);
+
+        for line in [
+            "12:00:00 close(3) = 0",
+            "1700000000.123456 close(3) = 0",
+            "[pid 200]      0.000123 close(3) = 0",
+        ] {
+            assert_eq!(parse_syscall(line).unwrap().name, "close", "{}", line);
+        }
+        let line = "[pid 200] 12:00:00.123 nanosleep({tv_sec=1, tv_nsec=0}, NULL) = 0 <0.001>";
+        let (range, seconds) = timestamp(line).unwrap();
+        assert_eq!(&line[range], "12:00:00.123");
+        assert!((seconds - 43_200.123).abs() < 1e-6);
+        assert_eq!(
+            timestamp("1700000000.5 close(3) = 0").unwrap().1,
+            1_700_000_000.5
+        );
+        assert!(timestamp("close(3) = 0").is_none());
+        let (pid, rest) = split_prefix("[pid 200] 12:00:00.123 close(3) = 0");
+        assert_eq!(pid, Some(200));
+        assert_eq!(rest, "close(3) = 0");
+    }
+
+    #[test]
+    fn split_calls_are_joined() {
+        let unfinished = "[pid 123] read(3,  <unfinished ...>";
+        let resumed = "[pid 123] <... read resumed>\"abc\", 3) = 3";
+        let call = parse_syscall(unfinished).unwrap();
+        assert!(call.kind == CallKind::Unfinished);
+        assert_eq!(call.name, "read");
+        let call = parse_syscall(resumed).unwrap();
+        assert!(call.kind == CallKind::Resumed);
+        assert_eq!(call.name, "read");
+        assert_eq!(call.pid, Some(123));
+        assert_eq!(
+            join_split_call(unfinished, resumed).as_deref(),
+            Some("[pid 123] read(3, \"abc\", 3) = 3")
+        );
+
+        // Nothing to add between the halves when the call took no more arguments.
+        assert_eq!(
+            join_split_call("getpid( <unfinished ...>", "<... getpid resumed>) = 7").as_deref(),
+            Some("getpid() = 7")
+        );
+        assert_eq!(join_split_call("read(3, \"abc\", 3) = 3", resumed), None);
+    }
+}
@@ -0,0 +1,403 @@
+//! Access to the `/proc` filesystem.
+//!
+//! `/proc` may be missing altogether (not mounted in a container) or partly
+//! hidden (`hidepid`, other users' entries without root), so every reader here
+//! returns a `Result` and callers show an "unavailable" placeholder instead of
+//! an empty or misleading value.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What a process is doing right now, from `/proc/<pid>/syscall`.
+pub(crate) enum SyscallState {
+    /// Executing in user space.
+    Running,
+    /// Blocked, but not inside a syscall (e.g. stopped by a signal).
+    NotInSyscall,
+    /// Blocked in the syscall with this number.
+    In(u64),
+}
+
+/// One region of a process's address space, from `/proc/<pid>/maps`.
+pub(crate) struct Mapping {
+    pub(crate) start: u64,
+    pub(crate) end: u64,
+    /// Permissions such as `r-xp`.
+    pub(crate) perms: String,
+    /// The mapped file, a pseudo-path like `[heap]`, or empty for anonymous
+    /// memory.
+    pub(crate) path: String,
+}
+
+impl Mapping {
+    pub(crate) fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Cumulative activity counters of a process, from `/proc/<pid>/stat` and
+/// `/proc/<pid>/status`.
+#[derive(Clone, Copy)]
+pub(crate) struct Counters {
+    /// CPU time in user and kernel mode, in clock ticks.
+    pub(crate) utime: u64,
+    pub(crate) stime: u64,
+    /// Page faults served without and with disk I/O.
+    pub(crate) minor_faults: u64,
+    pub(crate) major_faults: u64,
+    /// Context switches because the process blocked, and because it was
+    /// preempted.
+    pub(crate) voluntary_switches: u64,
+    pub(crate) involuntary_switches: u64,
+}
+
+/// The real and effective user and group IDs of a process, from the `Uid:`
+/// and `Gid:` lines of `/proc/<pid>/status`.
+pub(crate) struct Credentials {
+    pub(crate) real_uid: u32,
+    pub(crate) effective_uid: u32,
+    pub(crate) real_gid: u32,
+    pub(crate) effective_gid: u32,
+}
+
+impl Credentials {
+    /// Whether the process runs with IDs other than those of who started it,
+    /// as a setuid or setgid program does.
+    pub(crate) fn elevated(&self) -> bool {
+        self.real_uid != self.effective_uid || self.real_gid != self.effective_gid
+    }
+}
+
+/// Whether `/proc` is mounted and readable at all.
+pub(crate) fn available() -> bool {
+    Path::new("/proc/self/status").exists()
+}
+
+/// Reads the `Threads:` count from `/proc/<pid>/status`.
+pub(crate) fn thread_count(pid: i32) -> io::Result<u32> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid))?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Threads:"))
+        .and_then(|count| count.trim().parse().ok())
+        .ok_or_else(|| malformed("status has no Threads: line"))
+}
+
+/// Reads the real and effective user and group IDs of `pid`.
+pub(crate) fn credentials(pid: i32) -> io::Result<Credentials> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid))?;
+    // Real, effective, saved and filesystem IDs, in that order.
+    let ids = |field: &str| -> io::Result<(u32, u32)> {
+        let mut values = status
+            .lines()
+            .find_map(|line| line.strip_prefix(field))
+            .ok_or_else(|| malformed("status has no Uid:/Gid: lines"))?
+            .split_whitespace()
+            .map(|value| value.parse().map_err(|_| malformed("bad ID in status")));
+        match (values.next(), values.next()) {
+            (Some(real), Some(effective)) => Ok((real?, effective?)),
+            _ => Err(malformed("status has too few IDs")),
+        }
+    };
+    let (real_uid, effective_uid) = ids("Uid:")?;
+    let (real_gid, effective_gid) = ids("Gid:")?;
+    Ok(Credentials {
+        real_uid,
+        effective_uid,
+        real_gid,
+        effective_gid,
+    })
+}
+
+/// Reads the effective and permitted capability masks (`CapEff:` and
+/// `CapPrm:`, in hex) from `/proc/<pid>/status`.
+pub(crate) fn capabilities(pid: i32) -> io::Result<(u64, u64)> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let mask = |field: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(field))
+            .and_then(|mask| u64::from_str_radix(mask.trim(), 16).ok())
+            .ok_or_else(|| malformed("status has no capability masks"))
+    };
+    Ok((mask("CapEff:")?, mask("CapPrm:")?))
+}
+
+/// Reads what `pid` is doing from `/proc/<pid>/syscall`.
+pub(crate) fn syscall_state(pid: i32) -> io::Result<SyscallState> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/syscall", pid))?;
+    match contents.split_whitespace().next().unwrap_or("") {
+        "running" => Ok(SyscallState::Running),
+        "-1" => Ok(SyscallState::NotInSyscall),
+        first => first
+            .parse()
+            .map(SyscallState::In)
+            .map_err(|_| malformed("unexpected syscall contents")),
+    }
+}
+
+/// Reads the command name of `pid` from `/proc/<pid>/comm`.
+pub(crate) fn comm(pid: i32) -> io::Result<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid))?;
+    Ok(comm.trim_end().to_string())
+}
+
+/// Reads the name of thread `tid` of process `pid` from
+/// `/proc/<pid>/task/<tid>/comm`; it fails if `tid` is not one of its threads.
+pub(crate) fn thread_name(pid: i32, tid: i32) -> io::Result<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{}/task/{}/comm", pid, tid))?;
+    Ok(comm.trim_end().to_string())
+}
+
+/// Whether `pid` has exited but is still waiting to be reaped by its parent.
+pub(crate) fn is_zombie(pid: i32) -> io::Result<bool> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let (_, rest) = stat
+        .rsplit_once(')')
+        .ok_or_else(|| malformed("stat has no command name"))?;
+    Ok(rest.split_whitespace().next() == Some("Z"))
+}
+
+/// Reads the working directory of `pid` from the `/proc/<pid>/cwd` link,
+/// which only the process's owner (or root) may follow.
+pub(crate) fn cwd(pid: i32) -> io::Result<PathBuf> {
+    std::fs::read_link(format!("/proc/{}/cwd", pid))
+}
+
+/// Lists the children of `pid`'s main thread, oldest first.
+pub(crate) fn children(pid: u32) -> io::Result<Vec<i32>> {
+    let contents = std::fs::read_to_string(format!("/proc/{0}/task/{0}/children", pid))?;
+    Ok(contents
+        .split_whitespace()
+        .filter_map(|p| p.parse().ok())
+        .collect())
+}
+
+/// Reads the cgroup path of `pid` from `/proc/<pid>/cgroup`.
+///
+/// The unified (v2) hierarchy is preferred, then systemd's v1 hierarchy, then
+/// any other v1 controller that places the process somewhere other than the
+/// root, so mixed setups still reveal the container.
+pub(crate) fn cgroup(pid: i32) -> io::Result<String> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/cgroup", pid))?;
+    let mut paths: Vec<(&str, &str)> = contents
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(':')?;
+            rest.split_once(':')
+        })
+        .collect();
+    if paths.is_empty() {
+        return Err(malformed("cgroup has no entries"));
+    }
+    let rank = |controllers: &str| match controllers {
+        "" => 0,
+        "name=systemd" => 1,
+        _ => 2,
+    };
+    paths.sort_by_key(|(controllers, _)| rank(controllers));
+    let path = paths
+        .iter()
+        .map(|(_, path)| *path)
+        .find(|path| *path != "/")
+        .unwrap_or("/");
+    Ok(path.to_string())
+}
+
+/// Lists the PIDs of every process in `/proc`.
+pub(crate) fn pids() -> io::Result<Vec<i32>> {
+    Ok(std::fs::read_dir("/proc")?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect())
+}
+
+/// Reads the parent of `pid` from `/proc/<pid>/stat`; 0 for the kernel's
+/// own processes and init.
+pub(crate) fn parent(pid: i32) -> io::Result<i32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let (_, rest) = stat
+        .rsplit_once(')')
+        .ok_or_else(|| malformed("stat has no command name"))?;
+    rest.split_whitespace()
+        .nth(1)
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| malformed("stat is too short"))
+}
+
+/// Reads what descriptor `fd` of `pid` refers to, such as `/etc/passwd` or
+/// `socket:[12345]`.
+pub(crate) fn fd_target(pid: i32, fd: i32) -> io::Result<String> {
+    std::fs::read_link(format!("/proc/{}/fd/{}", pid, fd))
+        .map(|target| target.display().to_string())
+}
+
+/// Reads the memory regions of `pid` from `/proc/<pid>/maps`, which needs
+/// the same access as tracing it.
+pub(crate) fn maps(pid: i32) -> io::Result<Vec<Mapping>> {
+    let maps = std::fs::read_to_string(format!("/proc/{}/maps", pid))?;
+    maps.lines()
+        .map(|line| {
+            // address perms offset dev inode [path]; the path may contain spaces.
+            let mut fields = line.splitn(6, ' ');
+            let (start, end) = fields
+                .next()
+                .and_then(|range| range.split_once('-'))
+                .and_then(|(start, end)| {
+                    Some((
+                        u64::from_str_radix(start, 16).ok()?,
+                        u64::from_str_radix(end, 16).ok()?,
+                    ))
+                })
+                .ok_or_else(|| malformed("maps has a bad address range"))?;
+            let perms = fields.next().unwrap_or_default().to_string();
+            let path = fields.nth(3).unwrap_or_default().trim().to_string();
+            Ok(Mapping {
+                start,
+                end,
+                perms,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Clock ticks per second, the unit of the CPU times in [`Counters`].
+pub(crate) fn clock_ticks() -> u64 {
+    // SAFETY: `sysconf` only reads a configuration value.
+    let ticks = unsafe { nix::libc::sysconf(nix::libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as u64
+    } else {
+        100
+    }
+}
+
+/// Reads the activity counters of `pid`, which needs no ptrace access.
+pub(crate) fn counters(pid: i32) -> io::Result<Counters> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let (_, rest) = stat
+        .rsplit_once(')')
+        .ok_or_else(|| malformed("stat has no command name"))?;
+    // Fields after the command name, from state (field 3) on.
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |number: usize| -> io::Result<u64> {
+        fields
+            .get(number - 3)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| malformed("stat is too short"))
+    };
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let switches = |key: &str| -> io::Result<u64> {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|value| value.trim().parse().ok())
+            .ok_or_else(|| malformed("status has no context switch counts"))
+    };
+    Ok(Counters {
+        minor_faults: field(10)?,
+        major_faults: field(12)?,
+        utime: field(14)?,
+        stime: field(15)?,
+        voluntary_switches: switches("voluntary_ctxt_switches:")?,
+        involuntary_switches: switches("nonvoluntary_ctxt_switches:")?,
+    })
+}
+
+/// Reads the process group and session of `pid` from `/proc/<pid>/stat`.
+pub(crate) fn group_and_session(pid: i32) -> io::Result<(i32, i32)> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    // The command name is parenthesized and may itself contain spaces and
+    // parentheses, so fields are counted from the last `)`: state, ppid,
+    // pgrp, session.
+    let (_, rest) = stat
+        .rsplit_once(')')
+        .ok_or_else(|| malformed("stat has no command name"))?;
+    let mut fields = rest.split_whitespace().skip(2);
+    let mut next = || -> io::Result<i32> {
+        fields
+            .next()
+            .and_then(|field| field.parse().ok())
+            .ok_or_else(|| malformed("stat is too short"))
+    };
+    Ok((next()?, next()?))
+}
+
+/// Maps each local port with a listening TCP socket or a bound UDP socket to
+/// the PIDs holding it, by matching socket inodes from `/proc/net/*` against the
+/// `socket:[inode]` links in every `/proc/<pid>/fd`. Processes whose fds cannot
+/// be read (other users' without root) are left out; it is an error only if
+/// none of the socket tables can be read.
+pub(crate) fn listening_ports() -> io::Result<HashMap<u16, HashSet<i32>>> {
+    // TCP sockets count in the LISTEN state, UDP ones when unconnected.
+    const TABLES: [(&str, &str); 4] = [
+        ("/proc/net/tcp", "0A"),
+        ("/proc/net/tcp6", "0A"),
+        ("/proc/net/udp", "07"),
+        ("/proc/net/udp6", "07"),
+    ];
+    let mut ports_by_inode: HashMap<u64, u16> = HashMap::new();
+    let mut last_error = None;
+    let mut read_any = false;
+    for (table, listening) in TABLES {
+        let contents = match std::fs::read_to_string(table) {
+            Ok(contents) => contents,
+            Err(err) => {
+                last_error = Some(err);
+                continue;
+            }
+        };
+        read_any = true;
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[3] != listening {
+                continue;
+            }
+            let port = fields[1]
+                .rsplit_once(':')
+                .and_then(|(_, port)| u16::from_str_radix(port, 16).ok());
+            if let (Some(port), Ok(inode)) = (port, fields[9].parse()) {
+                ports_by_inode.insert(inode, port);
+            }
+        }
+    }
+    if !read_any {
+        return Err(last_error.unwrap_or_else(|| malformed("no socket tables")));
+    }
+    let mut owners: HashMap<u16, HashSet<i32>> = HashMap::new();
+    if ports_by_inode.is_empty() {
+        return Ok(owners);
+    }
+    for entry in std::fs::read_dir("/proc")?.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            if let Some(port) = inode.and_then(|inode| ports_by_inode.get(&inode)) {
+                owners.entry(*port).or_default().insert(pid);
+            }
+        }
+    }
+    Ok(owners)
+}
+
+fn malformed(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
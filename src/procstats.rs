@@ -0,0 +1,107 @@
+//! The /proc backend: coarse activity of a process for when strace cannot
+//! run at all, such as where ptrace is forbidden or strace is not installed.
+//!
+//! Instead of tracing, the process's counters in `/proc/<pid>/stat` and
+//! `/proc/<pid>/status` are read once a second and their rates kept as a
+//! history to graph: CPU time, page faults and context switches. Reading
+//! them needs no ptrace access, but no syscall is named; a high voluntary
+//! switch rate only suggests a process blocking in syscalls a lot.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::procfs::{self, Counters};
+
+/// How often the counters are read.
+pub(crate) const INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many samples each history keeps, enough for a wide terminal.
+const HISTORY: usize = 300;
+
+/// What is graphed, each with its unit.
+pub(crate) const METRICS: [(&str, &str); 6] = [
+    ("User CPU", "%"),
+    ("System CPU", "%"),
+    ("Voluntary context switches", "/s"),
+    ("Involuntary context switches", "/s"),
+    ("Minor page faults", "/s"),
+    ("Major page faults", "/s"),
+];
+
+/// The sampled activity of one process.
+pub(crate) struct ProcStats {
+    pid: i32,
+    last: Option<(Instant, Counters)>,
+    /// Rates per sample, oldest first, one history per entry of [`METRICS`].
+    pub(crate) history: Vec<VecDeque<u64>>,
+    /// Set once the counters can no longer be read, usually because the
+    /// process exited.
+    pub(crate) ended: bool,
+    ticks_per_second: u64,
+    samples: u64,
+}
+
+impl ProcStats {
+    pub(crate) fn new(pid: i32) -> Self {
+        Self {
+            pid,
+            last: None,
+            history: METRICS.iter().map(|_| VecDeque::new()).collect(),
+            ended: false,
+            ticks_per_second: procfs::clock_ticks(),
+            samples: 0,
+        }
+    }
+
+    /// How many times the counters have been read, to tell when there is a
+    /// new sample to draw.
+    pub(crate) fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Reads the counters if a sample is due and records the rates since the
+    /// previous one. Returns false once they cannot be read any more.
+    pub(crate) fn poll(&mut self) -> bool {
+        if self.ended {
+            return false;
+        }
+        let now = Instant::now();
+        if self
+            .last
+            .is_some_and(|(at, _)| now.duration_since(at) < INTERVAL)
+        {
+            return true;
+        }
+        let Ok(counters) = procfs::counters(self.pid) else {
+            self.ended = true;
+            return false;
+        };
+        if let Some((at, previous)) = self.last {
+            let secs = now.duration_since(at).as_secs_f64();
+            let rate = |current: u64, before: u64| {
+                (current.saturating_sub(before) as f64 / secs).round() as u64
+            };
+            let cpu = |current: u64, before: u64| {
+                let used = current.saturating_sub(before) as f64 / self.ticks_per_second as f64;
+                (used / secs * 100.0).round() as u64
+            };
+            let rates = [
+                cpu(counters.utime, previous.utime),
+                cpu(counters.stime, previous.stime),
+                rate(counters.voluntary_switches, previous.voluntary_switches),
+                rate(counters.involuntary_switches, previous.involuntary_switches),
+                rate(counters.minor_faults, previous.minor_faults),
+                rate(counters.major_faults, previous.major_faults),
+            ];
+            for (history, value) in self.history.iter_mut().zip(rates) {
+                if history.len() >= HISTORY {
+                    history.pop_front();
+                }
+                history.push_back(value);
+            }
+        }
+        self.last = Some((now, counters));
+        self.samples += 1;
+        true
+    }
+}
@@ -0,0 +1,70 @@
+//! Processes the inspector refuses to signal, so a slip of the kill key on a
+//! production box cannot take down init, sshd or the shell it runs in.
+//!
+//! The list comes from the `protected` config key: process names, PIDs, and
+//! `ancestors` for this inspector and every process above it up to init. It
+//! defaults to `1, ancestors`.
+
+use crate::procfs;
+
+/// The processes that must not be signalled.
+pub(crate) struct Protection {
+    names: Vec<String>,
+    pids: Vec<i32>,
+    /// This inspector and its ancestors, resolved at startup.
+    ancestors: Vec<i32>,
+}
+
+impl Protection {
+    /// Builds the protection from the `protected` config entries.
+    pub(crate) fn new(entries: &[String]) -> Self {
+        let mut names = Vec::new();
+        let mut pids = Vec::new();
+        let mut ancestors = Vec::new();
+        for entry in entries {
+            if entry == "ancestors" {
+                ancestors = lineage();
+            } else if let Ok(pid) = entry.parse() {
+                pids.push(pid);
+            } else {
+                names.push(entry.clone());
+            }
+        }
+        Self {
+            names,
+            pids,
+            ancestors,
+        }
+    }
+
+    /// Why `pid` is protected, or `None` if it may be signalled.
+    pub(crate) fn reason(&self, pid: i32) -> Option<String> {
+        if self.pids.contains(&pid) {
+            return Some(format!("PID {} is in the protected list", pid));
+        }
+        if self.ancestors.contains(&pid) {
+            return Some(if pid as u32 == std::process::id() {
+                format!("PID {} is this inspector", pid)
+            } else {
+                format!("PID {} is one of the processes this inspector runs in", pid)
+            });
+        }
+        let name = procfs::comm(pid).ok()?;
+        self.names
+            .contains(&name)
+            .then(|| format!("PID {} is {}, which is in the protected list", pid, name))
+    }
+}
+
+/// This process and its parents, up to and including init.
+fn lineage() -> Vec<i32> {
+    let mut pids = vec![std::process::id() as i32];
+    while let Some(&pid) = pids.last() {
+        match procfs::parent(pid) {
+            // A loop would mean /proc changed under us; stop rather than spin.
+            Ok(parent) if parent > 0 && !pids.contains(&parent) => pids.push(parent),
+            _ => break,
+        }
+    }
+    pids
+}
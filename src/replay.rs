@@ -0,0 +1,127 @@
+//! Session recordings that play back with their original timing.
+//!
+//! `--record FILE` writes every line the first traced session receives, each
+//! prefixed with the milliseconds since the first one and a space:
+//!
+//! ```text
+//! # syscall-monitor replay v1
+//! 0 strace: Process 1234 attached
+//! 3 read(3, "abc", 3) = 3
+//! 1012 write(1, "x", 1) = 1
+//! ```
+//!
+//! Opening such a file with `--input` feeds the lines to the same pipeline a
+//! live trace uses, each at its recorded offset, so the playback looks like
+//! the original session. Batch mode reads the lines without waiting.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Start of a replay file's first line, followed by the format version.
+const HEADER: &str = "# syscall-monitor replay v";
+/// The format version written and understood by this build.
+const VERSION: &str = "1";
+
+/// Writes the lines of a session to a replay file as they arrive.
+pub(crate) struct Recorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    /// When the first line arrived; offsets count from there.
+    started: Option<Instant>,
+    lines: u64,
+}
+
+impl Recorder {
+    /// Creates (or truncates) the replay file at `path`.
+    pub(crate) fn create(path: PathBuf) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(&path)?);
+        writeln!(out, "{}{}", HEADER, VERSION)?;
+        Ok(Self {
+            path,
+            out,
+            started: None,
+            lines: 0,
+        })
+    }
+
+    /// Appends `line`, stamped with its arrival time.
+    pub(crate) fn record(&mut self, line: &str) -> io::Result<()> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        writeln!(self.out, "{} {}", started.elapsed().as_millis(), line)?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// Writes out what has been recorded so far.
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// How many lines have been recorded.
+    pub(crate) fn lines(&self) -> u64 {
+        self.lines
+    }
+}
+
+/// Reads the lines of a log, which may be a replay file, as `(offset, line)`
+/// pairs. Plain strace logs have no offsets. A replay file of an unknown
+/// version yields a single `strace: ` message saying so, which the TUI shows
+/// as an error like any other strace diagnostic.
+pub(crate) fn entries(
+    lines: impl Iterator<Item = String>,
+) -> impl Iterator<Item = (Option<Duration>, String)> {
+    let mut lines = lines.peekable();
+    let version = lines
+        .peek()
+        .and_then(|first| first.strip_prefix(HEADER))
+        .map(|version| version.trim().to_string());
+    if version.is_some() {
+        lines.next();
+    }
+    let mut unsupported = version.as_deref().filter(|v| *v != VERSION).map(|v| {
+        format!(
+            "strace: this is a version {} replay file; only version {} can be played",
+            v, VERSION
+        )
+    });
+    let replay = version.is_some();
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if let Some(message) = unsupported.take() {
+            done = true;
+            return Some((None, message));
+        }
+        if done {
+            return None;
+        }
+        loop {
+            let line = lines.next()?;
+            if !replay {
+                return Some((None, line));
+            }
+            // Entries that do not parse are skipped rather than ending playback.
+            let Some((offset, text)) = line.split_once(' ') else {
+                continue;
+            };
+            let Ok(millis) = offset.parse() else {
+                continue;
+            };
+            return Some((Some(Duration::from_millis(millis)), text.to_string()));
+        }
+    })
+}
+
+/// Whether the file at `path` starts like a replay file.
+pub(crate) fn is_replay(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut first = String::new();
+    BufReader::new(file).read_line(&mut first).is_ok() && first.starts_with(HEADER)
+}
@@ -0,0 +1,142 @@
+//! The resources view: captured calls grouped by the file or socket they
+//! touch, answering "what did the process do to /var/log/app.log?" across
+//! every syscall rather than one at a time.
+//!
+//! File descriptors are mapped to what they refer to as the trace goes: a
+//! call returning a new fd names it after the path it opened (or the peer it
+//! accepted), `connect` and `bind` rename a socket after its address, and
+//! `close` forgets the fd. In a live trace, a descriptor opened before the
+//! trace started is looked up in `/proc/<pid>/fd` the first time it is used;
+//! a log's PIDs may name other processes by now, so there it stays unknown.
+//! Calls that name
+//! a path instead of an fd, such as `stat` or `unlink`, count towards that
+//! path.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::args;
+use crate::parser;
+use crate::procfs;
+use crate::syscalls;
+
+/// What was done to one resource.
+#[derive(Default)]
+pub(crate) struct Activity {
+    /// Calls by syscall name.
+    pub(crate) calls: BTreeMap<String, u64>,
+    pub(crate) failures: u64,
+}
+
+impl Activity {
+    pub(crate) fn total(&self) -> u64 {
+        self.calls.values().sum()
+    }
+}
+
+/// Per-resource activity of a trace.
+#[derive(Default)]
+pub(crate) struct Resources {
+    /// What each `(pid, fd)` refers to at this point of the trace.
+    open: HashMap<(i32, i32), String>,
+    activity: HashMap<String, Activity>,
+}
+
+impl Resources {
+    /// Attributes the completed call `name` on `line`, made by `pid`, to the
+    /// resource it touched, if any. Unknown descriptors are only looked up
+    /// in /proc when the trace is `live`.
+    pub(crate) fn record(&mut self, pid: i32, name: &str, line: &str, live: bool) {
+        let hints = args::decode(line);
+        let hint = |key: &str| {
+            hints
+                .iter()
+                .find(|(label, _)| *label == key)
+                .map(|(_, value)| value.clone())
+        };
+        let lookup = |fd: i32| {
+            if live {
+                procfs::fd_target(pid, fd).ok()
+            } else {
+                None
+            }
+        };
+        let failed = parser::errno(line).is_some();
+        let resource = if syscalls::returns_fd(name) && !failed {
+            parser::returned_fd(line).map(|fd| {
+                let resource = hint("path")
+                    .or_else(|| hint("peer"))
+                    .or_else(|| lookup(fd))
+                    .unwrap_or_else(|| unresolved(pid, fd));
+                self.open.insert((pid, fd), resource.clone());
+                resource
+            })
+        } else if syscalls::takes_fd(name) {
+            parser::fd_argument(line).map(|fd| {
+                let known = self.open.get(&(pid, fd)).cloned();
+                let resource = known.unwrap_or_else(|| {
+                    let resource = lookup(fd).unwrap_or_else(|| unresolved(pid, fd));
+                    self.open.insert((pid, fd), resource.clone());
+                    resource
+                });
+                match name {
+                    "close" => {
+                        self.open.remove(&(pid, fd));
+                        resource
+                    }
+                    "connect" | "bind" if !failed => match hint("address") {
+                        Some(address) => self.rename(pid, fd, &resource, address),
+                        None => resource,
+                    },
+                    _ => resource,
+                }
+            })
+        } else {
+            None
+        };
+        // Failed opens and `*at` calls relative to the current directory
+        // have only their path to go by.
+        if let Some(resource) = resource.or_else(|| hint("path")) {
+            let activity = self.activity.entry(resource).or_default();
+            *activity.calls.entry(name.to_string()).or_insert(0) += 1;
+            if failed {
+                activity.failures += 1;
+            }
+        }
+    }
+
+    /// Names the socket `fd` after its address from now on, carrying over
+    /// what was done to it under its anonymous `socket:[inode]` name.
+    fn rename(&mut self, pid: i32, fd: i32, old: &str, new: String) -> String {
+        self.open.insert((pid, fd), new.clone());
+        if old.starts_with("socket:") {
+            if let Some(earlier) = self.activity.remove(old) {
+                let activity = self.activity.entry(new.clone()).or_default();
+                for (name, count) in earlier.calls {
+                    *activity.calls.entry(name).or_insert(0) += count;
+                }
+                activity.failures += earlier.failures;
+            }
+        }
+        new
+    }
+
+    /// Every resource with its activity, most used first.
+    pub(crate) fn sorted(&self) -> Vec<(&str, &Activity)> {
+        let mut resources: Vec<(&str, &Activity)> = self
+            .activity
+            .iter()
+            .map(|(name, activity)| (name.as_str(), activity))
+            .collect();
+        resources.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then_with(|| a.0.cmp(b.0)));
+        resources
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.activity.is_empty()
+    }
+}
+
+/// The name of a descriptor whose target is unknown, kept apart per process.
+fn unresolved(pid: i32, fd: i32) -> String {
+    format!("fd {} of PID {}", fd, pid)
+}
@@ -0,0 +1,126 @@
+//! Screen snapshots: the frame on screen written out as text, for bug
+//! reports and documentation where a terminal screenshot is awkward (over
+//! SSH, or to paste into an issue).
+//!
+//! Two files are written side by side: plain text, and the same text with
+//! ANSI colour and style codes for viewing with `cat` or `less -R`.
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+/// SGR codes of the modifiers, in the order they are emitted.
+const MODIFIER_CODES: [(Modifier, u8); 9] = [
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::SLOW_BLINK, 5),
+    (Modifier::RAPID_BLINK, 6),
+    (Modifier::REVERSED, 7),
+    (Modifier::HIDDEN, 8),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+/// The screen as plain text, one line per row without trailing spaces.
+pub(crate) fn plain(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let line: String = row.iter().map(|cell| cell.symbol.as_str()).collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// The screen with its colours and styles as ANSI escape sequences.
+pub(crate) fn ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let mut style = None;
+        for cell in row {
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(current);
+            }
+            out.push_str(&cell.symbol);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// The cells of each row that start a character: a wide character fills
+/// two cells, and the one it covers is left out.
+fn rows(buffer: &Buffer) -> Vec<Vec<&ratatui::buffer::Cell>> {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width.max(1))
+        .map(|row| {
+            let mut cells = Vec::with_capacity(row.len());
+            let mut skip = 0;
+            for cell in row {
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                skip = cell.symbol.width().saturating_sub(1);
+                cells.push(cell);
+            }
+            cells
+        })
+        .collect()
+}
+
+/// The escape sequence switching to a cell's style, starting from a reset
+/// so no attribute of the previous cell carries over.
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in MODIFIER_CODES {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = color_code(fg, false) {
+        codes.push(code);
+    }
+    if let Some(code) = color_code(bg, true) {
+        codes.push(code);
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// The SGR parameters of a foreground or background colour; `None` for the
+/// terminal's default.
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = |code: u8| Some((if background { code + 10 } else { code }).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => base(30),
+        Color::Red => base(31),
+        Color::Green => base(32),
+        Color::Yellow => base(33),
+        Color::Blue => base(34),
+        Color::Magenta => base(35),
+        Color::Cyan => base(36),
+        Color::Gray => base(37),
+        Color::DarkGray => base(90),
+        Color::LightRed => base(91),
+        Color::LightGreen => base(92),
+        Color::LightYellow => base(93),
+        Color::LightBlue => base(94),
+        Color::LightMagenta => base(95),
+        Color::LightCyan => base(96),
+        Color::White => base(97),
+        Color::Indexed(index) => Some(format!("{};5;{}", if background { 48 } else { 38 }, index)),
+        Color::Rgb(r, g, b) => Some(format!(
+            "{};2;{};{};{}",
+            if background { 48 } else { 38 },
+            r,
+            g,
+            b
+        )),
+    }
+}
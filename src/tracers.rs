@@ -0,0 +1,185 @@
+//! Registry of the strace processes started by running instances, so that
+//! tracers left behind by a run that crashed can be found and stopped later.
+//!
+//! Every instance records `<instance pid> <strace pid>` lines in a file of
+//! the user's, in `$XDG_RUNTIME_DIR` or else the config directory, and
+//! removes its lines as each strace exits. Lines whose instance is gone but
+//! whose strace still runs are orphans.
+//!
+//! The inspector often runs as root, so the file is only trusted if it is a
+//! regular file owned by the user and private to them, and it is locked for
+//! every read and change so instances cannot lose each other's lines.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+
+use nix::errno::Errno;
+use nix::fcntl::{flock, FlockArg};
+use nix::sys::signal::{self, Signal};
+use nix::unistd::{getuid, Pid};
+
+use crate::config::Config;
+use crate::procfs;
+
+fn path() -> io::Result<PathBuf> {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir).join("syscall-monitor.tracers")),
+        _ => Config::dir().map(|dir| dir.join("tracers")).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "neither XDG_RUNTIME_DIR nor HOME is set",
+            )
+        }),
+    }
+}
+
+/// Opens and locks the registry, exclusively to change it or shared to read
+/// it. To change it, a missing registry is created only readable by the user.
+fn open(create: bool) -> io::Result<File> {
+    let path = path()?;
+    let mut options = OpenOptions::new();
+    options
+        .read(true)
+        .write(true)
+        .custom_flags(nix::libc::O_NOFOLLOW);
+    let opened = if create {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        match options.clone().create_new(true).mode(0o600).open(&path) {
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => options.open(&path),
+            opened => opened,
+        }
+    } else {
+        options.open(&path)
+    };
+    let file = opened?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() || metadata.uid() != getuid().as_raw() || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not a private file of this user; not trusting it",
+                path.display()
+            ),
+        ));
+    }
+    let lock = if create {
+        FlockArg::LockExclusive
+    } else {
+        FlockArg::LockShared
+    };
+    flock(file.as_raw_fd(), lock).map_err(io::Error::from)?;
+    Ok(file)
+}
+
+/// Parses the registry's `(instance, strace)` pairs.
+fn read(file: &mut File) -> io::Result<Vec<(i32, i32)>> {
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| {
+            let (owner, strace) = line.split_once(' ')?;
+            Some((owner.trim().parse().ok()?, strace.trim().parse().ok()?))
+        })
+        .collect())
+}
+
+/// Reads the registry; a missing or untrusted one is empty.
+fn entries() -> Vec<(i32, i32)> {
+    open(false)
+        .and_then(|mut file| read(&mut file))
+        .unwrap_or_default()
+}
+
+/// Rewrites the registry with `change` applied to its entries, holding the
+/// lock from the read to the write.
+fn update(change: impl FnOnce(&mut Vec<(i32, i32)>)) -> io::Result<()> {
+    let mut file = open(true)?;
+    let mut entries = read(&mut file)?;
+    change(&mut entries);
+    let contents: String = entries
+        .iter()
+        .map(|(owner, strace)| format!("{} {}\n", owner, strace))
+        .collect();
+    file.set_len(0)?;
+    file.rewind()?;
+    file.write_all(contents.as_bytes())
+}
+
+/// Whether a process exists, even if it belongs to another user.
+fn alive(pid: i32) -> bool {
+    matches!(
+        signal::kill(Pid::from_raw(pid), None),
+        Ok(()) | Err(Errno::EPERM)
+    )
+}
+
+/// Records an strace started by this instance.
+pub(crate) fn register(strace: u32) -> io::Result<()> {
+    update(|entries| entries.push((std::process::id() as i32, strace as i32)))
+}
+
+/// Removes an strace of this instance once it has exited.
+pub(crate) fn unregister(strace: u32) -> io::Result<()> {
+    let own = (std::process::id() as i32, strace as i32);
+    update(|entries| entries.retain(|entry| *entry != own))
+}
+
+/// Counts the recorded tracers, of any instance, that are still running.
+pub(crate) fn running() -> usize {
+    entries()
+        .into_iter()
+        .filter(|(_, strace)| is_strace(*strace))
+        .count()
+}
+
+/// Whether `pid` still names a live strace process, rather than having exited
+/// (a zombie keeps its name until reaped) or been reused by an unrelated program.
+fn is_strace(pid: i32) -> bool {
+    procfs::comm(pid).is_ok_and(|comm| comm == "strace") && !procfs::is_zombie(pid).unwrap_or(true)
+}
+
+/// Refuses to start another tracer once `max` of them are running; 0 means
+/// there is no limit. The error has kind `QuotaExceeded`.
+pub(crate) fn check_limit(max: usize) -> io::Result<()> {
+    let running = running();
+    if max > 0 && running >= max {
+        return Err(io::Error::new(
+            io::ErrorKind::QuotaExceeded,
+            format!(
+                "the max_tracers limit of {} strace processes is reached ({} running); stop one first",
+                max, running
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the PIDs of tracers whose instance exited without stopping them.
+///
+/// A PID only counts while it still names an strace process, so one reused by
+/// an unrelated program is never reported.
+pub(crate) fn orphans() -> Vec<i32> {
+    entries()
+        .into_iter()
+        .filter(|(owner, strace)| !alive(*owner) && is_strace(*strace))
+        .map(|(_, strace)| strace)
+        .collect()
+}
+
+/// Stops the given orphaned tracers with SIGTERM, on which strace detaches
+/// from its tracees, and drops every entry of instances that are gone.
+/// Returns how many tracers were signalled.
+pub(crate) fn stop_orphans(pids: &[i32]) -> io::Result<usize> {
+    let stopped = pids
+        .iter()
+        .filter(|pid| signal::kill(Pid::from_raw(**pid), Signal::SIGTERM).is_ok())
+        .count();
+    update(|entries| entries.retain(|(owner, _)| alive(*owner)))?;
+    Ok(stopped)
+}
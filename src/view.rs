@@ -0,0 +1,154 @@
+//! Display choices that are remembered between runs: the syscall list's
+//! ordering and coloring, the process list's columns and the dashboard split.
+//!
+//! They live apart from the UI that draws them so [`crate::config`] can read
+//! and write them without depending on it.
+
+/// Bounds of the dashboard's list share, in percent of the height.
+pub const DASHBOARD_SPLIT_MIN: u16 = 20;
+pub const DASHBOARD_SPLIT_MAX: u16 = 80;
+
+/// A column of the process list.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ProcessColumn {
+    Pid,
+    Threads,
+    User,
+    Memory,
+    Name,
+    Cgroup,
+    Cwd,
+    Capabilities,
+    Command,
+}
+
+impl ProcessColumn {
+    pub const ALL: [ProcessColumn; 9] = [
+        ProcessColumn::Pid,
+        ProcessColumn::Threads,
+        ProcessColumn::User,
+        ProcessColumn::Memory,
+        ProcessColumn::Name,
+        ProcessColumn::Cgroup,
+        ProcessColumn::Cwd,
+        ProcessColumn::Capabilities,
+        ProcessColumn::Command,
+    ];
+
+    /// The default column layout: everything in [`ProcessColumn::ALL`] order,
+    /// with user, memory, cgroup, working directory and capabilities hidden.
+    pub fn defaults() -> Vec<(ProcessColumn, bool)> {
+        Self::ALL
+            .iter()
+            .map(|&c| {
+                let hidden = matches!(
+                    c,
+                    ProcessColumn::User
+                        | ProcessColumn::Memory
+                        | ProcessColumn::Cgroup
+                        | ProcessColumn::Cwd
+                        | ProcessColumn::Capabilities
+                );
+                (c, !hidden)
+            })
+            .collect()
+    }
+
+    /// Identifier used when saving the layout in the state file.
+    pub fn key(self) -> &'static str {
+        match self {
+            ProcessColumn::Pid => "pid",
+            ProcessColumn::Threads => "threads",
+            ProcessColumn::User => "user",
+            ProcessColumn::Memory => "memory",
+            ProcessColumn::Name => "name",
+            ProcessColumn::Cgroup => "cgroup",
+            ProcessColumn::Cwd => "cwd",
+            ProcessColumn::Capabilities => "caps",
+            ProcessColumn::Command => "cmd",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|c| c.key() == key)
+    }
+}
+
+/// What the colors of syscall names stand for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// The syscall's category, as in the legend.
+    Category,
+    /// How often the syscall failed, see [`crate::error_rate_color`].
+    ErrorRate,
+}
+
+impl ColorMode {
+    pub fn next(self) -> Self {
+        match self {
+            ColorMode::Category => ColorMode::ErrorRate,
+            ColorMode::ErrorRate => ColorMode::Category,
+        }
+    }
+
+    /// Identifier used when saving the mode in the state file.
+    pub fn key(self) -> &'static str {
+        match self {
+            ColorMode::Category => "category",
+            ColorMode::ErrorRate => "errors",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "category" => Some(ColorMode::Category),
+            "errors" => Some(ColorMode::ErrorRate),
+            _ => None,
+        }
+    }
+}
+
+/// Ordering applied to the syscall list.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SyscallOrder {
+    Alphabetical,
+    ByCount,
+    FirstSeen,
+}
+
+impl SyscallOrder {
+    /// Cycles to the next ordering.
+    pub fn next(self) -> Self {
+        match self {
+            SyscallOrder::Alphabetical => SyscallOrder::ByCount,
+            SyscallOrder::ByCount => SyscallOrder::FirstSeen,
+            SyscallOrder::FirstSeen => SyscallOrder::Alphabetical,
+        }
+    }
+
+    /// Identifier used when saving the ordering in the state file.
+    pub fn key(self) -> &'static str {
+        match self {
+            SyscallOrder::Alphabetical => "alpha",
+            SyscallOrder::ByCount => "count",
+            SyscallOrder::FirstSeen => "first",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "alpha" => Some(SyscallOrder::Alphabetical),
+            "count" => Some(SyscallOrder::ByCount),
+            "first" => Some(SyscallOrder::FirstSeen),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SyscallOrder::Alphabetical => "A-Z",
+            SyscallOrder::ByCount => "by count",
+            SyscallOrder::FirstSeen => "first seen",
+        }
+    }
+}